@@ -0,0 +1,69 @@
+//! `account_deletion.rs` - Defines the `AccountDeletion` entity using `SeaORM`.
+//! This module represents a pending request to delete a user account.
+//!
+//! # Entity Overview
+//! - Represents a deletion waiting out its grace period.
+//! - Records what happens to the user's articles once the account is erased.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What happens to the articles of an erased account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleErasure {
+    /// The articles are deleted along with the account.
+    #[sea_orm(string_value = "delete")]
+    Delete,
+    /// The articles stay published under an anonymous author.
+    #[sea_orm(string_value = "anonymize")]
+    Anonymize,
+}
+
+/// Represents a pending account deletion in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "account_deletion")]
+pub struct Model {
+    /// Unique identifier for the deletion request (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user to delete (Foreign Key, Unique constraint).
+    #[sea_orm(unique)]
+    pub user_id: i32,
+
+    /// What happens to the user's articles.
+    pub articles: ArticleErasure,
+
+    /// Timestamp of when the deletion was requested.
+    pub requested_at: DateTime,
+
+    /// Timestamp after which the account is erased.
+    pub scheduled_for: DateTime,
+}
+
+/// Defines relationships between `AccountDeletion` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each deletion request belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `AccountDeletion` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
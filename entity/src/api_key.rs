@@ -0,0 +1,67 @@
+//! `api_key.rs` - Defines the `ApiKey` entity using `SeaORM`.
+//! This module represents named API keys users create for automation.
+//!
+//! # Entity Overview
+//! - Represents an API key, stored as a hash with a short display prefix.
+//! - Contains the space separated scopes granted to the key.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents an API key in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "api_key")]
+pub struct Model {
+    /// Unique identifier for the API key (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user who owns the key (Foreign Key).
+    pub user_id: i32,
+
+    /// Name given to the key by its owner.
+    pub name: String,
+
+    /// First characters of the key, shown to help users recognize it.
+    pub prefix: String,
+
+    /// SHA-256 hash of the key (Unique constraint).
+    #[sea_orm(unique)]
+    pub key_hash: String,
+
+    /// Space separated scopes granted to the key, e.g. `article:write subscribers:read`.
+    pub scopes: String,
+
+    /// Timestamp of when the key was created.
+    pub created_at: DateTime,
+
+    /// Timestamp of when the key was last used, if it was.
+    pub last_used_at: Option<DateTime>,
+
+    /// Timestamp of when the key was revoked, if it was.
+    pub revoked_at: Option<DateTime>,
+}
+
+/// Defines relationships between `ApiKey` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each API key belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `ApiKey` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
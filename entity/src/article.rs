@@ -4,10 +4,30 @@
 //!
 //! # Entity Overview
 //! - Represents an article in the database.
-//! - Includes fields such as `id`, `title`, `content`, `uuid`, `user_id`, etc.
+//! - Includes fields such as `id`, `title`, `content` with its rendered versions, `uuid`, `user_id`, `status`, `publish_at`, `updated_at` and `deleted_at`.
 //! - Establishes a relationship with the `User` entity.
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Publication state of an article.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleStatus {
+    /// Only visible to its author.
+    #[sea_orm(string_value = "draft")]
+    Draft,
+    /// Published automatically at `publish_at`.
+    #[sea_orm(string_value = "scheduled")]
+    Scheduled,
+    /// Public and listed.
+    #[sea_orm(string_value = "published")]
+    Published,
+    /// Readable by link but no longer listed.
+    #[sea_orm(string_value = "archived")]
+    Archived,
+}
 
 /// Represents an article in the database.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -20,8 +40,16 @@ pub struct Model {
     /// Title of the article.
     pub title: String,
     
-    /// The main content/body of the article.
+    /// The main content/body of the article, in Markdown.
     pub content: String,
+
+    /// The content rendered to sanitized HTML; `None` for articles stored before rendering existed.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub content_html: Option<String>,
+
+    /// The content rendered to plain text; `None` for articles stored before rendering existed.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub content_text: Option<String>,
     
     /// Universally Unique Identifier (UUID) for the article (Unique constraint).
     #[sea_orm(unique)]
@@ -35,6 +63,27 @@ pub struct Model {
     
     /// Optional field for storing an image URL or path associated with the article.
     pub image: Option<String>,
+
+    /// Publication state of the article.
+    pub status: ArticleStatus,
+
+    /// When the article was published, or is going to be if it is scheduled.
+    pub publish_at: Option<DateTime>,
+
+    /// Timestamp of the last edit, or of the creation if it was never edited.
+    pub updated_at: DateTime,
+
+    /// Timestamp of when the author deleted the article; deleted articles are no longer listed.
+    pub deleted_at: Option<DateTime>,
+}
+
+/// Implements helpers for reading an `Article`.
+impl Model {
+    /// Returns when the article was published.
+    /// Articles published before `publish_at` was recorded fall back to their creation time.
+    pub fn published_at(&self) -> DateTime {
+        self.publish_at.unwrap_or(self.created_at)
+    }
 }
 
 /// Defines relationships between `Article` and other entities.
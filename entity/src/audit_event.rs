@@ -0,0 +1,91 @@
+//! `audit_event.rs` - Defines the `AuditEvent` entity using `SeaORM`.
+//! This module represents an entry of the security audit log.
+//!
+//! # Entity Overview
+//! - Represents an authentication or account event, with the acting user and client IP.
+//! - The table is append-only: events are never updated or deleted, except that erasing
+//!   an account clears the IP addresses and details of its events.
+//! - Deliberately has no foreign keys, so events outlive the rows they mention.
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Kind of an audited event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A login completed and a session started.
+    #[sea_orm(string_value = "login_succeeded")]
+    LoginSucceeded,
+    /// A login was refused because of a wrong password or code.
+    #[sea_orm(string_value = "login_failed")]
+    LoginFailed,
+    /// The password was changed with the current one.
+    #[sea_orm(string_value = "password_changed")]
+    PasswordChanged,
+    /// The password was reset with an emailed link.
+    #[sea_orm(string_value = "password_reset")]
+    PasswordReset,
+    /// The email address was changed.
+    #[sea_orm(string_value = "email_changed")]
+    EmailChanged,
+    /// Two-factor authentication was enabled.
+    #[sea_orm(string_value = "two_factor_enabled")]
+    TwoFactorEnabled,
+    /// An API key was created.
+    #[sea_orm(string_value = "api_key_created")]
+    ApiKeyCreated,
+    /// An API key was revoked.
+    #[sea_orm(string_value = "api_key_revoked")]
+    ApiKeyRevoked,
+    /// A subscription was removed by its subscriber.
+    #[sea_orm(string_value = "unsubscribed")]
+    Unsubscribed,
+    /// An admin changed the role of a user.
+    #[sea_orm(string_value = "role_changed")]
+    RoleChanged,
+    /// The deletion of the account was requested.
+    #[sea_orm(string_value = "account_deletion_requested")]
+    AccountDeletionRequested,
+    /// The account was erased.
+    #[sea_orm(string_value = "account_erased")]
+    AccountErased,
+    /// An author deleted one of their articles.
+    #[sea_orm(string_value = "article_deleted")]
+    ArticleDeleted,
+}
+
+/// Represents an audit event in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "audit_event")]
+pub struct Model {
+    /// Unique identifier for the event (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the account the event belongs to, if it is known.
+    pub user_id: Option<i32>,
+
+    /// ID of the user who caused the event, if it is known; differs from `user_id` for admin actions.
+    pub actor_id: Option<i32>,
+
+    /// Kind of the event.
+    pub action: AuditAction,
+
+    /// Client IP address the event came from.
+    pub ip_address: String,
+
+    /// Free-form details, such as the affected API key or the attempted email address.
+    pub details: Option<String>,
+
+    /// Timestamp of the event.
+    pub created_at: DateTime,
+}
+
+/// `AuditEvent` has no relations to other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
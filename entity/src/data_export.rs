@@ -0,0 +1,76 @@
+//! `data_export.rs` - Defines the `DataExport` entity using `SeaORM`.
+//! This module represents an archive of the personal data held about a user.
+//!
+//! # Entity Overview
+//! - Represents one export request and, once built, its zip archive.
+//! - Only the hash of the emailed download token is stored.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Progress of a data export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum DataExportStatus {
+    /// The archive is being built.
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// The archive is built and can be downloaded.
+    #[sea_orm(string_value = "ready")]
+    Ready,
+    /// Building the archive failed.
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+/// Represents a data export in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "data_export")]
+pub struct Model {
+    /// Unique identifier for the export (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user whose data is exported (Foreign Key).
+    pub user_id: i32,
+
+    /// Progress of the export.
+    pub status: DataExportStatus,
+
+    /// The zip archive, once built.
+    pub archive: Option<Vec<u8>>,
+
+    /// SHA-256 hash of the download token, once the archive is built (Unique constraint).
+    #[sea_orm(unique)]
+    pub token_hash: Option<String>,
+
+    /// Timestamp of when the export was requested.
+    pub created_at: DateTime,
+
+    /// Timestamp after which the archive can no longer be downloaded, once built.
+    pub expires_at: Option<DateTime>,
+}
+
+/// Defines relationships between `DataExport` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each export belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `DataExport` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
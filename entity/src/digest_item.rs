@@ -0,0 +1,71 @@
+//! `digest_item.rs` - Defines the `DigestItem` entity using `SeaORM`.
+//! This module represents an article waiting to be sent in a subscriber's digest.
+//!
+//! # Entity Overview
+//! - Represents one article queued for one subscriber with a daily or weekly delivery mode.
+//! - Items due at the same time are sent together in one email and then deleted.
+//! - Establishes relationships with the `User` and `Article` entities.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a queued digest item in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "digest_item")]
+pub struct Model {
+    /// Unique identifier for the item (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the subscriber receiving the digest (Foreign Key).
+    pub subscriber_user_id: i32,
+
+    /// ID of the queued article (Foreign Key).
+    pub article_id: i32,
+
+    /// Timestamp from which the digest containing the item is sent.
+    pub deliver_after: DateTime,
+
+    /// Timestamp of when the item was queued.
+    pub created_at: DateTime,
+}
+
+/// Defines relationships between `DigestItem` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each item belongs to a single subscriber.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::SubscriberUserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+
+    /// Relationship: Each item refers to a single article.
+    #[sea_orm(
+        belongs_to = "super::article::Entity",
+        from = "Column::ArticleId",
+        to = "super::article::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Article,
+}
+
+/// Implements relationship behavior for `DigestItem` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Implements relationship behavior for `DigestItem` and `Article`.
+impl Related<super::article::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Article.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
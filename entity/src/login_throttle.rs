@@ -0,0 +1,52 @@
+//! `login_throttle.rs` - Defines the `LoginThrottle` entity using `SeaORM`.
+//! This module represents the failed login counter of an account or a client IP.
+//!
+//! # Entity Overview
+//! - Counts recent failed logins for a throttling key.
+//! - Records until when further login attempts are refused.
+//! - Accounts are keyed by email address, so unknown emails are throttled the same way.
+
+use sea_orm::entity::prelude::*;
+
+/// What a throttling key identifies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum ThrottleKind {
+    /// An email address entered at login.
+    #[sea_orm(string_value = "account")]
+    Account,
+    /// A client IP address.
+    #[sea_orm(string_value = "ip")]
+    Ip,
+}
+
+/// Represents a failed login counter in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "login_throttle")]
+pub struct Model {
+    /// Unique identifier for the counter (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// What the key identifies.
+    pub kind: ThrottleKind,
+
+    /// Normalized email address or IP address.
+    pub key: String,
+
+    /// Number of failed logins since the counter was last reset.
+    pub failed_count: i32,
+
+    /// Timestamp of the most recent failed login.
+    pub last_failed_at: DateTime,
+
+    /// Timestamp until which login attempts are refused, if any.
+    pub locked_until: Option<DateTime>,
+}
+
+/// `LoginThrottle` has no relations to other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
@@ -3,11 +3,35 @@
 //!
 //! # Modules
 //! - `prelude` - Common traits and types for entity interactions.
+//! - `account_deletion` - Defines the `AccountDeletion` entity.
+//! - `api_key` - Defines the `ApiKey` entity.
 //! - `article` - Defines the `Article` entity.
+//! - `audit_event` - Defines the `AuditEvent` entity.
+//! - `data_export` - Defines the `DataExport` entity.
+//! - `digest_item` - Defines the `DigestItem` entity.
+//! - `login_throttle` - Defines the `LoginThrottle` entity.
+//! - `oauth_identity` - Defines the `OauthIdentity` entity.
+//! - `one_time_token` - Defines the `OneTimeToken` entity.
+//! - `recovery_code` - Defines the `RecoveryCode` entity.
+//! - `refresh_token` - Defines the `RefreshToken` entity.
+//! - `revoked_token` - Defines the `RevokedToken` entity.
+//! - `session` - Defines the `Session` entity.
 //! - `subscription` - Defines the `Subscription` entity.
 //! - `user` - Defines the `User` entity.
 
 pub mod prelude;
+pub mod account_deletion;
+pub mod api_key;
 pub mod article;
+pub mod audit_event;
+pub mod data_export;
+pub mod digest_item;
+pub mod login_throttle;
+pub mod oauth_identity;
+pub mod one_time_token;
+pub mod recovery_code;
+pub mod refresh_token;
+pub mod revoked_token;
+pub mod session;
 pub mod subscription;
 pub mod user;
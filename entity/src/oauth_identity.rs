@@ -0,0 +1,54 @@
+//! `oauth_identity.rs` - Defines the `OauthIdentity` entity using `SeaORM`.
+//! This module represents an account at an external OpenID Connect provider.
+//!
+//! # Entity Overview
+//! - Links a provider's subject identifier to a user.
+//! - Each provider and subject pair is unique.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a linked provider account in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "oauth_identity")]
+pub struct Model {
+    /// Unique identifier for the identity (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the linked user (Foreign Key).
+    pub user_id: i32,
+
+    /// Name of the provider, as used in `/auth/oauth/{provider}`.
+    pub provider: String,
+
+    /// Stable identifier of the account at the provider (`sub` claim).
+    pub subject: String,
+
+    /// Timestamp of when the identity was linked.
+    pub created_at: DateTime,
+}
+
+/// Defines relationships between `OauthIdentity` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each identity belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `OauthIdentity` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
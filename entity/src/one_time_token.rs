@@ -0,0 +1,79 @@
+//! `one_time_token.rs` - Defines the `OneTimeToken` entity using `SeaORM`.
+//! This module represents single-use tokens sent to users by email.
+//!
+//! # Entity Overview
+//! - Represents a hashed, expiring, single-use token.
+//! - The `purpose` column tells which flow the token belongs to.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Flow a one-time token was issued for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+pub enum TokenPurpose {
+    /// Confirms ownership of the email address given at registration.
+    #[sea_orm(string_value = "email_verification")]
+    EmailVerification,
+    /// Allows setting a new password without knowing the current one.
+    #[sea_orm(string_value = "password_reset")]
+    PasswordReset,
+    /// Logs the user in without a password.
+    #[sea_orm(string_value = "magic_link")]
+    MagicLink,
+    /// Confirms ownership of the new address in `user.pending_email`.
+    #[sea_orm(string_value = "email_change")]
+    EmailChange,
+}
+
+/// Represents a one-time token in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "one_time_token")]
+pub struct Model {
+    /// Unique identifier for the token (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user the token was issued to (Foreign Key).
+    pub user_id: i32,
+
+    /// Flow the token belongs to.
+    pub purpose: TokenPurpose,
+
+    /// SHA-256 hash of the token (Unique constraint).
+    #[sea_orm(unique)]
+    pub token_hash: String,
+
+    /// Timestamp after which the token can no longer be used.
+    pub expires_at: DateTime,
+
+    /// Timestamp of when the token was consumed, if it was.
+    pub used_at: Option<DateTime>,
+
+    /// Timestamp of when the token was created.
+    pub created_at: DateTime,
+}
+
+/// Defines relationships between `OneTimeToken` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each token belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `OneTimeToken` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
@@ -2,10 +2,34 @@
 //! This module provides easy access to all entity structs, simplifying imports.
 //!
 //! # Re-exported Entities
+//! - `AccountDeletion` - Represents the `AccountDeletion` entity.
+//! - `ApiKey` - Represents the `ApiKey` entity.
 //! - `Article` - Represents the `Article` entity.
+//! - `AuditEvent` - Represents the `AuditEvent` entity.
+//! - `DataExport` - Represents the `DataExport` entity.
+//! - `DigestItem` - Represents the `DigestItem` entity.
+//! - `LoginThrottle` - Represents the `LoginThrottle` entity.
+//! - `OauthIdentity` - Represents the `OauthIdentity` entity.
+//! - `OneTimeToken` - Represents the `OneTimeToken` entity.
+//! - `RecoveryCode` - Represents the `RecoveryCode` entity.
+//! - `RefreshToken` - Represents the `RefreshToken` entity.
+//! - `RevokedToken` - Represents the `RevokedToken` entity.
+//! - `Session` - Represents the `Session` entity.
 //! - `Subscription` - Represents the `Subscription` entity.
 //! - `User` - Represents the `User` entity.
 
+pub use super::account_deletion::Entity as AccountDeletion;
+pub use super::api_key::Entity as ApiKey;
 pub use super::article::Entity as Article;
+pub use super::audit_event::Entity as AuditEvent;
+pub use super::data_export::Entity as DataExport;
+pub use super::digest_item::Entity as DigestItem;
+pub use super::login_throttle::Entity as LoginThrottle;
+pub use super::oauth_identity::Entity as OauthIdentity;
+pub use super::one_time_token::Entity as OneTimeToken;
+pub use super::recovery_code::Entity as RecoveryCode;
+pub use super::refresh_token::Entity as RefreshToken;
+pub use super::revoked_token::Entity as RevokedToken;
+pub use super::session::Entity as Session;
 pub use super::subscription::Entity as Subscription;
 pub use super::user::Entity as User;
@@ -0,0 +1,53 @@
+//! `recovery_code.rs` - Defines the `RecoveryCode` entity using `SeaORM`.
+//! This module represents two-factor authentication recovery codes.
+//!
+//! # Entity Overview
+//! - Represents a hashed, single-use recovery code.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a recovery code in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "recovery_code")]
+pub struct Model {
+    /// Unique identifier for the recovery code (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user the code belongs to (Foreign Key).
+    pub user_id: i32,
+
+    /// SHA-256 hash of the recovery code.
+    pub code_hash: String,
+
+    /// Timestamp of when the code was used, if it was.
+    pub used_at: Option<DateTime>,
+
+    /// Timestamp of when the code was created.
+    pub created_at: DateTime,
+}
+
+/// Defines relationships between `RecoveryCode` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each recovery code belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `RecoveryCode` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
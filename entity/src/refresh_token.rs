@@ -0,0 +1,61 @@
+//! `refresh_token.rs` - Defines the `RefreshToken` entity using `SeaORM`.
+//! This module represents the rotating refresh tokens issued at login.
+//!
+//! # Entity Overview
+//! - Represents a single refresh token, stored as a hash.
+//! - Tokens rotated from the same login share a `family_id`.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a refresh token in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "refresh_token")]
+pub struct Model {
+    /// Unique identifier for the refresh token (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the user the token was issued to (Foreign Key).
+    pub user_id: i32,
+
+    /// SHA-256 hash of the refresh token (Unique constraint).
+    #[sea_orm(unique)]
+    pub token_hash: String,
+
+    /// Identifier shared by all tokens rotated from the same login.
+    pub family_id: Uuid,
+
+    /// Timestamp after which the token can no longer be used.
+    pub expires_at: DateTime,
+
+    /// Timestamp of when the token was rotated or revoked, if it was.
+    pub revoked_at: Option<DateTime>,
+
+    /// Timestamp of when the token was created.
+    pub created_at: DateTime,
+}
+
+/// Defines relationships between `RefreshToken` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each refresh token belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `RefreshToken` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
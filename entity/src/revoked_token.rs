@@ -0,0 +1,31 @@
+//! `revoked_token.rs` - Defines the `RevokedToken` entity using `SeaORM`.
+//! This module represents access tokens revoked before their expiry.
+//!
+//! # Entity Overview
+//! - Represents the revocation list checked by the auth middleware.
+//! - Contains the token `jti` and its original expiry, after which the row can be purged.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a revoked access token in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "revoked_token")]
+pub struct Model {
+    /// Unique identifier for the revoked token (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// The `jti` claim of the revoked access token (Unique constraint).
+    #[sea_orm(unique)]
+    pub jti: String,
+
+    /// Expiry of the revoked access token.
+    pub expires_at: DateTime,
+}
+
+/// `RevokedToken` has no relations to other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,64 @@
+//! `session.rs` - Defines the `Session` entity using `SeaORM`.
+//! This module represents a device or browser a user logged in from.
+//!
+//! # Entity Overview
+//! - Represents a single login, identified by its refresh token family.
+//! - Records the user agent, client IP and when the session was last used.
+//! - Establishes a relationship with the `User` entity.
+
+use sea_orm::entity::prelude::*;
+
+/// Represents a login session in the database.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "session")]
+pub struct Model {
+    /// Unique identifier for the session (Primary Key).
+    #[sea_orm(primary_key)]
+    pub id: i32,
+
+    /// ID of the logged in user (Foreign Key).
+    pub user_id: i32,
+
+    /// Refresh token family of the session, also carried by its access tokens as `sid` (Unique constraint).
+    #[sea_orm(unique)]
+    pub family_id: Uuid,
+
+    /// `User-Agent` header sent at login, if any.
+    pub user_agent: Option<String>,
+
+    /// Client IP address at login.
+    pub ip_address: String,
+
+    /// Timestamp of the login.
+    pub created_at: DateTime,
+
+    /// Timestamp of the most recent token refresh.
+    pub last_seen_at: DateTime,
+
+    /// Timestamp of when the session was ended, if it was.
+    pub revoked_at: Option<DateTime>,
+}
+
+/// Defines relationships between `Session` and other entities.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    /// Relationship: Each session belongs to a single user.
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    User,
+}
+
+/// Implements relationship behavior for `Session` and `User`.
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+/// Defines custom behavior for the active model (if needed in the future).
+impl ActiveModelBehavior for ActiveModel {}
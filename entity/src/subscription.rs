@@ -3,10 +3,30 @@
 //!
 //! # Entity Overview
 //! - Represents a subscription relationship between users.
-//! - Contains fields such as `id`, `subscribed_user_id`, `subscriber_user_id`, and `created_at`.
+//! - Contains fields such as `id`, `subscribed_user_id`, `subscriber_user_id`, `delivery_mode` and `created_at`.
 //! - Establishes relationships with the `User` entity.
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How new articles reach a subscriber.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryMode {
+    /// One email per article, as soon as it is published.
+    #[sea_orm(string_value = "instant")]
+    Instant,
+    /// One email a day listing the new articles.
+    #[sea_orm(string_value = "daily")]
+    Daily,
+    /// One email a week listing the new articles.
+    #[sea_orm(string_value = "weekly")]
+    Weekly,
+    /// No emails.
+    #[sea_orm(string_value = "none")]
+    None,
+}
 
 /// Represents a subscription relationship between users.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -21,6 +41,9 @@ pub struct Model {
     
     /// ID of the user who is subscribing.
     pub subscriber_user_id: i32,
+
+    /// Delivery mode of this subscription; the subscriber's default mode applies if unset.
+    pub delivery_mode: Option<DeliveryMode>,
     
     /// Timestamp of when the subscription was created.
     pub created_at: DateTime,
@@ -3,10 +3,30 @@
 //!
 //! # Entity Overview
 //! - Represents a user in the database.
-//! - Contains fields such as `id`, `name`, `email`, and `password`.
+//! - Contains fields such as `id`, `name`, `email`, `password`, `email_verified_at`, `pending_email`, `role` and `deleted_at`,
+//!   plus the public profile: `handle`, `bio`, `avatar_url` and `website`, the default `delivery_mode`, the `timezone` and the `locale`.
 //! - Establishes a one-to-many relationship with the `Article` entity.
 
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::subscription::DeliveryMode;
+
+/// Role of a user, deciding which permissions they hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    /// Manages users and their roles.
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    /// Publishes articles; the role given to new accounts.
+    #[sea_orm(string_value = "author")]
+    Author,
+    /// Reads and subscribes, but cannot publish.
+    #[sea_orm(string_value = "reader")]
+    Reader,
+}
 
 /// Represents a user in the database.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -25,6 +45,51 @@ pub struct Model {
     
     /// Hashed password of the user.
     pub password: String,
+
+    /// Timestamp of when the email address was verified, if it was.
+    pub email_verified_at: Option<DateTime>,
+
+    /// New email address waiting to be confirmed from that address, if any.
+    pub pending_email: Option<String>,
+
+    /// Base32 encoded TOTP secret, set once two-factor enrollment starts.
+    pub totp_secret: Option<String>,
+
+    /// Timestamp of when two-factor authentication was confirmed, if it was.
+    pub totp_enabled_at: Option<DateTime>,
+
+    /// Time step of the last TOTP code accepted at login, so that it cannot be replayed.
+    pub totp_last_step: Option<i64>,
+
+    /// Role of the user.
+    pub role: UserRole,
+
+    /// Timestamp of when the account was erased, if it was. Erased accounts only keep
+    /// this row so their anonymized articles still have an author.
+    pub deleted_at: Option<DateTime>,
+
+    /// Unique handle of the public author page, e.g. `jane-doe`, once the user picked one.
+    #[sea_orm(unique)]
+    pub handle: Option<String>,
+
+    /// Short self-description shown on the author page.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub bio: Option<String>,
+
+    /// URL of the profile picture.
+    pub avatar_url: Option<String>,
+
+    /// URL of the author's own website.
+    pub website: Option<String>,
+
+    /// Default delivery mode of the user's subscriptions.
+    pub delivery_mode: DeliveryMode,
+
+    /// IANA name of the user's timezone, e.g. `Europe/Berlin`.
+    pub timezone: String,
+
+    /// Locale tag used for emails and dates, e.g. `de-DE`.
+    pub locale: String,
 }
 
 /// Defines relationships between `User` and other entities.
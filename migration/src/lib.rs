@@ -5,6 +5,28 @@
 //! - `m20241130_145647_create_user_table` - Creates the `User` table.
 //! - `m20250102_221835_article_table` - Creates the `Article` table.
 //! - `m20250208_132108_subscription_table` - Creates the `Subscription` table.
+//! - `m20250301_120000_refresh_token_table` - Creates the `RefreshToken` table.
+//! - `m20250301_120500_revoked_token_table` - Creates the `RevokedToken` table.
+//! - `m20250315_090000_email_verification` - Adds email verification and the `OneTimeToken` table.
+//! - `m20250322_100000_two_factor_auth` - Adds TOTP columns and the `RecoveryCode` table.
+//! - `m20250405_080000_api_key_table` - Creates the `ApiKey` table.
+//! - `m20250412_090000_user_role` - Adds the `role` column to the `User` table.
+//! - `m20250419_100000_oauth_identity_table` - Creates the `OauthIdentity` table.
+//! - `m20250426_110000_login_throttle_table` - Creates the `LoginThrottle` table.
+//! - `m20250503_090000_session_table` - Creates the `Session` table.
+//! - `m20250510_090000_account_deletion` - Adds `deleted_at` to the `User` table and creates the `AccountDeletion` table.
+//! - `m20250517_090000_data_export_table` - Creates the `DataExport` table.
+//! - `m20250524_090000_user_pending_email` - Adds the `pending_email` column to the `User` table.
+//! - `m20250531_090000_audit_event_table` - Creates the `AuditEvent` table.
+//! - `m20250607_090000_user_profile` - Adds the public profile columns to the `User` table.
+//! - `m20250614_090000_delivery_preferences` - Adds delivery modes and creates the `DigestItem` table.
+//! - `m20250621_090000_user_locale` - Adds the `timezone` and `locale` columns to the `User` table.
+//! - `m20250628_090000_article_edits` - Adds the `updated_at` and `deleted_at` columns to the `Article` table.
+//! - `m20250705_090000_article_status` - Adds the `status` and `publish_at` columns to the `Article` table.
+//! - `m20250712_090000_article_rendering` - Adds the `content_html` and `content_text` columns to the `Article` table.
+//! - `m20250719_090000_article_listing_index` - Indexes `created_at` and `id` of the `Article` table for paging through articles.
+//! - `m20250726_090000_article_search` - Adds the generated full-text `search_vector` column and its GIN index to the `Article` table on PostgreSQL.
+//! - `m20250802_090000_totp_last_step` - Adds the `totp_last_step` column to the `User` table.
 
 pub use sea_orm_migration::prelude::*;
 
@@ -12,6 +34,28 @@ pub use sea_orm_migration::prelude::*;
 mod m20241130_145647_create_user_table;
 mod m20250102_221835_article_table;
 mod m20250208_132108_subscription_table;
+mod m20250301_120000_refresh_token_table;
+mod m20250301_120500_revoked_token_table;
+mod m20250315_090000_email_verification;
+mod m20250322_100000_two_factor_auth;
+mod m20250405_080000_api_key_table;
+mod m20250412_090000_user_role;
+mod m20250419_100000_oauth_identity_table;
+mod m20250426_110000_login_throttle_table;
+mod m20250503_090000_session_table;
+mod m20250510_090000_account_deletion;
+mod m20250517_090000_data_export_table;
+mod m20250524_090000_user_pending_email;
+mod m20250531_090000_audit_event_table;
+mod m20250607_090000_user_profile;
+mod m20250614_090000_delivery_preferences;
+mod m20250621_090000_user_locale;
+mod m20250628_090000_article_edits;
+mod m20250705_090000_article_status;
+mod m20250712_090000_article_rendering;
+mod m20250719_090000_article_listing_index;
+mod m20250726_090000_article_search;
+mod m20250802_090000_totp_last_step;
 
 /// Handles database migrations.
 pub struct Migrator;
@@ -23,7 +67,29 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20241130_145647_create_user_table::Migration),
             Box::new(m20250102_221835_article_table::Migration),
-            Box::new(m20250208_132108_subscription_table::Migration)
+            Box::new(m20250208_132108_subscription_table::Migration),
+            Box::new(m20250301_120000_refresh_token_table::Migration),
+            Box::new(m20250301_120500_revoked_token_table::Migration),
+            Box::new(m20250315_090000_email_verification::Migration),
+            Box::new(m20250322_100000_two_factor_auth::Migration),
+            Box::new(m20250405_080000_api_key_table::Migration),
+            Box::new(m20250412_090000_user_role::Migration),
+            Box::new(m20250419_100000_oauth_identity_table::Migration),
+            Box::new(m20250426_110000_login_throttle_table::Migration),
+            Box::new(m20250503_090000_session_table::Migration),
+            Box::new(m20250510_090000_account_deletion::Migration),
+            Box::new(m20250517_090000_data_export_table::Migration),
+            Box::new(m20250524_090000_user_pending_email::Migration),
+            Box::new(m20250531_090000_audit_event_table::Migration),
+            Box::new(m20250607_090000_user_profile::Migration),
+            Box::new(m20250614_090000_delivery_preferences::Migration),
+            Box::new(m20250621_090000_user_locale::Migration),
+            Box::new(m20250628_090000_article_edits::Migration),
+            Box::new(m20250705_090000_article_status::Migration),
+            Box::new(m20250712_090000_article_rendering::Migration),
+            Box::new(m20250719_090000_article_listing_index::Migration),
+            Box::new(m20250726_090000_article_search::Migration),
+            Box::new(m20250802_090000_totp_last_step::Migration),
         ]
     }
 }
@@ -0,0 +1,86 @@
+/// Migration script for creating the `RefreshToken` table.
+/// This migration uses `sea_orm_migration` and references the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `RefreshToken` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RefreshToken::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RefreshToken::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(RefreshToken::UserId).integer().not_null())
+                    .col(ColumnDef::new(RefreshToken::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(RefreshToken::FamilyId).uuid().not_null())
+                    .col(ColumnDef::new(RefreshToken::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(RefreshToken::RevokedAt).timestamp())
+                    .col(ColumnDef::new(RefreshToken::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refresh_token-user_id")
+                            .from(RefreshToken::Table, RefreshToken::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-refresh_token-family_id")
+                    .table(RefreshToken::Table)
+                    .col(RefreshToken::FamilyId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `RefreshToken` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RefreshToken::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `RefreshToken`.
+#[derive(DeriveIden)]
+pub enum RefreshToken {
+    /// Table identifier for `RefreshToken`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `token_hash`
+    TokenHash,
+    /// Column identifier for `family_id`
+    FamilyId,
+    /// Column identifier for `expires_at`
+    ExpiresAt,
+    /// Column identifier for `revoked_at`
+    RevokedAt,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
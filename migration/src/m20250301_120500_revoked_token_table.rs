@@ -0,0 +1,57 @@
+/// Migration script for creating the `RevokedToken` table.
+/// This migration uses `sea_orm_migration` to store revoked access token ids (`jti`).
+use sea_orm_migration::prelude::*;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `RevokedToken` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RevokedToken::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RevokedToken::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(RevokedToken::Jti).string().not_null().unique_key())
+                    .col(ColumnDef::new(RevokedToken::ExpiresAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `RevokedToken` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RevokedToken::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `RevokedToken`.
+#[derive(DeriveIden)]
+pub enum RevokedToken {
+    /// Table identifier for `RevokedToken`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `jti`
+    Jti,
+    /// Column identifier for `expires_at`
+    ExpiresAt,
+}
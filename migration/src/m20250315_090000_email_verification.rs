@@ -0,0 +1,112 @@
+/// Migration script adding email verification.
+/// This migration adds `email_verified_at` to the `User` table and creates the
+/// `OneTimeToken` table holding hashed single-use tokens sent by email.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the verification column and create the `OneTimeToken` table.
+    /// Accounts that already exist are considered verified.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserVerification::EmailVerifiedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(User::Table)
+                    .value(UserVerification::EmailVerifiedAt, Expr::current_timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OneTimeToken::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(OneTimeToken::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(OneTimeToken::UserId).integer().not_null())
+                    .col(ColumnDef::new(OneTimeToken::Purpose).string_len(32).not_null())
+                    .col(ColumnDef::new(OneTimeToken::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(OneTimeToken::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(OneTimeToken::UsedAt).timestamp())
+                    .col(ColumnDef::new(OneTimeToken::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-one_time_token-user_id")
+                            .from(OneTimeToken::Table, OneTimeToken::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `OneTimeToken` table and the verification column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OneTimeToken::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserVerification::EmailVerifiedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserVerification {
+    /// Column identifier for `email_verified_at`
+    EmailVerifiedAt,
+}
+
+/// Enum representing identifiers (columns and table name) for `OneTimeToken`.
+#[derive(DeriveIden)]
+pub enum OneTimeToken {
+    /// Table identifier for `OneTimeToken`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `purpose`
+    Purpose,
+    /// Column identifier for `token_hash`
+    TokenHash,
+    /// Column identifier for `expires_at`
+    ExpiresAt,
+    /// Column identifier for `used_at`
+    UsedAt,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
@@ -0,0 +1,100 @@
+/// Migration script adding TOTP two-factor authentication.
+/// This migration adds the TOTP columns to the `User` table and creates the
+/// `RecoveryCode` table holding hashed single-use recovery codes.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the TOTP columns and create the `RecoveryCode` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserTwoFactor::TotpSecret).string())
+                    .add_column(ColumnDef::new(UserTwoFactor::TotpEnabledAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecoveryCode::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RecoveryCode::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(RecoveryCode::UserId).integer().not_null())
+                    .col(ColumnDef::new(RecoveryCode::CodeHash).string().not_null())
+                    .col(ColumnDef::new(RecoveryCode::UsedAt).timestamp())
+                    .col(ColumnDef::new(RecoveryCode::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-recovery_code-user_id")
+                            .from(RecoveryCode::Table, RecoveryCode::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `RecoveryCode` table and the TOTP columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RecoveryCode::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserTwoFactor::TotpSecret)
+                    .drop_column(UserTwoFactor::TotpEnabledAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserTwoFactor {
+    /// Column identifier for `totp_secret`
+    TotpSecret,
+    /// Column identifier for `totp_enabled_at`
+    TotpEnabledAt,
+}
+
+/// Enum representing identifiers (columns and table name) for `RecoveryCode`.
+#[derive(DeriveIden)]
+pub enum RecoveryCode {
+    /// Table identifier for `RecoveryCode`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `code_hash`
+    CodeHash,
+    /// Column identifier for `used_at`
+    UsedAt,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
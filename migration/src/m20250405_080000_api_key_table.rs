@@ -0,0 +1,82 @@
+/// Migration script for creating the `ApiKey` table.
+/// This migration uses `sea_orm_migration` and references the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `ApiKey` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKey::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ApiKey::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(ApiKey::UserId).integer().not_null())
+                    .col(ColumnDef::new(ApiKey::Name).string().not_null())
+                    .col(ColumnDef::new(ApiKey::Prefix).string().not_null())
+                    .col(ColumnDef::new(ApiKey::KeyHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(ApiKey::Scopes).string().not_null())
+                    .col(ColumnDef::new(ApiKey::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(ApiKey::LastUsedAt).timestamp())
+                    .col(ColumnDef::new(ApiKey::RevokedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-api_key-user_id")
+                            .from(ApiKey::Table, ApiKey::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `ApiKey` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiKey::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `ApiKey`.
+#[derive(DeriveIden)]
+pub enum ApiKey {
+    /// Table identifier for `ApiKey`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `name`
+    Name,
+    /// Column identifier for `prefix`
+    Prefix,
+    /// Column identifier for `key_hash`
+    KeyHash,
+    /// Column identifier for `scopes`
+    Scopes,
+    /// Column identifier for `created_at`
+    CreatedAt,
+    /// Column identifier for `last_used_at`
+    LastUsedAt,
+    /// Column identifier for `revoked_at`
+    RevokedAt,
+}
@@ -0,0 +1,60 @@
+/// Migration script adding roles to users.
+/// This migration adds the `role` column to the `User` table. Existing users become authors,
+/// which keeps their ability to publish; admins have to be promoted explicitly.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the `role` column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(UserRole::Role)
+                            .string_len(16)
+                            .not_null()
+                            .default("author"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `role` column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserRole::Role)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserRole {
+    /// Column identifier for `role`
+    Role,
+}
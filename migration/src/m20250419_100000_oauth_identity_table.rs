@@ -0,0 +1,82 @@
+/// Migration script for creating the `OauthIdentity` table.
+/// This migration uses `sea_orm_migration` and references the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `OauthIdentity` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthIdentity::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(OauthIdentity::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(OauthIdentity::UserId).integer().not_null())
+                    .col(ColumnDef::new(OauthIdentity::Provider).string().not_null())
+                    .col(ColumnDef::new(OauthIdentity::Subject).string().not_null())
+                    .col(ColumnDef::new(OauthIdentity::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-oauth_identity-user_id")
+                            .from(OauthIdentity::Table, OauthIdentity::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-oauth_identity-provider-subject")
+                    .table(OauthIdentity::Table)
+                    .col(OauthIdentity::Provider)
+                    .col(OauthIdentity::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `OauthIdentity` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OauthIdentity::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `OauthIdentity`.
+#[derive(DeriveIden)]
+pub enum OauthIdentity {
+    /// Table identifier for `OauthIdentity`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `provider`
+    Provider,
+    /// Column identifier for `subject`
+    Subject,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
@@ -0,0 +1,78 @@
+/// Migration script for creating the `LoginThrottle` table.
+/// This migration uses `sea_orm_migration` to store failed login counters per account and client IP.
+use sea_orm_migration::prelude::*;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `LoginThrottle` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginThrottle::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(LoginThrottle::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(LoginThrottle::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(LoginThrottle::Key).string().not_null())
+                    .col(ColumnDef::new(LoginThrottle::FailedCount).integer().not_null())
+                    .col(ColumnDef::new(LoginThrottle::LastFailedAt).timestamp().not_null())
+                    .col(ColumnDef::new(LoginThrottle::LockedUntil).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-login_throttle-kind-key")
+                    .table(LoginThrottle::Table)
+                    .col(LoginThrottle::Kind)
+                    .col(LoginThrottle::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `LoginThrottle` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginThrottle::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `LoginThrottle`.
+#[derive(DeriveIden)]
+pub enum LoginThrottle {
+    /// Table identifier for `LoginThrottle`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `kind`
+    Kind,
+    /// Column identifier for `key`
+    Key,
+    /// Column identifier for `failed_count`
+    FailedCount,
+    /// Column identifier for `last_failed_at`
+    LastFailedAt,
+    /// Column identifier for `locked_until`
+    LockedUntil,
+}
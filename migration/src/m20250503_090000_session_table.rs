@@ -0,0 +1,79 @@
+/// Migration script for creating the `Session` table.
+/// This migration uses `sea_orm_migration` and references the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `Session` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Session::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Session::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(Session::UserId).integer().not_null())
+                    .col(ColumnDef::new(Session::FamilyId).uuid().not_null().unique_key())
+                    .col(ColumnDef::new(Session::UserAgent).string())
+                    .col(ColumnDef::new(Session::IpAddress).string().not_null())
+                    .col(ColumnDef::new(Session::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(Session::LastSeenAt).timestamp().not_null())
+                    .col(ColumnDef::new(Session::RevokedAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-session-user_id")
+                            .from(Session::Table, Session::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `Session` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Session::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `Session`.
+#[derive(DeriveIden)]
+pub enum Session {
+    /// Table identifier for `Session`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `family_id`
+    FamilyId,
+    /// Column identifier for `user_agent`
+    UserAgent,
+    /// Column identifier for `ip_address`
+    IpAddress,
+    /// Column identifier for `created_at`
+    CreatedAt,
+    /// Column identifier for `last_seen_at`
+    LastSeenAt,
+    /// Column identifier for `revoked_at`
+    RevokedAt,
+}
@@ -0,0 +1,96 @@
+/// Migration script adding account deletion.
+/// This migration adds `deleted_at` to the `User` table and creates the `AccountDeletion`
+/// table holding deletions that wait out their grace period.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the deletion column and create the `AccountDeletion` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserDeletion::DeletedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AccountDeletion::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AccountDeletion::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(AccountDeletion::UserId).integer().not_null().unique_key())
+                    .col(ColumnDef::new(AccountDeletion::Articles).string_len(16).not_null())
+                    .col(ColumnDef::new(AccountDeletion::RequestedAt).timestamp().not_null())
+                    .col(ColumnDef::new(AccountDeletion::ScheduledFor).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-account_deletion-user_id")
+                            .from(AccountDeletion::Table, AccountDeletion::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `AccountDeletion` table and the deletion column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AccountDeletion::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserDeletion::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserDeletion {
+    /// Column identifier for `deleted_at`
+    DeletedAt,
+}
+
+/// Enum representing identifiers (columns and table name) for `AccountDeletion`.
+#[derive(DeriveIden)]
+pub enum AccountDeletion {
+    /// Table identifier for `AccountDeletion`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `articles`
+    Articles,
+    /// Column identifier for `requested_at`
+    RequestedAt,
+    /// Column identifier for `scheduled_for`
+    ScheduledFor,
+}
@@ -0,0 +1,76 @@
+/// Migration script for creating the `DataExport` table.
+/// This migration uses `sea_orm_migration` and references the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `DataExport` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataExport::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(DataExport::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(DataExport::UserId).integer().not_null())
+                    .col(ColumnDef::new(DataExport::Status).string_len(16).not_null())
+                    .col(ColumnDef::new(DataExport::Archive).binary())
+                    .col(ColumnDef::new(DataExport::TokenHash).string().unique_key())
+                    .col(ColumnDef::new(DataExport::CreatedAt).timestamp().not_null())
+                    .col(ColumnDef::new(DataExport::ExpiresAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-data_export-user_id")
+                            .from(DataExport::Table, DataExport::UserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `DataExport` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DataExport::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `DataExport`.
+#[derive(DeriveIden)]
+pub enum DataExport {
+    /// Table identifier for `DataExport`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `status`
+    Status,
+    /// Column identifier for `archive`
+    Archive,
+    /// Column identifier for `token_hash`
+    TokenHash,
+    /// Column identifier for `created_at`
+    CreatedAt,
+    /// Column identifier for `expires_at`
+    ExpiresAt,
+}
@@ -0,0 +1,55 @@
+/// Migration script adding email changes.
+/// This migration adds the `pending_email` column to the `User` table, holding a new
+/// address until it is confirmed.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the `pending_email` column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserPendingEmail::PendingEmail).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `pending_email` column.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserPendingEmail::PendingEmail)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserPendingEmail {
+    /// Column identifier for `pending_email`
+    PendingEmail,
+}
@@ -0,0 +1,80 @@
+/// Migration script for creating the `AuditEvent` table.
+/// This migration uses `sea_orm_migration`. The table has no foreign keys so the audit log
+/// keeps events of erased accounts.
+use sea_orm_migration::prelude::*;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the `AuditEvent` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(AuditEvent::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(AuditEvent::UserId).integer())
+                    .col(ColumnDef::new(AuditEvent::ActorId).integer())
+                    .col(ColumnDef::new(AuditEvent::Action).string_len(32).not_null())
+                    .col(ColumnDef::new(AuditEvent::IpAddress).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Details).string())
+                    .col(ColumnDef::new(AuditEvent::CreatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-audit_event-user_id")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `AuditEvent` table.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await
+    }
+}
+
+/// Enum representing identifiers (columns and table name) for `AuditEvent`.
+#[derive(DeriveIden)]
+pub enum AuditEvent {
+    /// Table identifier for `AuditEvent`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `user_id`
+    UserId,
+    /// Column identifier for `actor_id`
+    ActorId,
+    /// Column identifier for `action`
+    Action,
+    /// Column identifier for `ip_address`
+    IpAddress,
+    /// Column identifier for `details`
+    Details,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
@@ -0,0 +1,67 @@
+/// Migration script adding public author profiles.
+/// This migration adds the `handle`, `bio`, `avatar_url` and `website` columns to the
+/// `User` table. Handles are unique and identify an author in public URLs.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the profile columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserProfile::Handle).string().unique_key())
+                    .add_column(ColumnDef::new(UserProfile::Bio).text())
+                    .add_column(ColumnDef::new(UserProfile::AvatarUrl).string())
+                    .add_column(ColumnDef::new(UserProfile::Website).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the profile columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserProfile::Handle)
+                    .drop_column(UserProfile::Bio)
+                    .drop_column(UserProfile::AvatarUrl)
+                    .drop_column(UserProfile::Website)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserProfile {
+    /// Column identifier for `handle`
+    Handle,
+    /// Column identifier for `bio`
+    Bio,
+    /// Column identifier for `avatar_url`
+    AvatarUrl,
+    /// Column identifier for `website`
+    Website,
+}
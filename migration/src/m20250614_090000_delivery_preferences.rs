@@ -0,0 +1,139 @@
+/// Migration script adding delivery preferences.
+/// This migration adds the default `delivery_mode` to the `User` table and the per-subscription
+/// override to the `Subscription` table, and creates the `DigestItem` table queueing articles
+/// for daily and weekly digests.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+use crate::m20250102_221835_article_table::Article;
+use crate::m20250208_132108_subscription_table::Subscription;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the delivery columns and create the `DigestItem` table.
+    /// Existing users keep getting every article right away.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(Delivery::DeliveryMode)
+                            .string_len(16)
+                            .not_null()
+                            .default("instant"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(ColumnDef::new(Delivery::DeliveryMode).string_len(16))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DigestItem::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(DigestItem::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(DigestItem::SubscriberUserId).integer().not_null())
+                    .col(ColumnDef::new(DigestItem::ArticleId).integer().not_null())
+                    .col(ColumnDef::new(DigestItem::DeliverAfter).timestamp().not_null())
+                    .col(ColumnDef::new(DigestItem::CreatedAt).timestamp().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-digest_item-subscriber_user_id")
+                            .from(DigestItem::Table, DigestItem::SubscriberUserId)
+                            .to(User::Table, User::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-digest_item-article_id")
+                            .from(DigestItem::Table, DigestItem::ArticleId)
+                            .to(Article::Table, Article::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-digest_item-deliver_after")
+                    .table(DigestItem::Table)
+                    .col(DigestItem::DeliverAfter)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the `DigestItem` table and the delivery columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the drop or alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DigestItem::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .drop_column(Delivery::DeliveryMode)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(Delivery::DeliveryMode)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifier added to `User` and `Subscription`.
+#[derive(DeriveIden)]
+pub enum Delivery {
+    /// Column identifier for `delivery_mode`
+    DeliveryMode,
+}
+
+/// Enum representing identifiers (columns and table name) for `DigestItem`.
+#[derive(DeriveIden)]
+pub enum DigestItem {
+    /// Table identifier for `DigestItem`
+    Table,
+    /// Column identifier for `id`
+    Id,
+    /// Column identifier for `subscriber_user_id`
+    SubscriberUserId,
+    /// Column identifier for `article_id`
+    ArticleId,
+    /// Column identifier for `deliver_after`
+    DeliverAfter,
+    /// Column identifier for `created_at`
+    CreatedAt,
+}
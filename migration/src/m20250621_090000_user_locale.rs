@@ -0,0 +1,60 @@
+/// Migration script adding timezone and locale preferences.
+/// This migration adds the `timezone` (an IANA name such as `Europe/Berlin`) and `locale`
+/// (a tag such as `de-DE`) columns to the `User` table.
+use sea_orm_migration::prelude::*;
+use crate::m20241130_145647_create_user_table::User;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the preference columns.
+    /// Existing users keep UTC and English.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(UserLocale::Timezone).string_len(64).not_null().default("UTC"))
+                    .add_column(ColumnDef::new(UserLocale::Locale).string_len(16).not_null().default("en-US"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the preference columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(UserLocale::Timezone)
+                    .drop_column(UserLocale::Locale)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `User`.
+#[derive(DeriveIden)]
+pub enum UserLocale {
+    /// Column identifier for `timezone`
+    Timezone,
+    /// Column identifier for `locale`
+    Locale,
+}
@@ -0,0 +1,74 @@
+/// Migration script adding article edits and soft deletion.
+/// This migration adds the `updated_at` and `deleted_at` columns to the `Article` table.
+/// Deleted articles are kept so that links to them can report that they were removed.
+use sea_orm_migration::prelude::*;
+use crate::m20250102_221835_article_table::Article;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the columns.
+    /// Existing articles count as last updated when they were created.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or the update fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .add_column(
+                        ColumnDef::new(ArticleEdits::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .add_column(ColumnDef::new(ArticleEdits::DeletedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Article::Table)
+                    .value(ArticleEdits::UpdatedAt, Expr::col(Article::CreatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .drop_column(ArticleEdits::UpdatedAt)
+                    .drop_column(ArticleEdits::DeletedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `Article`.
+#[derive(DeriveIden)]
+pub enum ArticleEdits {
+    /// Column identifier for `updated_at`
+    UpdatedAt,
+    /// Column identifier for `deleted_at`
+    DeletedAt,
+}
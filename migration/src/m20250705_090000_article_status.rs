@@ -0,0 +1,94 @@
+/// Migration script adding the publication state of articles.
+/// This migration adds the `status` and `publish_at` columns to the `Article` table, and an
+/// index the publishing job uses to find scheduled articles that are due.
+use sea_orm_migration::prelude::*;
+use crate::m20250102_221835_article_table::Article;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the columns and the index.
+    /// Existing articles count as published when they were created.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration, the update or the index creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .add_column(
+                        ColumnDef::new(ArticleStatus::Status)
+                            .string_len(16)
+                            .not_null()
+                            .default("published"),
+                    )
+                    .add_column(ColumnDef::new(ArticleStatus::PublishAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Article::Table)
+                    .value(ArticleStatus::PublishAt, Expr::col(Article::CreatedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-article-status-publish_at")
+                    .table(Article::Table)
+                    .col(ArticleStatus::Status)
+                    .col(ArticleStatus::PublishAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the index and the columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if dropping fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-article-status-publish_at")
+                    .table(Article::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .drop_column(ArticleStatus::Status)
+                    .drop_column(ArticleStatus::PublishAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `Article`.
+#[derive(DeriveIden)]
+pub enum ArticleStatus {
+    /// Column identifier for `status`
+    Status,
+    /// Column identifier for `publish_at`
+    PublishAt,
+}
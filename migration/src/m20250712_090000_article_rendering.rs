@@ -0,0 +1,60 @@
+/// Migration script adding the rendered versions of articles.
+/// This migration adds the `content_html` and `content_text` columns to the `Article` table,
+/// holding the sanitized HTML and the plain text rendered from the Markdown in `content`.
+use sea_orm_migration::prelude::*;
+use crate::m20250102_221835_article_table::Article;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the columns.
+    /// Existing articles are rendered when they are read until they are edited.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .add_column(ColumnDef::new(ArticleRendering::ContentHtml).text())
+                    .add_column(ColumnDef::new(ArticleRendering::ContentText).text())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the columns.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .drop_column(ArticleRendering::ContentHtml)
+                    .drop_column(ArticleRendering::ContentText)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `Article`.
+#[derive(DeriveIden)]
+pub enum ArticleRendering {
+    /// Column identifier for `content_html`
+    ContentHtml,
+    /// Column identifier for `content_text`
+    ContentText,
+}
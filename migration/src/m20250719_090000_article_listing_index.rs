@@ -0,0 +1,50 @@
+/// Migration script adding the index used to page through articles.
+/// Article listings are ordered by `created_at` and `id`, and continue after the last article
+/// of the previous page, so both columns are indexed together.
+use sea_orm_migration::prelude::*;
+use crate::m20250102_221835_article_table::Article;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to create the index.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the index creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-article-created_at-id")
+                    .table(Article::Table)
+                    .col(Article::CreatedAt)
+                    .col(Article::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the index.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if dropping fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-article-created_at-id")
+                    .table(Article::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,100 @@
+/// Migration script adding full-text search of articles on PostgreSQL.
+/// This migration adds the generated `search_vector` column to the `Article` table, holding the
+/// title (weight `A`) and the plain text (weight `B`) of an article, and a GIN index on it.
+/// Other backends have no text search; the application searches them without the column.
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DatabaseBackend;
+use crate::m20250102_221835_article_table::Article;
+
+/// Struct representing the migration.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Name of the GIN index on `search_vector`.
+const SEARCH_INDEX: &str = "idx-article-search_vector";
+
+/// Expression of `search_vector`. The text search configuration must match the one of the
+/// search queries in `utils::search`; articles stored before rendering existed use their source.
+const SEARCH_VECTOR: &str = "GENERATED ALWAYS AS (\
+    setweight(to_tsvector('english', \"title\"), 'A') || \
+    setweight(to_tsvector('english', COALESCE(\"content_text\", \"content\")), 'B')\
+    ) STORED";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    /// Runs the `up` migration to add the column and the index on PostgreSQL.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if the alteration or the index creation fails.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DatabaseBackend::Postgres {
+            return Ok(());
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .add_column(
+                        ColumnDef::new(ArticleSearch::SearchVector)
+                            .custom(Alias::new("tsvector"))
+                            .extra(SEARCH_VECTOR),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(SEARCH_INDEX)
+                    .table(Article::Table)
+                    .col(ArticleSearch::SearchVector)
+                    .index_type(IndexType::Custom(SeaRc::new(Alias::new("GIN"))))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    /// Runs the `down` migration to drop the index and the column on PostgreSQL.
+    /// 
+    /// # Arguments
+    /// * `manager` - The schema manager to handle database operations.
+    /// 
+    /// # Errors
+    /// Returns `DbErr` if dropping fails.
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DatabaseBackend::Postgres {
+            return Ok(());
+        }
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(SEARCH_INDEX)
+                    .table(Article::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Article::Table)
+                    .drop_column(ArticleSearch::SearchVector)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Enum representing the identifiers added to `Article`.
+#[derive(DeriveIden)]
+pub enum ArticleSearch {
+    /// Column identifier for `search_vector`
+    SearchVector,
+}
+
@@ -0,0 +1,198 @@
+//! Handlers for managing API keys.
+//!
+//! API keys let automation (e.g. CI publishing articles) call the secure endpoints
+//! without storing the user's password. Keys are shown once and stored hashed.
+//! Managing keys requires a JWT, so a key can never mint or revoke other keys.
+
+use std::sync::Arc;
+
+use actix_web::{delete, get, post, web, HttpRequest};
+use chrono::{NaiveDateTime, Utc};
+use entity::audit_event::AuditAction;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{
+    api_response::ApiResponse,
+    app_state::AppState,
+    audit,
+    auth_tokens::{generate_token, hash_token},
+    jwt::Claims,
+    login_throttle::client_ip,
+    scopes::{parse_scopes, GrantedScopes, Scope},
+};
+
+/// Prefix marking a string as one of our API keys.
+const API_KEY_PREFIX: &str = "nlk_";
+
+/// Number of leading characters of a key kept for display.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Request model for creating an API key.
+#[derive(Serialize, Deserialize)]
+pub struct CreateApiKeyModel {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+/// Represents an API key without its secret.
+#[derive(Serialize, Deserialize)]
+pub struct ApiKeyModel {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+/// Response model for a newly created API key, the only time the key is returned.
+#[derive(Serialize, Deserialize)]
+pub struct CreatedApiKeyModel {
+    pub key: String,
+    pub api_key: ApiKeyModel,
+}
+
+impl From<entity::api_key::Model> for ApiKeyModel {
+    fn from(api_key: entity::api_key::Model) -> Self {
+        ApiKeyModel {
+            id: api_key.id,
+            name: api_key.name,
+            prefix: api_key.prefix,
+            scopes: api_key.scopes.split_whitespace().map(str::to_owned).collect(),
+            created_at: api_key.created_at,
+            last_used_at: api_key.last_used_at,
+        }
+    }
+}
+
+/// Handler for creating a new API key.
+///
+/// # Errors
+/// * Returns a `400` error if the name is empty or a scope is unknown.
+/// * Returns a `403` error if called with an API key.
+#[post("/create")]
+pub async fn create_api_key(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    api_key_json: web::Json<CreateApiKeyModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    if api_key_json.name.trim().is_empty() {
+        return Err(ApiResponse::new(400, "Name cannot be empty".to_owned()));
+    }
+
+    let scopes: Vec<Scope> = parse_scopes(&api_key_json.scopes.join(" "))
+        .map_err(|err| ApiResponse::new(400, err))?;
+
+    if scopes.is_empty() {
+        return Err(ApiResponse::new(400, "At least one scope is required".to_owned()));
+    }
+
+    let key = format!("{}{}", API_KEY_PREFIX, generate_token());
+
+    let api_key = entity::api_key::ActiveModel {
+        user_id: Set(claims.id),
+        name: Set(api_key_json.name.trim().to_owned()),
+        prefix: Set(key[..DISPLAY_PREFIX_LEN].to_owned()),
+        key_hash: Set(hash_token(&key)),
+        scopes: Set(scopes.iter().map(Scope::as_str).collect::<Vec<&str>>().join(" ")),
+        created_at: Set(Utc::now().naive_local()),
+        ..Default::default()
+    }
+    .insert(&*db)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(
+        &*db,
+        AuditAction::ApiKeyCreated,
+        Some(claims.id),
+        Some(claims.id),
+        &client_ip(&req),
+        Some(format!("{} ({}): {}", api_key.name, api_key.prefix, api_key.scopes)),
+    )
+    .await;
+
+    let res_str = serde_json::to_string(&CreatedApiKeyModel { key, api_key: api_key.into() })
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, res_str))
+}
+
+/// Handler for listing the authenticated user's active API keys.
+#[get("/my-keys")]
+pub async fn my_api_keys(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let api_keys: Vec<ApiKeyModel> = entity::api_key::Entity::find()
+        .filter(entity::api_key::Column::UserId.eq(claims.id))
+        .filter(entity::api_key::Column::RevokedAt.is_null())
+        .order_by_desc(entity::api_key::Column::CreatedAt)
+        .all(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .map(ApiKeyModel::from)
+        .collect();
+
+    let res_str = serde_json::to_string(&api_keys)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, res_str))
+}
+
+/// Handler for revoking one of the authenticated user's API keys.
+///
+/// # Errors
+/// * Returns a `404` error if the key does not exist, belongs to someone else or is already revoked.
+#[delete("/revoke/{id}")]
+pub async fn revoke_api_key(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    api_key_id: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let update_result = entity::api_key::Entity::update_many()
+        .col_expr(entity::api_key::Column::RevokedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::api_key::Column::Id.eq(*api_key_id))
+        .filter(entity::api_key::Column::UserId.eq(claims.id))
+        .filter(entity::api_key::Column::RevokedAt.is_null())
+        .exec(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if update_result.rows_affected == 0 {
+        return Err(ApiResponse::new(404, "API key not found".to_owned()));
+    }
+
+    audit::record(
+        &*db,
+        AuditAction::ApiKeyRevoked,
+        Some(claims.id),
+        Some(claims.id),
+        &client_ip(&req),
+        Some(format!("API key {}", api_key_id)),
+    )
+    .await;
+
+    Ok(ApiResponse::new(200, "API key revoked successfully".to_owned()))
+}
@@ -0,0 +1,27 @@
+/// Routes configuration for API key endpoints.
+/// This module registers routes for managing API keys with authentication.
+use actix_web::{middleware::from_fn, web};
+use crate::middlewares;
+use super::api_key_handlers;
+
+/// Configures routes for API key management.
+/// 
+/// # Routes:
+/// - `/secure/api-key/create`: Create a named, scoped API key (requires authentication).
+/// - `/secure/api-key/my-keys`: List the user's active API keys (requires authentication).
+/// - `/secure/api-key/revoke/{id}`: Revoke an API key (requires authentication).
+/// 
+/// # Middleware:
+/// - `auth_middlewares::check_auth_middleware`: Ensures user authentication.
+/// 
+/// # Arguments:
+/// * `config` - Actix Web `ServiceConfig` to which routes are added.
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/secure/api-key")
+            .wrap(from_fn(middlewares::auth_middlewares::check_auth_middleware))
+            .service(api_key_handlers::create_api_key)
+            .service(api_key_handlers::my_api_keys)
+            .service(api_key_handlers::revoke_api_key)
+    );
+}
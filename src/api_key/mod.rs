@@ -0,0 +1,8 @@
+//! Module declarations for `api_key` components.
+//! This module exposes handlers and route configurations.
+
+/// Module for API key request handlers.
+pub mod api_key_handlers;
+
+/// Module for API key route configurations.
+pub mod api_key_routes;
@@ -1,46 +1,167 @@
 //! Handlers for managing articles in the newsletter backend.
 //! 
-//! This module provides functions for creating, retrieving, and listing articles,
-//! along with sending notification emails to subscribers.
+//! This module provides functions for creating, retrieving, listing, editing and deleting
+//! articles, along with delivering them to subscribers, see `utils::newsletter`.
+//! Articles start as drafts, scheduled or published (see `utils::publishing`); only published
+//! and archived articles are visible to readers. Deleted articles are kept but hidden, so that
+//! old links report that they were removed.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use actix_web::{post, web};
+use actix_web::{delete, post, put, web, HttpRequest};
 use actix_web::get;
-use chrono::{NaiveDateTime, Utc};
-use sea_orm::{JoinType, QuerySelect, Set};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use sea_orm::Set;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use entity::article::ArticleStatus;
+use entity::audit_event::AuditAction;
 use sea_orm::ActiveModelTrait;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
 use sea_orm::ColumnTrait;
+use sea_orm::{Condition, QueryOrder, QuerySelect, Select};
 
-use crate::email::email_service;
-use crate::utils::api_response::ApiResponse;
-use crate::utils::{api_response, app_state, jwt::Claims};
+use crate::utils::scopes::{GrantedScopes, Scope};
+use crate::utils::{api_response, app_state, audit, jwt::Claims, locale::UserLocale, login_throttle::client_ip, markdown, newsletter, pagination::{decode_cursor, encode_cursor, page_limit}, publishing, search};
 
 /// Represents an article with associated metadata.
 #[derive(Serialize,Deserialize)]
 pub struct ArticleModel {
     pub id: i32,
     pub title: String,
+    /// The Markdown source.
     pub content: String,
+    /// The content rendered to sanitized HTML.
+    pub content_html: String,
+    /// The content rendered to plain text.
+    pub content_text: String,
     pub uuid: Uuid,
     pub user_id: i32,
-    pub created_at: NaiveDateTime,
+    /// In UTC, or in the reader's timezone when listing their own articles.
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+    pub status: ArticleStatus,
+    /// When the article was published, or is going to be if it is scheduled.
+    pub publish_at: Option<DateTime<FixedOffset>>,
     pub image: Option<String>,
     pub user: Option<UserModel>
 }
 
 /// Represents the request model for creating an article.
+/// The content is Markdown (CommonMark with tables and footnotes).
+/// Without a `status` the article is published right away, or scheduled if `publish_at` is given.
 #[derive(Serialize,Deserialize)]
 pub struct CreateArticleModel {
     pub title: String,
-    pub content: String
+    pub content: String,
+    /// `draft`, `scheduled` or `published`.
+    #[serde(default)]
+    pub status: Option<ArticleStatus>,
+    /// When a scheduled article is published, in the author's timezone.
+    #[serde(default)]
+    pub publish_at: Option<NaiveDateTime>,
+}
+
+/// Represents the request model for editing an article.
+/// Without a `status` the article keeps its state, or is scheduled if `publish_at` is given
+/// for a draft.
+#[derive(Serialize,Deserialize)]
+pub struct UpdateArticleModel {
+    pub title: String,
+    pub content: String,
+    /// The new state; published articles can only be archived and archived ones republished.
+    #[serde(default)]
+    pub status: Option<ArticleStatus>,
+    /// When a scheduled article is published, in the author's timezone.
+    #[serde(default)]
+    pub publish_at: Option<NaiveDateTime>,
+}
+
+/// Order of article listings.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleSort {
+    /// Most recently created articles first.
+    #[default]
+    Newest,
+    /// Oldest articles first.
+    Oldest,
+}
+
+impl ArticleSort {
+    /// Returns the name of the order, as used in cursors.
+    fn as_str(&self) -> &'static str {
+        match self {
+            ArticleSort::Newest => "newest",
+            ArticleSort::Oldest => "oldest",
+        }
+    }
+}
+
+/// Query model for paging through article listings.
+#[derive(Serialize, Deserialize)]
+pub struct ArticleListQuery {
+    #[serde(default)]
+    pub sort: ArticleSort,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Number of articles per page, capped at `ARTICLES_MAX_LIMIT`.
+    pub limit: Option<u64>,
+    /// Only articles of this author.
+    pub author_id: Option<i32>,
+    /// Only articles created at or after this time, in UTC or the reader's timezone for their own articles.
+    pub from: Option<NaiveDateTime>,
+    /// Only articles created before this time, in UTC or the reader's timezone for their own articles.
+    pub to: Option<NaiveDateTime>,
+}
+
+/// Represents one page of an article listing.
+#[derive(Serialize, Deserialize)]
+pub struct ArticlePageModel {
+    pub articles: Vec<ArticleModel>,
+    /// Cursor of the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+/// Query model for searching published articles.
+#[derive(Serialize, Deserialize)]
+pub struct ArticleSearchQuery {
+    /// The search terms.
+    pub q: String,
+    /// Only articles of this author.
+    pub author_id: Option<i32>,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Number of results per page, capped at `ARTICLES_MAX_LIMIT`.
+    pub limit: Option<u64>,
+}
+
+/// Represents an article found by a search.
+#[derive(Serialize, Deserialize)]
+pub struct SearchResultModel {
+    pub article: ArticleModel,
+    /// Relevance; higher is better.
+    pub rank: f32,
+    /// Part of the text around the matches, as HTML with the matches in `<mark>`.
+    pub snippet: String,
 }
 
+/// Represents one page of search results, most relevant first.
+#[derive(Serialize, Deserialize)]
+pub struct ArticleSearchModel {
+    pub results: Vec<SearchResultModel>,
+    /// Cursor of the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+/// Number of articles per page unless another limit is given.
+const ARTICLES_DEFAULT_LIMIT: u64 = 20;
+
+/// Largest page of an article listing.
+const ARTICLES_MAX_LIMIT: u64 = 100;
+
 /// Represents a user with minimal details.
 #[derive(Serialize,Deserialize)]
 pub struct UserModel {
@@ -48,25 +169,106 @@ pub struct UserModel {
     email: String,
 }
 
+/// Builds the response model of an article, dated in the given timezone.
+fn article_model(article: entity::article::Model, locale: &UserLocale) -> ArticleModel {
+    let rendered = markdown::rendered(&article);
+
+    ArticleModel {
+        id: article.id,
+        title: article.title,
+        content: article.content,
+        content_html: rendered.html,
+        content_text: rendered.text,
+        uuid: article.uuid,
+        user_id: article.user_id,
+        created_at: locale.timestamp(article.created_at),
+        updated_at: locale.timestamp(article.updated_at),
+        status: article.status,
+        publish_at: article.publish_at.map(|publish_at| locale.timestamp(publish_at)),
+        image: article.image,
+        user: None
+    }
+}
+
+/// Checks the publication time of a scheduled article and converts it to UTC.
+///
+/// # Errors
+/// * Returns a `400` error if the time is missing or not in the future.
+fn schedule_time(
+    publish_at: Option<NaiveDateTime>,
+    locale: &UserLocale,
+) -> Result<NaiveDateTime, api_response::ApiResponse> {
+    let publish_at = publish_at
+        .map(|publish_at| locale.to_utc(publish_at))
+        .ok_or(api_response::ApiResponse::new(400, "Scheduled articles need a publish_at".to_owned()))?;
+
+    if publish_at <= Utc::now().naive_local() {
+        return Err(api_response::ApiResponse::new(400, "publish_at must be in the future".to_owned()));
+    }
+
+    Ok(publish_at)
+}
+
 /// Handler for creating a new article.
 /// 
-/// This function inserts a new article into the database and optionally sends
-/// a newsletter email to subscribers.
+/// This function inserts a new article into the database. Published articles are
+/// delivered to subscribers unless `send_email=false`; scheduled articles are delivered
+/// by the publishing job once they are due, and drafts stay private.
 #[post("/create")]
 pub async fn create_article(
     app_state: web::Data<app_state::AppState>,
     claims: Claims,
+    granted_scopes: GrantedScopes,
     article_model: web::Json<CreateArticleModel>,
     query: web::Query<HashMap<String, String>>, 
 ) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require(Scope::ArticleWrite)?;
+
     let db = Arc::clone(&app_state.db);
 
+    // Only verified accounts can publish
+    let author = entity::user::Entity::find_by_id(claims.id)
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .ok_or(api_response::ApiResponse::new(404, "User not found".to_owned()))?;
+
+    if author.email_verified_at.is_none() {
+        return Err(api_response::ApiResponse::new(
+            403,
+            "Verify your email address before publishing".to_owned(),
+        ));
+    }
+
+    let now = Utc::now().naive_local();
+
+    let status = article_model.status.unwrap_or(match article_model.publish_at {
+        Some(_) => ArticleStatus::Scheduled,
+        None => ArticleStatus::Published,
+    });
+
+    let publish_at = match status {
+        ArticleStatus::Draft => None,
+        ArticleStatus::Scheduled => Some(schedule_time(article_model.publish_at, &UserLocale::of(&author))?),
+        ArticleStatus::Published => Some(now),
+        ArticleStatus::Archived => {
+            return Err(api_response::ApiResponse::new(400, "New articles cannot be archived".to_owned()));
+        }
+    };
+
+    let rendered = markdown::render(&article_model.content);
+
     let article_entity = entity::article::ActiveModel {
         title: Set(article_model.title.clone()),
         content: Set(article_model.content.clone()),
+        content_html: Set(Some(rendered.html)),
+        content_text: Set(Some(rendered.text)),
         user_id: Set(claims.id),
         uuid: Set(Uuid::new_v4()),
-        created_at: Set(Utc::now().naive_local()),
+        status: Set(status),
+        publish_at: Set(publish_at),
+        created_at: Set(now),
+        updated_at: Set(now),
         ..Default::default()
     };
 
@@ -80,81 +282,165 @@ pub async fn create_article(
         .map(|v| v == "true")
         .unwrap_or(true); // Default: true
 
-    if send_email {
-        let subscribers = entity::subscription::Entity::find()
-            .filter(entity::subscription::Column::SubscribedUserId.eq(claims.id))
-            .join_rev(
-                JoinType::InnerJoin,
-                entity::user::Entity::belongs_to(entity::subscription::Entity)
-                    .from(entity::user::Column::Id)
-                    .to(entity::subscription::Column::SubscriberUserId)
-                    .into(),
-            )
-            .select_also(entity::user::Entity)
-            .all(&*db)
-            .await
-            .map_err(|err| ApiResponse::new(500, err.to_string()))?
-            .into_iter()
-            .filter_map(|(_, user)| user)
-            .collect::<Vec<entity::user::Model>>();
-
-        let article_link =format!(
-            "http://localhost:8080/article/get-by-uuid/{}",inserted_article.uuid
-        );
-
-        for subscriber in subscribers {
-            let unsubscribe_link = format!(
-                "http://localhost:8080/subscription/unsubscribe-user-from-email?user_id={}&subscriber_id={}",
-                claims.id, subscriber.id
-            );
-
-            email_service::send_newsletter_email(
-                &subscriber.email,
-                &inserted_article.title,
-                &inserted_article.content[..50],
-                &article_link,
-                &unsubscribe_link,
-            )
-            .await
-            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    // The article is saved, so a failed delivery must not fail the request and invite a retry
+    if send_email && status == ArticleStatus::Published {
+        if let Err(err) = newsletter::notify_subscribers(&*db, &inserted_article).await {
+            eprintln!("Newsletter error for article {}: {}", inserted_article.id, err);
         }
     }
 
     Ok(api_response::ApiResponse::new(200, "Article created successfully".to_owned()))
 }
 
-/// Handler for retrieving all articles.
+/// Loads one page of articles, ordered by creation time and ID.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `articles` - The articles to list.
+/// * `query` - The sort order, cursor, page size and filters.
+/// * `locale` - The timezone of the `from` and `to` filters and of the returned dates.
+///
+/// # Errors
+/// * Returns a `400` error if the cursor is invalid or `from` is after `to`.
+/// * Returns a `500` error if the database query fails.
+async fn article_page(
+    db: &sea_orm::DatabaseConnection,
+    mut articles: Select<entity::article::Entity>,
+    query: &ArticleListQuery,
+    locale: &UserLocale,
+) -> Result<ArticlePageModel, api_response::ApiResponse> {
+    let limit = page_limit(query.limit, ARTICLES_DEFAULT_LIMIT, ARTICLES_MAX_LIMIT);
+    let sort = query.sort;
+
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(api_response::ApiResponse::new(400, "`from` must not be after `to`".to_owned()));
+        }
+    }
+
+    if let Some(author_id) = query.author_id {
+        articles = articles.filter(entity::article::Column::UserId.eq(author_id));
+    }
+    if let Some(from) = query.from {
+        articles = articles.filter(entity::article::Column::CreatedAt.gte(locale.to_utc(from)));
+    }
+    if let Some(to) = query.to {
+        articles = articles.filter(entity::article::Column::CreatedAt.lt(locale.to_utc(to)));
+    }
+
+    if let Some(cursor) = &query.cursor {
+        let keys = decode_cursor(cursor, sort.as_str(), 2)?;
+        let created_at = DateTime::from_timestamp_micros(keys[0])
+            .ok_or(api_response::ApiResponse::new(400, "Invalid cursor".to_owned()))?
+            .naive_utc();
+
+        let after = match sort {
+            ArticleSort::Newest => Condition::any()
+                .add(entity::article::Column::CreatedAt.lt(created_at))
+                .add(
+                    Condition::all()
+                        .add(entity::article::Column::CreatedAt.eq(created_at))
+                        .add(entity::article::Column::Id.lt(keys[1])),
+                ),
+            ArticleSort::Oldest => Condition::any()
+                .add(entity::article::Column::CreatedAt.gt(created_at))
+                .add(
+                    Condition::all()
+                        .add(entity::article::Column::CreatedAt.eq(created_at))
+                        .add(entity::article::Column::Id.gt(keys[1])),
+                ),
+        };
+        articles = articles.filter(after);
+    }
+
+    articles = match sort {
+        ArticleSort::Newest => articles
+            .order_by_desc(entity::article::Column::CreatedAt)
+            .order_by_desc(entity::article::Column::Id),
+        ArticleSort::Oldest => articles
+            .order_by_asc(entity::article::Column::CreatedAt)
+            .order_by_asc(entity::article::Column::Id),
+    };
+
+    // One extra row tells whether there is a next page
+    let mut articles = articles
+        .limit(limit + 1)
+        .all(db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let next_cursor = if articles.len() as u64 > limit {
+        articles.truncate(limit as usize);
+        articles.last().map(|article| {
+            encode_cursor(sort.as_str(), &[article.created_at.and_utc().timestamp_micros(), article.id.into()])
+        })
+    } else {
+        None
+    };
+
+    Ok(ArticlePageModel {
+        articles: articles.into_iter().map(|article| article_model(article, locale)).collect(),
+        next_cursor,
+    })
+}
+
+/// Handler for paging through the published articles of all authors.
+///
+/// # Errors
+/// * Returns a `400` error if the cursor or the date range is invalid.
+/// * Returns a `500` error if the database query fails.
 #[get("/all-article")]
 pub async fn all_articles(
     app_state: web::Data<app_state::AppState>,
+    query: web::Query<ArticleListQuery>,
 )-> Result<api_response::ApiResponse, api_response::ApiResponse> {
 
     let db = Arc::clone(&app_state.db);
 
+    let articles = entity::article::Entity::find()
+    .filter(entity::article::Column::Status.eq(ArticleStatus::Published))
+    .filter(entity::article::Column::DeletedAt.is_null());
 
-    let articles: Vec<ArticleModel> = entity::article::Entity::find()
-    .all(&*db).await
-    .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
-    .into_iter()
-    .map(|article: entity::article::Model|
-        ArticleModel {
-            id: article.id,
-            title: article.title,
-            content: article.content,
-            uuid: article.uuid,
-            user_id: article.user_id,
-            created_at: article.created_at,
-            image: article.image,
-            user: None
-        }
-    ).collect();
-    let res_str = serde_json::to_string(&articles)
+    let page = article_page(&db, articles, &query, &UserLocale::default()).await?;
+    let res_str = serde_json::to_string(&page)
     .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
 
     Ok(api_response::ApiResponse::new(200, res_str.to_owned()))
 }
 
+/// Handler for searching the published articles of all authors, most relevant first.
+///
+/// # Errors
+/// * Returns a `400` error if the search has no words or the cursor is invalid.
+/// * Returns a `500` error if the database query fails.
+#[get("/search")]
+pub async fn search_articles(
+    app_state: web::Data<app_state::AppState>,
+    query: web::Query<ArticleSearchQuery>,
+)-> Result<api_response::ApiResponse, api_response::ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+    let limit = page_limit(query.limit, ARTICLES_DEFAULT_LIMIT, ARTICLES_MAX_LIMIT);
+
+    let page = search::search_articles(&*db, &query.q, query.author_id, query.cursor.as_deref(), limit).await?;
+
+    let results = page.hits
+    .into_iter()
+    .map(|hit| SearchResultModel {
+        article: article_model(hit.article, &UserLocale::default()),
+        rank: hit.rank,
+        snippet: hit.snippet,
+    })
+    .collect();
+    let res_str = serde_json::to_string(&ArticleSearchModel { results, next_cursor: page.next_cursor })
+    .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, res_str))
+}
+
 /// Handler for retrieving a single article by its UUID.
+/// Drafts and scheduled articles are not found; authors preview them with `preview_article`.
+/// Deleted articles answer with `410 Gone`, so that links in old emails explain what happened.
 #[get("/get-by-uuid/{article_uuid}")]
 pub async fn one_article(
     app_state: web::Data<app_state::AppState>,
@@ -163,57 +449,254 @@ pub async fn one_article(
 
     let db = Arc::clone(&app_state.db);
 
-    let articles: ArticleModel = entity::article::Entity::find()
-    .filter(entity::article::Column::Uuid.eq(article_uuid.clone()))
+    let (article, author) = entity::article::Entity::find()
+    .filter(entity::article::Column::Uuid.eq(*article_uuid))
     .find_also_related(entity::user::Entity)
     .one(&*db).await
     .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
-    .map(|article|
-        ArticleModel {
-            id: article.0.id,
-            title: article.0.title,
-            content: article.0.content,
-            uuid: article.0.uuid,
-            user_id: article.0.user_id,
-            created_at: article.0.created_at,
-            image: article.0.image,
-            user: article.1.map(|item | UserModel { name: item.name, email: item.email })
-        }
-    )
     .ok_or(api_response::ApiResponse::new(404, "No article Found".to_string()))?;
+
+    if article.deleted_at.is_some() {
+        return Err(api_response::ApiResponse::new(410, "This article has been removed by its author".to_string()));
+    }
+
+    if matches!(article.status, ArticleStatus::Draft | ArticleStatus::Scheduled) {
+        return Err(api_response::ApiResponse::new(404, "No article Found".to_string()));
+    }
+
+    let articles = ArticleModel {
+        user: author.map(|item | UserModel { name: item.name, email: item.email }),
+        ..article_model(article, &UserLocale::default())
+    };
     let res_str = serde_json::to_string(&articles)
     .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
 
     Ok(api_response::ApiResponse::new(200, res_str.to_owned()))
 }
 
-/// Retrieves all articles created by the authenticated user.
+/// Loads the timezone and locale of a user.
+async fn user_locale(
+    db: &sea_orm::DatabaseConnection,
+    user_id: i32,
+) -> Result<UserLocale, api_response::ApiResponse> {
+    Ok(entity::user::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .map(|user| UserLocale::of(&user))
+        .unwrap_or_default())
+}
+
+/// Pages through the articles of the authenticated user in any state, dated in their timezone.
+/// The date range is given in their timezone as well.
 #[get("/my-article")]
 pub async fn my_article(
     app_state: web::Data<app_state::AppState>,
-    claim: Claims
+    claim: Claims,
+    granted_scopes: GrantedScopes,
+    query: web::Query<ArticleListQuery>,
 )-> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require(Scope::ArticleRead)?;
 
     let db = Arc::clone(&app_state.db);
 
-    let articles: Vec<ArticleModel> = entity::article::Entity::find()
-    .filter(entity::article::Column::UserId.eq(claim.id)).all(&*db).await
-    .map_err(|err| api_response::ApiResponse::new(500,err.to_string()))?
-    .into_iter()
-    .map(|article|
-        ArticleModel {
-            id: article.id,
-            title: article.title,
-            content: article.content,
-            uuid: article.uuid,
-            image: article.image,
-            user_id: article.user_id,
-            created_at: article.created_at,
-            user: None
-        }  
-    ).collect();
-    let res_str = serde_json::to_string(&articles)
+    let locale = user_locale(&db, claim.id).await?;
+
+    let articles = entity::article::Entity::find()
+    .filter(entity::article::Column::UserId.eq(claim.id))
+    .filter(entity::article::Column::DeletedAt.is_null());
+
+    let page = article_page(&db, articles, &query, &locale).await?;
+    let res_str = serde_json::to_string(&page)
     .map_err(|err| api_response::ApiResponse::new(500,err.to_string()))?;
 
     Ok(api_response::ApiResponse::new(200, res_str.to_owned()))
-}
\ No newline at end of file
+}
+
+/// Loads an article of the authenticated user for previewing, editing or deleting it.
+///
+/// # Errors
+/// * Returns a `403` error if the article belongs to someone else.
+/// * Returns a `404` error if there is no such article or it was deleted.
+/// * Returns a `500` error if the database lookup fails.
+async fn owned_article(
+    db: &sea_orm::DatabaseConnection,
+    article_uuid: Uuid,
+    user_id: i32,
+) -> Result<entity::article::Model, api_response::ApiResponse> {
+    let article = entity::article::Entity::find()
+        .filter(entity::article::Column::Uuid.eq(article_uuid))
+        .filter(entity::article::Column::DeletedAt.is_null())
+        .one(db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .ok_or(api_response::ApiResponse::new(404, "No article Found".to_owned()))?;
+
+    if article.user_id != user_id {
+        return Err(api_response::ApiResponse::new(403, "You can only change your own articles".to_owned()));
+    }
+
+    Ok(article)
+}
+
+/// Handler for previewing an article of the authenticated user in any state, dated in their timezone.
+///
+/// # Errors
+/// * Returns a `403` error if the article belongs to someone else or the scope is missing.
+/// * Returns a `404` error if there is no such article or it was deleted.
+/// * Returns a `500` error if the database lookup fails.
+#[get("/{article_uuid}")]
+pub async fn preview_article(
+    app_state: web::Data<app_state::AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    article_uuid: web::Path<Uuid>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require(Scope::ArticleRead)?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let article = owned_article(&db, *article_uuid, claims.id).await?;
+    let locale = user_locale(&db, claims.id).await?;
+
+    let res_str = serde_json::to_string(&article_model(article, &locale))
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, res_str))
+}
+
+/// Handler for editing an article of the authenticated user and changing its state.
+///
+/// Drafts and scheduled articles can be (re)scheduled, turned back into drafts or published,
+/// which notifies subscribers. Published articles can be archived and archived ones
+/// republished; neither notifies subscribers again.
+///
+/// # Errors
+/// * Returns a `400` error if the state change or the publication time is not allowed.
+/// * Returns a `403` error if the article belongs to someone else or the scope is missing.
+/// * Returns a `404` error if there is no such article or it was deleted.
+/// * Returns a `409` error if the article changed state, e.g. was published, meanwhile.
+/// * Returns a `500` error if the database update fails.
+#[put("/{article_uuid}")]
+pub async fn update_article(
+    app_state: web::Data<app_state::AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    article_uuid: web::Path<Uuid>,
+    article_model: web::Json<UpdateArticleModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require(Scope::ArticleWrite)?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let article = owned_article(&db, *article_uuid, claims.id).await?;
+    let unpublished = matches!(article.status, ArticleStatus::Draft | ArticleStatus::Scheduled);
+
+    let status = article_model.status.unwrap_or(match article_model.publish_at {
+        Some(_) if unpublished => ArticleStatus::Scheduled,
+        _ => article.status,
+    });
+
+    let publish_at = match (unpublished, status) {
+        (true, ArticleStatus::Draft) => None,
+        (true, ArticleStatus::Scheduled) => match (article_model.publish_at, article.status) {
+            // Keeps the time when only the content of a scheduled article changes
+            (None, ArticleStatus::Scheduled) => article.publish_at,
+            (publish_at, _) => Some(schedule_time(publish_at, &user_locale(&db, claims.id).await?)?),
+        },
+        // Published below, once the content is saved
+        (true, ArticleStatus::Published) => article.publish_at,
+        (true, ArticleStatus::Archived) => {
+            return Err(api_response::ApiResponse::new(400, "Only published articles can be archived".to_owned()));
+        }
+        (false, ArticleStatus::Published | ArticleStatus::Archived) => article.publish_at,
+        (false, ArticleStatus::Draft | ArticleStatus::Scheduled) => {
+            return Err(api_response::ApiResponse::new(
+                400,
+                "Published articles cannot be unpublished, archive them instead".to_owned(),
+            ));
+        }
+    };
+
+    let publish_now = unpublished && status == ArticleStatus::Published;
+    let current_status = article.status;
+
+    let mut active_article: entity::article::ActiveModel = article.into();
+    active_article.title = Set(article_model.title.clone());
+    active_article.content = Set(article_model.content.clone());
+    let rendered = markdown::render(&article_model.content);
+    active_article.content_html = Set(Some(rendered.html));
+    active_article.content_text = Set(Some(rendered.text));
+    active_article.updated_at = Set(Utc::now().naive_local());
+    if !publish_now {
+        active_article.status = Set(status);
+        active_article.publish_at = Set(publish_at);
+    }
+
+    // Fails when the publishing job published the article since it was loaded
+    let updated_article = entity::article::Entity::update(active_article)
+        .filter(entity::article::Column::Status.eq(current_status))
+        .exec(&*db)
+        .await
+        .map_err(|err| match err {
+            sea_orm::DbErr::RecordNotUpdated => {
+                api_response::ApiResponse::new(409, "The article changed meanwhile, reload it".to_owned())
+            }
+            err => api_response::ApiResponse::new(500, err.to_string()),
+        })?;
+
+    if publish_now {
+        let published_article = publishing::publish(&*db, &updated_article)
+            .await
+            .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+        // The article is published, so a failed delivery must not fail the request
+        if let Some(published_article) = published_article {
+            if let Err(err) = newsletter::notify_subscribers(&*db, &published_article).await {
+                eprintln!("Newsletter error for article {}: {}", published_article.id, err);
+            }
+        }
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Article updated successfully".to_owned()))
+}
+
+/// Handler for deleting an article of the authenticated user.
+/// The article is hidden rather than removed, and dropped from queued digests.
+///
+/// # Errors
+/// * Returns a `403` error if the article belongs to someone else or the scope is missing.
+/// * Returns a `404` error if there is no such article or it was already deleted.
+/// * Returns a `500` error if the database update fails.
+#[delete("/{article_uuid}")]
+pub async fn delete_article(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    article_uuid: web::Path<Uuid>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require(Scope::ArticleWrite)?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let article = owned_article(&db, *article_uuid, claims.id).await?;
+    let details = format!("{}: {}", article.uuid, article.title);
+    let article_id = article.id;
+
+    let mut article: entity::article::ActiveModel = article.into();
+    article.deleted_at = Set(Some(Utc::now().naive_local()));
+
+    article
+        .update(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    newsletter::unqueue_article(&*db, article_id)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(&*db, AuditAction::ArticleDeleted, Some(claims.id), Some(claims.id), &client_ip(&req), Some(details)).await;
+
+    Ok(api_response::ApiResponse::new(200, "Article deleted successfully".to_owned()))
+}
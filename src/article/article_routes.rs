@@ -1,7 +1,7 @@
 /// Route configuration for `article` endpoints.
 /// This function defines secure and public routes for article operations.
 use actix_web::{middleware::from_fn, web};
-use crate::middlewares;
+use crate::{middlewares, utils::roles::Permission};
 use super::article_handlers;
 
 /// Configures routes for article-related operations.
@@ -11,22 +11,35 @@ use super::article_handlers;
 /// 
 /// ## Routes:
 /// - **Secure Routes** (`/secure/article`): Require authentication middleware.
-///   - `create_article`: Create a new article.
 ///   - `my_article`: View articles created by the authenticated user.
+///   - `preview_article`: View an own article, including drafts and scheduled ones.
+///   - `update_article`, `delete_article`: Edit, publish, archive or delete an own article.
+///   - `create_article`: Create a new article, requires the `PublishArticles` permission.
 /// 
 /// - **Public Routes** (`/article`): Accessible without authentication.
-///   - `one_article`: View a single article by ID.
-///   - `all_articles`: View all articles.
+///   - `one_article`: View a single published article by ID; deleted articles answer with `410`.
+///   - `all_articles`: Page through the published articles.
+///   - `search_articles`: Search the published articles by their title and text.
 pub fn config(config: &mut web::ServiceConfig) {
     config.service(
         web::scope("secure/article")
             .wrap(from_fn(middlewares::auth_middlewares::check_auth_middleware))
-            .service(article_handlers::create_article)
             .service(article_handlers::my_article)
+            .service(article_handlers::preview_article)
+            .service(article_handlers::update_article)
+            .service(article_handlers::delete_article)
+            .service(
+                web::scope("")
+                    .wrap(from_fn(|req, next| {
+                        middlewares::role_middlewares::require_permission(Permission::PublishArticles, req, next)
+                    }))
+                    .service(article_handlers::create_article)
+            )
     )
     .service(
         web::scope("/article")
             .service(article_handlers::one_article)
             .service(article_handlers::all_articles)
+            .service(article_handlers::search_articles)
     );
 }
@@ -1,18 +1,36 @@
 /// Handlers for authentication endpoints.
-/// This module provides `register` and `login` endpoints for user management.
-use actix_web::{post, web};
+/// This module provides `register`, `login` (with an optional TOTP step), `refresh`, `logout`,
+/// email verification and change, password reset and magic link endpoints for user management.
+use actix_web::{get, post, web, HttpRequest};
+use chrono::{Duration, Utc};
+use entity::audit_event::AuditAction;
+use entity::one_time_token::TokenPurpose;
+use entity::user::UserRole;
+use sea_orm::sea_query::Expr;
 use sea_orm::ActiveModelTrait;
 use sea_orm::ColumnTrait;
+use sea_orm::Condition;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
 use sea_orm::Set;
+use sea_orm::SqlErr;
+use sea_orm::TransactionTrait;
 use serde::Deserialize;
 use serde::Serialize;
-use sha256::digest;
 use std::sync::Arc;
 
+use crate::email::email_service;
 use crate::utils::api_response::ApiResponse;
-use crate::utils::jwt::encode_jwt;
+use crate::utils::audit;
+use crate::utils::auth_tokens::{
+    consume_mfa_token, consume_one_time_token, find_one_time_token, hash_token, issue_one_time_token, issue_refresh_token,
+    revoke_access_token, revoke_all_for_user, revoke_session, start_session, touch_session,
+};
+use crate::utils::contants;
+use crate::utils::jwt::{decode_jwt, decode_mfa_jwt, encode_jwt, encode_mfa_jwt};
+use crate::utils::login_throttle::{client_ip, LoginThrottle};
+use crate::utils::password::{hash_password, verify_dummy_password, verify_password, PasswordCheck};
+use crate::utils::totp::{normalize_recovery_code, verify_code};
 use crate::utils::{api_response, app_state};
 
 /// Request model for user registration.
@@ -30,6 +48,39 @@ pub struct LoginModel {
     pub password: String,
 }
 
+/// Request model carrying a refresh token, used by refresh and logout.
+#[derive(Serialize, Deserialize)]
+pub struct RefreshTokenModel {
+    pub refresh_token: String,
+}
+
+/// Request model carrying only an email address.
+#[derive(Serialize, Deserialize)]
+pub struct EmailModel {
+    pub email: String,
+}
+
+/// Request model for the second step of a two-factor login.
+/// `code` is either a TOTP code or an unused recovery code.
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorLoginModel {
+    pub mfa_token: String,
+    pub code: String,
+}
+
+/// Request model for setting a new password with a reset token.
+#[derive(Serialize, Deserialize)]
+pub struct ResetPasswordModel {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Request model carrying a token from an emailed link, in the query string or body.
+#[derive(Serialize, Deserialize)]
+pub struct TokenQuery {
+    pub token: String,
+}
+
 /// Endpoint to register a new user.
 /// Creates a new user record and returns their ID.
 #[post("/register")]
@@ -61,16 +112,24 @@ pub async fn register(
         return Err(ApiResponse::new(400, "Name cannot be empty".to_owned()));
     }
 
+    let password_hash = hash_password(&register_json.password)
+        .map_err(|err| ApiResponse::new(500, err))?;
+
     let user_model = entity::user::ActiveModel {
         name: Set(register_json.name.clone()),
         email: Set(register_json.email.clone()),
-        password: Set(digest(&register_json.password)), // Hashing the password
+        password: Set(password_hash),
+        role: Set(UserRole::Author),
         ..Default::default()
     }
     .insert(&*db)
     .await
     .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
+    send_verification(&db, &user_model)
+        .await
+        .map_err(|err| ApiResponse::new(500, err))?;
+
     // Return user details as a JSON response
     Ok(api_response::ApiResponse::new(
         200,
@@ -81,9 +140,12 @@ pub async fn register(
 
 /// Endpoint to log in an existing user.
 /// Validates user credentials and returns a JWT token.
+/// Unknown emails and wrong passwords get the same `401` response; repeated failures
+/// are throttled per email address and client IP, see `utils::login_throttle`.
 #[post("/login")]
 pub async fn login(
     app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
     login_json: web::Json<LoginModel>,
 ) -> Result<ApiResponse, ApiResponse> {
 
@@ -94,6 +156,16 @@ pub async fn login(
         return Err(ApiResponse::new(400, "Invalid email format".to_owned()));
     }
 
+    let client_ip = client_ip(&req);
+
+    let throttle = LoginThrottle::load(&*db, &login_json.email, &client_ip)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if throttle.locked_until().is_some() {
+        return Err(ApiResponse::new(429, "Too many failed login attempts, try again later".to_owned()));
+    }
+
     // Check if the user exists
     let user_data = entity::user::Entity::find()
         .filter(entity::user::Column::Email.eq(&login_json.email))
@@ -101,22 +173,776 @@ pub async fn login(
         .await
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
-    if user_data.is_none() {
-        return Err(ApiResponse::new(404, "User not found".to_owned()));
+    // Validate password, spending the same time when the user does not exist
+    let password_check = match &user_data {
+        Some(user_data) => verify_password(&login_json.password, &user_data.password)
+            .map_err(|err| ApiResponse::new(500, err))?,
+        None => {
+            verify_dummy_password(&login_json.password);
+            PasswordCheck::Invalid
+        }
+    };
+
+    let user_data = match user_data {
+        Some(user_data) if password_check != PasswordCheck::Invalid => user_data,
+        user_data => {
+            let account_locked = throttle
+                .record_failure(&*db)
+                .await
+                .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+            audit::record(
+                &*db,
+                AuditAction::LoginFailed,
+                user_data.as_ref().map(|user_data| user_data.id),
+                None,
+                &client_ip,
+                Some(login_json.email.clone()),
+            )
+            .await;
+
+            if let Some(user_data) = user_data.filter(|_| account_locked) {
+                notify_lockout(&user_data, &client_ip).await;
+            }
+
+            return Err(ApiResponse::new(401, "Invalid email or password".to_owned()));
+        }
+    };
+
+    throttle
+        .record_success(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Transparently upgrade legacy or outdated password hashes
+    if password_check == PasswordCheck::ValidNeedsRehash {
+        if let Err(err) = rehash_password(&db, &user_data, &login_json.password).await {
+            eprintln!("Password rehash error: {}", err);
+        }
+    }
+
+    complete_login(&db, &req, user_data).await
+}
+
+/// Tells a user that their account was locked out after too many failed logins.
+async fn notify_lockout(user_data: &entity::user::Model, client_ip: &str) {
+    if let Err(err) = email_service::send_account_locked_email(
+        &user_data.email,
+        &user_data.name,
+        *contants::LOGIN_LOCKOUT_MINUTES,
+        client_ip,
+    )
+    .await
+    {
+        eprintln!("Lockout notification error: {}", err);
+    }
+}
+
+/// Endpoint to complete a two-factor login.
+/// Exchanges the "mfa pending" token and a TOTP or recovery code for the session tokens.
+/// Each "mfa pending" token allows one attempt, each TOTP code is accepted once, and failed
+/// attempts count towards the same lockout as wrong passwords.
+#[post("/login/2fa")]
+pub async fn login_two_factor(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    two_factor_json: web::Json<TwoFactorLoginModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let mfa_claims = decode_mfa_jwt(&two_factor_json.mfa_token)
+        .map_err(|_| ApiResponse::new(401, "Invalid or expired MFA token".to_owned()))?
+        .claims;
+
+    let is_unused_token = consume_mfa_token(&*db, &mfa_claims)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if !is_unused_token {
+        return Err(ApiResponse::new(401, "Invalid or expired MFA token".to_owned()));
+    }
+
+    let user_data = entity::user::Entity::find_by_id(mfa_claims.user_id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid or expired MFA token".to_owned()))?;
+
+    let client_ip = client_ip(&req);
+
+    let throttle = LoginThrottle::load(&*db, &user_data.email, &client_ip)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if throttle.locked_until().is_some() {
+        return Err(ApiResponse::new(429, "Too many failed login attempts, try again later".to_owned()));
+    }
+
+    let totp_secret = user_data
+        .totp_secret
+        .clone()
+        .filter(|_| user_data.totp_enabled_at.is_some())
+        .ok_or(ApiResponse::new(400, "Two-factor authentication is not enabled".to_owned()))?;
+
+    let code_step = verify_code(&totp_secret, &user_data.email, &two_factor_json.code)
+        .map_err(|err| ApiResponse::new(500, err))?;
+
+    let is_valid_code = match code_step {
+        Some(code_step) => use_totp_step(&db, user_data.id, code_step)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?,
+        None => false,
+    };
+
+    if !is_valid_code {
+        let is_valid_recovery_code = use_recovery_code(&db, user_data.id, &two_factor_json.code)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        if !is_valid_recovery_code {
+            let account_locked = throttle
+                .record_failure(&*db)
+                .await
+                .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+            audit::record(
+                &*db,
+                AuditAction::LoginFailed,
+                Some(user_data.id),
+                None,
+                &client_ip,
+                Some("Invalid two-factor code".to_owned()),
+            )
+            .await;
+
+            if account_locked {
+                notify_lockout(&user_data, &client_ip).await;
+            }
+
+            return Err(ApiResponse::new(401, "Invalid two-factor code".to_owned()));
+        }
+    }
+
+    throttle
+        .record_success(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    login_response(&db, &req, user_data).await
+}
+
+/// Endpoint to exchange a refresh token for a new access token.
+/// The presented refresh token is rotated; reusing an already rotated token
+/// ends its whole session.
+#[post("/refresh")]
+pub async fn refresh(
+    app_state: web::Data<app_state::AppState>,
+    refresh_json: web::Json<RefreshTokenModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let stored_token = entity::refresh_token::Entity::find()
+        .filter(entity::refresh_token::Column::TokenHash.eq(hash_token(&refresh_json.refresh_token)))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid refresh token".to_owned()))?;
+
+    // A rotated token being presented again means it leaked, so drop the whole family
+    if stored_token.revoked_at.is_some() {
+        revoke_session(&*db, stored_token.family_id)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        return Err(ApiResponse::new(401, "Refresh token reuse detected".to_owned()));
+    }
+
+    if stored_token.expires_at < Utc::now().naive_local() {
+        return Err(ApiResponse::new(401, "Refresh token expired".to_owned()));
+    }
+
+    let user_data = entity::user::Entity::find_by_id(stored_token.user_id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid refresh token".to_owned()))?;
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let family_id = stored_token.family_id;
+
+    // Only the request that flips `revoked_at` rotates the token; a concurrent request with
+    // the same token, or one racing the end of the session, counts as reuse
+    let rotated = entity::refresh_token::Entity::update_many()
+        .col_expr(entity::refresh_token::Column::RevokedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::refresh_token::Column::Id.eq(stored_token.id))
+        .filter(entity::refresh_token::Column::RevokedAt.is_null())
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if rotated.rows_affected == 0 {
+        txn.rollback()
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        revoke_session(&*db, family_id)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+        return Err(ApiResponse::new(401, "Refresh token reuse detected".to_owned()));
+    }
+
+    let refresh_token = issue_refresh_token(&txn, user_data.id, family_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    touch_session(&txn, family_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let token = encode_jwt(user_data.email, user_data.id, user_data.role, family_id)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        format!("{{'token': '{}', 'refresh_token': '{}'}}", token, refresh_token),
+    ))
+}
+
+/// Endpoint to log out.
+/// Ends the session of the refresh token and, when a bearer token is sent along,
+/// adds the access token to the revocation list.
+#[post("/logout")]
+pub async fn logout(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    logout_json: web::Json<RefreshTokenModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let stored_token = entity::refresh_token::Entity::find()
+        .filter(entity::refresh_token::Column::TokenHash.eq(hash_token(&logout_json.refresh_token)))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if let Some(stored_token) = stored_token {
+        revoke_session(&*db, stored_token.family_id)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    let bearer_token = req
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|auth| auth.to_str().ok())
+        .map(|auth| auth.replace("Bearer", "").trim().to_owned());
+
+    if let Some(claim) = bearer_token.and_then(|token| decode_jwt(token).ok()) {
+        revoke_access_token(&*db, &claim.claims)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Logged out successfully".to_owned()))
+}
+
+/// Replaces the stored password hash of a user with a fresh Argon2id hash.
+async fn rehash_password(
+    db: &sea_orm::DatabaseConnection,
+    user_data: &entity::user::Model,
+    password: &str,
+) -> Result<(), String> {
+    let mut user_model: entity::user::ActiveModel = user_data.clone().into();
+    user_model.password = Set(hash_password(password)?);
+    user_model.update(db).await.map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Endpoint to verify an email address.
+/// Consumes the single-use token sent by email on registration.
+#[get("/verify-email")]
+pub async fn verify_email(
+    app_state: web::Data<app_state::AppState>,
+    token_query: web::Query<TokenQuery>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let verification_token = consume_one_time_token(&*db, &token_query.token, TokenPurpose::EmailVerification)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(400, "Invalid or expired verification link".to_owned()))?;
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::EmailVerifiedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::user::Column::Id.eq(verification_token.user_id))
+        .filter(entity::user::Column::EmailVerifiedAt.is_null())
+        .exec(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, "Email verified successfully".to_owned()))
+}
+
+/// Endpoint to confirm a new email address.
+/// Consumes the single-use token sent to the new address and tells the previous address.
+#[get("/confirm-email-change")]
+pub async fn confirm_email_change(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    token_query: web::Query<TokenQuery>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let change_token = consume_one_time_token(&*db, &token_query.token, TokenPurpose::EmailChange)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(400, "Invalid or expired confirmation link".to_owned()))?;
+
+    let user_data = entity::user::Entity::find_by_id(change_token.user_id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let Some((user_data, new_email)) = user_data
+        .and_then(|user_data| user_data.pending_email.clone().map(|new_email| (user_data, new_email)))
+    else {
+        return Err(ApiResponse::new(400, "Invalid or expired confirmation link".to_owned()));
+    };
+
+    // The address may have been registered since the change was requested
+    let existing_user = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&new_email))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if existing_user.is_some() {
+        return Err(ApiResponse::new(409, "Email already registered".to_owned()));
+    }
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Email, Expr::value(&new_email))
+        .col_expr(entity::user::Column::PendingEmail, Expr::value(None::<String>))
+        .col_expr(entity::user::Column::EmailVerifiedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::user::Column::Id.eq(user_data.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| match err.sql_err() {
+            // Another account took the address since the check above
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                ApiResponse::new(409, "Email already registered".to_owned())
+            }
+            _ => ApiResponse::new(500, err.to_string()),
+        })?;
+
+    audit::record(
+        &*db,
+        AuditAction::EmailChanged,
+        Some(user_data.id),
+        Some(user_data.id),
+        &client_ip(&req),
+        Some(format!("{} -> {}", user_data.email, new_email)),
+    )
+    .await;
+
+    if let Err(err) = email_service::send_email_changed_email(&user_data.email, &user_data.name, &new_email).await {
+        eprintln!("Email change notification error: {}", err);
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Email address changed successfully".to_owned()))
+}
+
+/// Endpoint to resend the email verification link.
+/// Always answers the same way so it cannot be used to probe for accounts.
+#[post("/resend-verification")]
+pub async fn resend_verification(
+    app_state: web::Data<app_state::AppState>,
+    email_json: web::Json<EmailModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_data = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&email_json.email))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if let Some(user_data) = user_data.filter(|user| user.email_verified_at.is_none()) {
+        send_verification(&db, &user_data)
+            .await
+            .map_err(|err| ApiResponse::new(500, err))?;
+    }
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        "If the account exists and is not verified yet, a verification email has been sent".to_owned(),
+    ))
+}
+
+/// Issues a verification token for a user and emails them the link.
+/// Delivery failures are logged, since the user can ask for the link again.
+async fn send_verification(
+    db: &sea_orm::DatabaseConnection,
+    user_data: &entity::user::Model,
+) -> Result<(), String> {
+    let token = issue_one_time_token(
+        db,
+        user_data.id,
+        TokenPurpose::EmailVerification,
+        Duration::hours(*contants::EMAIL_VERIFICATION_TTL_HOURS),
+    )
+    .await
+    .map_err(|err| err.to_string())?;
+
+    let verification_link = format!("{}/auth/verify-email?token={}", *contants::APP_BASE_URL, token);
+
+    if let Err(err) = email_service::send_verification_email(&user_data.email, &user_data.name, &verification_link).await {
+        eprintln!("Verification email error: {}", err);
+    }
+
+    Ok(())
+}
+
+/// Builds the emailed password reset link, to the frontend if there is one.
+pub fn password_reset_link(token: &str) -> String {
+    match contants::FRONTEND_BASE_URL.as_deref() {
+        Some(frontend_base_url) => format!("{}/reset-password?token={}", frontend_base_url, token),
+        None => format!("{}/auth/reset-password?token={}", *contants::APP_BASE_URL, token),
+    }
+}
+
+/// Endpoint to request a password reset link.
+/// Always answers the same way so it cannot be used to probe for accounts.
+#[post("/forgot-password")]
+pub async fn forgot_password(
+    app_state: web::Data<app_state::AppState>,
+    email_json: web::Json<EmailModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_data = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&email_json.email))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if let Some(user_data) = user_data {
+        let token = issue_one_time_token(
+            &*db,
+            user_data.id,
+            TokenPurpose::PasswordReset,
+            Duration::minutes(*contants::PASSWORD_RESET_TTL_MINUTES),
+        )
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        let reset_link = password_reset_link(&token);
+
+        // Sent in the background, so that the response time does not tell whether the account exists
+        actix_web::rt::spawn(async move {
+            if let Err(err) = email_service::send_password_reset_email(&user_data.email, &user_data.name, &reset_link).await {
+                eprintln!("Password reset email error: {}", err);
+            }
+        });
+    }
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        "If an account exists for this email, a password reset link has been sent".to_owned(),
+    ))
+}
+
+/// Endpoint opened by the emailed password reset link.
+/// Checks the token without using it up; the new password is then posted with it.
+#[get("/reset-password")]
+pub async fn check_reset_token(
+    app_state: web::Data<app_state::AppState>,
+    token_query: web::Query<TokenQuery>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    find_one_time_token(&*db, &token_query.token, TokenPurpose::PasswordReset)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(400, "Invalid or expired reset link".to_owned()))?;
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        "Reset link is valid, post the new password with the token to /auth/reset-password".to_owned(),
+    ))
+}
+
+/// Endpoint to set a new password with a reset token.
+/// Consumes the token and ends every existing session of the user.
+#[post("/reset-password")]
+pub async fn reset_password(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    reset_json: web::Json<ResetPasswordModel>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    if reset_json.new_password.is_empty() {
+        return Err(ApiResponse::new(400, "Password cannot be empty".to_owned()));
+    }
+
+    let password_hash = hash_password(&reset_json.new_password)
+        .map_err(|err| ApiResponse::new(500, err))?;
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let reset_token = consume_one_time_token(&txn, &reset_json.token, TokenPurpose::PasswordReset)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(400, "Invalid or expired reset link".to_owned()))?;
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Password, Expr::value(password_hash))
+        .filter(entity::user::Column::Id.eq(reset_token.user_id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    revoke_all_for_user(&txn, reset_token.user_id, None)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(
+        &*db,
+        AuditAction::PasswordReset,
+        Some(reset_token.user_id),
+        Some(reset_token.user_id),
+        &client_ip(&req),
+        None,
+    )
+    .await;
+
+    Ok(api_response::ApiResponse::new(200, "Password reset successfully".to_owned()))
+}
+
+/// Builds the emailed magic login link, to the frontend if there is one.
+pub fn magic_link(token: &str) -> String {
+    match contants::FRONTEND_BASE_URL.as_deref() {
+        Some(frontend_base_url) => format!("{}/magic-link?token={}", frontend_base_url, token),
+        None => format!("{}/auth/magic-link?token={}", *contants::APP_BASE_URL, token),
     }
+}
+
+/// Endpoint to request a magic login link.
+/// Always answers the same way so it cannot be used to probe for accounts.
+#[post("/magic-link")]
+pub async fn request_magic_link(
+    app_state: web::Data<app_state::AppState>,
+    email_json: web::Json<EmailModel>,
+) -> Result<ApiResponse, ApiResponse> {
 
-    let user_data = user_data.unwrap();
+    let db = Arc::clone(&app_state.db);
 
-    // Validate password
-    if user_data.password != digest(&login_json.password) {
-        return Err(ApiResponse::new(401, "Incorrect password".to_owned()));
+    let user_data = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&email_json.email))
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if let Some(user_data) = user_data {
+        let token = issue_one_time_token(
+            &*db,
+            user_data.id,
+            TokenPurpose::MagicLink,
+            Duration::minutes(*contants::MAGIC_LINK_TTL_MINUTES),
+        )
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        let login_link = magic_link(&token);
+
+        // Sent in the background, so that the response time does not tell whether the account exists
+        actix_web::rt::spawn(async move {
+            if let Err(err) = email_service::send_magic_link_email(&user_data.email, &user_data.name, &login_link).await {
+                eprintln!("Magic link email error: {}", err);
+            }
+        });
     }
 
-    let token = encode_jwt(user_data.email, user_data.id)
+    Ok(api_response::ApiResponse::new(
+        200,
+        "If an account exists for this email, a login link has been sent".to_owned(),
+    ))
+}
+
+/// Endpoint opened by the emailed magic login link.
+/// Checks the token without using it up, so that link previews by mail scanners do not spend it;
+/// the login itself posts the token to `/auth/magic-link/consume`.
+#[get("/magic-link")]
+pub async fn check_magic_link(
+    app_state: web::Data<app_state::AppState>,
+    token_query: web::Query<TokenQuery>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    find_one_time_token(&*db, &token_query.token, TokenPurpose::MagicLink)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid or expired login link".to_owned()))?;
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        "Login link is valid, post the token to /auth/magic-link/consume to log in".to_owned(),
+    ))
+}
+
+/// Endpoint to log in with a magic link token.
+/// Consumes the token and completes the login like a password login would.
+/// Following the link proves ownership of the email address, so it also verifies it.
+#[post("/magic-link/consume")]
+pub async fn consume_magic_link(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    token_json: web::Json<TokenQuery>,
+) -> Result<ApiResponse, ApiResponse> {
+
+    let db = Arc::clone(&app_state.db);
+
+    let magic_link_token = consume_one_time_token(&*db, &token_json.token, TokenPurpose::MagicLink)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid or expired login link".to_owned()))?;
+
+    let user_data = entity::user::Entity::find_by_id(magic_link_token.user_id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(401, "Invalid or expired login link".to_owned()))?;
+
+    if user_data.email_verified_at.is_none() {
+        entity::user::Entity::update_many()
+            .col_expr(entity::user::Column::EmailVerifiedAt, Expr::value(Utc::now().naive_local()))
+            .filter(entity::user::Column::Id.eq(user_data.id))
+            .exec(&*db)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    complete_login(&db, &req, user_data).await
+}
+
+/// Completes a login once the user has proven who they are.
+/// Accounts with two-factor authentication get an "mfa pending" token instead of the session tokens.
+pub async fn complete_login(
+    db: &sea_orm::DatabaseConnection,
+    req: &HttpRequest,
+    user_data: entity::user::Model,
+) -> Result<ApiResponse, ApiResponse> {
+    if user_data.totp_enabled_at.is_some() {
+        let mfa_token = encode_mfa_jwt(user_data.id)
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+        return Ok(api_response::ApiResponse::new(
+            200,
+            format!("{{'mfa_required': true, 'mfa_token': '{}'}}", mfa_token),
+        ));
+    }
+
+    login_response(db, req, user_data).await
+}
+
+/// Starts a session and issues the access and refresh tokens that complete a login.
+async fn login_response(
+    db: &sea_orm::DatabaseConnection,
+    req: &HttpRequest,
+    user_data: entity::user::Model,
+) -> Result<ApiResponse, ApiResponse> {
+    let user_agent = req
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.to_string());
+
+    let client_ip = client_ip(req);
+
+    let family_id = start_session(db, user_data.id, user_agent.clone(), client_ip.clone())
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let refresh_token = issue_refresh_token(db, user_data.id, family_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(db, AuditAction::LoginSucceeded, Some(user_data.id), Some(user_data.id), &client_ip, user_agent).await;
+
+    let token = encode_jwt(user_data.email, user_data.id, user_data.role, family_id)
         .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
     Ok(api_response::ApiResponse::new(
         200,
-        format!("{{'token': '{}'}}", token),
+        format!("{{'token': '{}', 'refresh_token': '{}'}}", token, refresh_token),
     ))
 }
+
+/// Records the time step of a TOTP code accepted at login.
+///
+/// # Returns
+/// * `Ok(true)` - If the step is newer than the last accepted one.
+/// * `Ok(false)` - If a code of this or a later step was already accepted, i.e. the code is replayed.
+async fn use_totp_step(
+    db: &sea_orm::DatabaseConnection,
+    user_id: i32,
+    step: i64,
+) -> Result<bool, sea_orm::DbErr> {
+    let result = entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::TotpLastStep, Expr::value(step))
+        .filter(entity::user::Column::Id.eq(user_id))
+        .filter(
+            Condition::any()
+                .add(entity::user::Column::TotpLastStep.is_null())
+                .add(entity::user::Column::TotpLastStep.lt(step)),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// Marks an unused recovery code of a user as used.
+///
+/// # Returns
+/// * `Ok(true)` - If the code was valid and has now been used up.
+/// * `Ok(false)` - If the code is unknown or was already used.
+async fn use_recovery_code(
+    db: &sea_orm::DatabaseConnection,
+    user_id: i32,
+    code: &str,
+) -> Result<bool, sea_orm::DbErr> {
+    let result = entity::recovery_code::Entity::update_many()
+        .col_expr(entity::recovery_code::Column::UsedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::recovery_code::Column::UserId.eq(user_id))
+        .filter(entity::recovery_code::Column::CodeHash.eq(hash_token(&normalize_recovery_code(code))))
+        .filter(entity::recovery_code::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
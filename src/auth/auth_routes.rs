@@ -1,13 +1,31 @@
 /// Routes for authentication endpoints.
 /// This module configures `/auth` routes and connects them to handlers.
-use actix_web::web;
-use super::auth_handlers;
+use actix_web::{middleware::from_fn, web};
+use crate::middlewares;
+use super::{auth_handlers, oauth_handlers, session_handlers, two_factor_handlers};
 
 /// Configures authentication routes.
 /// 
 /// # Routes:
 /// - `/auth/register`: User registration handler.
 /// - `/auth/login`: User login handler.
+/// - `/auth/login/2fa`: Second login step for accounts with two-factor authentication.
+/// - `/auth/refresh`: Refresh token rotation handler.
+/// - `/auth/logout`: Logout handler revoking the session tokens.
+/// - `/auth/verify-email`: Email verification link handler.
+/// - `/auth/confirm-email-change`: Confirms a new email address from the emailed link.
+/// - `/auth/resend-verification`: Resends the email verification link.
+/// - `/auth/forgot-password`: Emails a password reset link.
+/// - `/auth/reset-password`: Checks a reset link (GET) and sets a new password with its token (POST).
+/// - `/auth/magic-link`: Checks a login link (GET) and emails a single-use login link (POST).
+/// - `/auth/magic-link/consume`: Logs in with a magic link token.
+/// - `/auth/oauth/{provider}`: Starts signing in with an OpenID Connect provider.
+/// - `/auth/oauth/{provider}/callback`: Completes signing in with an OpenID Connect provider.
+/// - `/secure/auth/2fa/setup`: Starts TOTP enrollment (requires authentication).
+/// - `/secure/auth/2fa/confirm`: Confirms TOTP enrollment (requires authentication).
+/// - `/secure/auth/sessions`: Lists active sessions (requires authentication).
+/// - `/secure/auth/sessions/revoke-others`: Ends all other sessions (requires authentication).
+/// - `/secure/auth/sessions/{id}`: Ends one session (requires authentication).
 /// 
 /// # Arguments:
 /// * `config` - Actix Web `ServiceConfig` to which routes are added.
@@ -16,5 +34,28 @@ pub fn config(config: &mut web::ServiceConfig) {
         web::scope("/auth")
         .service(auth_handlers::register)
         .service(auth_handlers::login)
+        .service(auth_handlers::login_two_factor)
+        .service(auth_handlers::refresh)
+        .service(auth_handlers::logout)
+        .service(auth_handlers::verify_email)
+        .service(auth_handlers::confirm_email_change)
+        .service(auth_handlers::resend_verification)
+        .service(auth_handlers::forgot_password)
+        .service(auth_handlers::check_reset_token)
+        .service(auth_handlers::reset_password)
+        .service(auth_handlers::check_magic_link)
+        .service(auth_handlers::request_magic_link)
+        .service(auth_handlers::consume_magic_link)
+        .service(oauth_handlers::oauth_start)
+        .service(oauth_handlers::oauth_callback)
+    )
+    .service(
+        web::scope("/secure/auth")
+        .wrap(from_fn(middlewares::auth_middlewares::check_auth_middleware))
+        .service(two_factor_handlers::setup_two_factor)
+        .service(two_factor_handlers::confirm_two_factor)
+        .service(session_handlers::my_sessions)
+        .service(session_handlers::revoke_other_sessions)
+        .service(session_handlers::revoke_one_session)
     );
 }
\ No newline at end of file
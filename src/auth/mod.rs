@@ -6,3 +6,13 @@ pub mod auth_handlers;
 
 /// Module for authentication route configurations.
 pub mod auth_routes;
+
+/// Module for two-factor authentication enrollment handlers.
+pub mod two_factor_handlers;
+
+
+/// Module for OpenID Connect sign-in handlers.
+pub mod oauth_handlers;
+
+/// Module for listing and revoking login sessions.
+pub mod session_handlers;
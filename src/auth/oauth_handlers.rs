@@ -0,0 +1,216 @@
+/// Handlers for OpenID Connect sign-in.
+/// This module implements the authorization code flow with PKCE: the user is redirected to
+/// the provider and, on return, linked to an existing account or registered from their
+/// verified email address.
+use actix_web::{
+    cookie::{time, Cookie, SameSite},
+    get,
+    http::header::LOCATION,
+    web, CustomizeResponder, HttpRequest, HttpResponse, Responder,
+};
+use chrono::Utc;
+use entity::user::UserRole;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::auth::auth_handlers::complete_login;
+use crate::utils::{
+    api_response::ApiResponse,
+    app_state::AppState,
+    auth_tokens::generate_token,
+    contants,
+    jwt::{decode_oauth_state_jwt, encode_oauth_state_jwt},
+    oauth::{pkce_challenge, OAuthProvider, UserInfo},
+};
+
+/// Name of the cookie holding the sign-in state between the redirect and the callback.
+const OAUTH_STATE_COOKIE: &str = "oauth_state";
+
+/// Query model of the provider's redirect back to the callback.
+#[derive(Serialize, Deserialize)]
+pub struct OAuthCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Endpoint to start signing in with a provider.
+/// Redirects to the provider's authorization page and stores the state and PKCE
+/// verifier in a short-lived signed cookie.
+#[get("/oauth/{provider}")]
+pub async fn oauth_start(provider: web::Path<String>) -> Result<HttpResponse, ApiResponse> {
+    let provider = OAuthProvider::from_env(&provider)
+        .ok_or(ApiResponse::new(404, "Unknown sign-in provider".to_owned()))?;
+
+    let state = generate_token();
+    let code_verifier = generate_token();
+
+    let authorization_url = provider
+        .authorization_url(&state, &pkce_challenge(&code_verifier))
+        .map_err(|err| ApiResponse::new(500, err))?;
+
+    let state_token = encode_oauth_state_jwt(provider.name.clone(), state, code_verifier)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let cookie = Cookie::build(OAUTH_STATE_COOKIE, state_token)
+        .path("/auth/oauth")
+        .http_only(true)
+        .secure(contants::APP_BASE_URL.starts_with("https://"))
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::minutes(*contants::OAUTH_STATE_TTL_MINUTES))
+        .finish();
+
+    Ok(HttpResponse::Found()
+        .insert_header((LOCATION, authorization_url))
+        .cookie(cookie)
+        .finish())
+}
+
+/// Endpoint the provider redirects back to.
+/// Exchanges the code, links or creates the user from the verified email address
+/// and completes the login like `/auth/login`.
+#[get("/oauth/{provider}/callback")]
+pub async fn oauth_callback(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    provider: web::Path<String>,
+    query: web::Query<OAuthCallbackQuery>,
+) -> Result<CustomizeResponder<ApiResponse>, ApiResponse> {
+    let db = Arc::clone(&app_state.db);
+
+    let provider = OAuthProvider::from_env(&provider)
+        .ok_or(ApiResponse::new(404, "Unknown sign-in provider".to_owned()))?;
+
+    if query.error.is_some() {
+        return Err(ApiResponse::new(400, "Sign-in was cancelled or failed at the provider".to_owned()));
+    }
+
+    let state_claims = req
+        .cookie(OAUTH_STATE_COOKIE)
+        .and_then(|cookie| decode_oauth_state_jwt(cookie.value()).ok())
+        .ok_or(ApiResponse::new(400, "Sign-in session is missing or has expired".to_owned()))?
+        .claims;
+
+    if state_claims.provider != provider.name || query.state.as_deref() != Some(state_claims.state.as_str()) {
+        return Err(ApiResponse::new(400, "Invalid sign-in state".to_owned()));
+    }
+
+    let code = query
+        .code
+        .as_deref()
+        .ok_or(ApiResponse::new(400, "Missing authorization code".to_owned()))?;
+
+    let access_token = provider
+        .exchange_code(code, &state_claims.code_verifier)
+        .await
+        .map_err(|err| ApiResponse::new(502, err))?;
+
+    let user_info = provider
+        .fetch_userinfo(&access_token)
+        .await
+        .map_err(|err| ApiResponse::new(502, err))?;
+
+    let user_data = find_or_create_user(&db, &provider.name, user_info).await?;
+
+    let removal_cookie = Cookie::build(OAUTH_STATE_COOKIE, "")
+        .path("/auth/oauth")
+        .max_age(time::Duration::ZERO)
+        .finish();
+
+    Ok(complete_login(&db, &req, user_data).await?.customize().add_cookie(&removal_cookie))
+}
+
+/// Resolves the user signing in with a provider account.
+///
+/// Known provider accounts map to their linked user. Otherwise the verified email decides:
+/// a verified local account with that email gets linked, and a new account is created
+/// when there is none.
+///
+/// # Errors
+/// * Returns a `403` error if the provider did not vouch for the email address.
+/// * Returns a `409` error if the email belongs to a local account that was never verified.
+async fn find_or_create_user(
+    db: &sea_orm::DatabaseConnection,
+    provider: &str,
+    user_info: UserInfo,
+) -> Result<entity::user::Model, ApiResponse> {
+    let linked_user = entity::oauth_identity::Entity::find()
+        .filter(entity::oauth_identity::Column::Provider.eq(provider))
+        .filter(entity::oauth_identity::Column::Subject.eq(&user_info.sub))
+        .find_also_related(entity::user::Entity)
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .and_then(|(_, user_data)| user_data);
+
+    if let Some(user_data) = linked_user {
+        return Ok(user_data);
+    }
+
+    let email = user_info
+        .email
+        .filter(|_| user_info.email_verified)
+        .ok_or(ApiResponse::new(403, "The provider did not return a verified email address".to_owned()))?;
+
+    let existing_user = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&email))
+        .one(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // Linking to an unverified account would let whoever registered it first keep access
+    if existing_user.as_ref().is_some_and(|user_data| user_data.email_verified_at.is_none()) {
+        return Err(ApiResponse::new(
+            409,
+            "An unverified account uses this email address; verify it or reset its password first".to_owned(),
+        ));
+    }
+
+    let now = Utc::now().naive_local();
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let user_data = match existing_user {
+        Some(user_data) => user_data,
+        None => {
+            let name = user_info
+                .name
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or(email.split('@').next().unwrap_or_default().to_string());
+
+            // An empty password matches no hash; a password can be set via reset
+            entity::user::ActiveModel {
+                name: Set(name),
+                email: Set(email),
+                password: Set(String::new()),
+                email_verified_at: Set(Some(now)),
+                role: Set(UserRole::Author),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        }
+    };
+
+    entity::oauth_identity::ActiveModel {
+        user_id: Set(user_data.id),
+        provider: Set(provider.to_string()),
+        subject: Set(user_info.sub),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(user_data)
+}
@@ -0,0 +1,116 @@
+/// Handlers for managing login sessions.
+/// This module lists the devices a user is logged in from and ends sessions remotely.
+use actix_web::{delete, get, post, web};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::utils::{
+    api_response::ApiResponse,
+    app_state::AppState,
+    auth_tokens::{revoke_all_for_user, revoke_session},
+    jwt::Claims,
+    scopes::GrantedScopes,
+};
+
+/// Response model describing a session.
+#[derive(Serialize, Deserialize)]
+pub struct SessionModel {
+    pub id: i32,
+    pub user_agent: Option<String>,
+    pub ip_address: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_seen_at: chrono::NaiveDateTime,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}
+
+/// Endpoint to list the active sessions of the authenticated user, most recently used first.
+#[get("/sessions")]
+pub async fn my_sessions(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let sessions = entity::session::Entity::find()
+        .filter(entity::session::Column::UserId.eq(claims.id))
+        .filter(entity::session::Column::RevokedAt.is_null())
+        .order_by_desc(entity::session::Column::LastSeenAt)
+        .all(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let session_list: Vec<SessionModel> = sessions
+        .into_iter()
+        .map(|session| SessionModel {
+            current: claims.sid.as_deref() == Some(session.family_id.to_string().as_str()),
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        })
+        .collect();
+
+    let res_str = serde_json::to_string(&session_list)
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, res_str))
+}
+
+/// Endpoint to end every session of the authenticated user except the current one.
+#[post("/sessions/revoke-others")]
+pub async fn revoke_other_sessions(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let current_session = claims
+        .sid
+        .as_deref()
+        .and_then(|sid| Uuid::parse_str(sid).ok())
+        .ok_or(ApiResponse::new(400, "Token is not tied to a session".to_owned()))?;
+
+    let db = Arc::clone(&app_state.db);
+
+    revoke_all_for_user(&*db, claims.id, Some(current_session))
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, "Other sessions revoked".to_owned()))
+}
+
+/// Endpoint to end one session of the authenticated user.
+/// Access tokens of the session are rejected from then on.
+#[delete("/sessions/{id}")]
+pub async fn revoke_one_session(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    session_id: web::Path<i32>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let session = entity::session::Entity::find_by_id(session_id.into_inner())
+        .filter(entity::session::Column::UserId.eq(claims.id))
+        .filter(entity::session::Column::RevokedAt.is_null())
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "Session not found".to_owned()))?;
+
+    revoke_session(&*db, session.family_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, "Session revoked".to_owned()))
+}
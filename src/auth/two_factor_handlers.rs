@@ -0,0 +1,163 @@
+/// Handlers for two-factor authentication enrollment.
+/// This module provides endpoints to start TOTP enrollment and confirm it with a code.
+use actix_web::{post, web, HttpRequest};
+use chrono::Utc;
+use entity::audit_event::AuditAction;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::utils::{
+    api_response::ApiResponse,
+    app_state::AppState,
+    audit,
+    auth_tokens::hash_token,
+    jwt::Claims,
+    login_throttle::client_ip,
+    scopes::GrantedScopes,
+    totp::{build_totp, generate_recovery_codes, generate_secret, normalize_recovery_code, verify_code},
+};
+
+/// Request model for confirming two-factor enrollment.
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorCodeModel {
+    pub code: String,
+}
+
+/// Response model for starting two-factor enrollment.
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Response model listing the recovery codes, shown only once.
+#[derive(Serialize, Deserialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Endpoint to start two-factor enrollment.
+/// Generates a new TOTP secret and returns the `otpauth://` URI for authenticator apps.
+/// Two-factor authentication stays disabled until the secret is confirmed with a code.
+#[post("/2fa/setup")]
+pub async fn setup_two_factor(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_data = entity::user::Entity::find_by_id(claims.id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "User not found".to_owned()))?;
+
+    if user_data.totp_enabled_at.is_some() {
+        return Err(ApiResponse::new(400, "Two-factor authentication is already enabled".to_owned()));
+    }
+
+    let secret = generate_secret();
+    let otpauth_uri = build_totp(&secret, &user_data.email)
+        .map_err(|err| ApiResponse::new(500, err))?
+        .get_url();
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::TotpSecret, Expr::value(secret.clone()))
+        .filter(entity::user::Column::Id.eq(user_data.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let res_str = serde_json::to_string(&TwoFactorSetupResponse { secret, otpauth_uri })
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, res_str))
+}
+
+/// Endpoint to confirm two-factor enrollment.
+/// Enables two-factor authentication once a valid code is supplied and returns
+/// a fresh set of recovery codes.
+#[post("/2fa/confirm")]
+pub async fn confirm_two_factor(
+    app_state: web::Data<AppState>,
+    req: HttpRequest,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    code_json: web::Json<TwoFactorCodeModel>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_data = entity::user::Entity::find_by_id(claims.id)
+        .one(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .ok_or(ApiResponse::new(404, "User not found".to_owned()))?;
+
+    if user_data.totp_enabled_at.is_some() {
+        return Err(ApiResponse::new(400, "Two-factor authentication is already enabled".to_owned()));
+    }
+
+    let secret = user_data
+        .totp_secret
+        .as_deref()
+        .ok_or(ApiResponse::new(400, "Two-factor setup has not been started".to_owned()))?;
+
+    let code_step = verify_code(secret, &user_data.email, &code_json.code)
+        .map_err(|err| ApiResponse::new(500, err))?
+        .ok_or(ApiResponse::new(400, "Invalid two-factor code".to_owned()))?;
+
+    let now = Utc::now().naive_local();
+    let recovery_codes = generate_recovery_codes();
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::TotpEnabledAt, Expr::value(now))
+        // The confirmation code cannot be used to log in
+        .col_expr(entity::user::Column::TotpLastStep, Expr::value(code_step))
+        .filter(entity::user::Column::Id.eq(user_data.id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    entity::recovery_code::Entity::delete_many()
+        .filter(entity::recovery_code::Column::UserId.eq(user_data.id))
+        .exec(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    for recovery_code in &recovery_codes {
+        entity::recovery_code::ActiveModel {
+            user_id: Set(user_data.id),
+            code_hash: Set(hash_token(&normalize_recovery_code(recovery_code))),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(&*db, AuditAction::TwoFactorEnabled, Some(user_data.id), Some(user_data.id), &client_ip(&req), None).await;
+
+    let res_str = serde_json::to_string(&RecoveryCodesResponse { recovery_codes })
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    Ok(ApiResponse::new(200, res_str))
+}
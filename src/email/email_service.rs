@@ -1,62 +1,389 @@
 //! Email Service Module
 //!
-//! This module provides functionality for sending newsletter emails
+//! This module provides functionality for sending newsletter and account emails
 //! using the `lettre` crate for SMTP transport.
 //!
 //! ## Features
 //! - Reads an HTML email template from a file.
 //! - Replaces placeholders with actual content.
-//! - Sends emails using SMTP with authentication.
+//! - Sends emails using SMTP with authentication, or prints them in development when opted in.
 
 use lettre::{message::header::ContentType, transport::smtp::authentication::Credentials, Message, SmtpTransport, Transport};
 use std::fs;
 use std::path::Path;
 
-/// Sends a newsletter email to a subscriber.
+use crate::utils::contants;
+
+/// Reads an email template and replaces its placeholders.
+/// Values are escaped, as names and addresses come from users.
 ///
-/// This function reads an email template from a file, replaces placeholders with actual values,
-/// and sends an email using an SMTP server.
+/// # Arguments
+/// * `template_name` - File name of the template inside `src/templates`.
+/// * `values` - Pairs of placeholder names and their replacement values, as plain text.
+///
+/// # Returns
+/// * `Ok(String)` - The rendered email body.
+/// * `Err(String)` - If the template cannot be read.
+pub(crate) fn render_template(template_name: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    render_localized_template(template_name, "en", values)
+}
+
+/// Reads the translation of an email template and replaces its placeholders.
+/// Translations live in `src/templates/<language>`; the English template is used when there is none.
+///
+/// # Arguments
+/// * `template_name` - File name of the template inside `src/templates`.
+/// * `language` - The recipient's language, e.g. `de`.
+/// * `values` - Pairs of placeholder names and their replacement values, as plain text.
+///
+/// # Returns
+/// * `Ok(String)` - The rendered email body.
+/// * `Err(String)` - If the template cannot be read.
+fn render_localized_template(template_name: &str, language: &str, values: &[(&str, &str)]) -> Result<String, String> {
+    render_localized_html_template(template_name, language, values, &[])
+}
+
+/// Like `render_localized_template`, but also inserts HTML fragments built by this module.
+///
+/// # Arguments
+/// * `template_name` - File name of the template inside `src/templates`.
+/// * `language` - The recipient's language, e.g. `de`.
+/// * `values` - Pairs of placeholder names and their replacement values, as plain text.
+/// * `html_values` - Pairs of placeholder names and HTML inserted as is; their parts must be escaped.
+///
+/// # Returns
+/// * `Ok(String)` - The rendered email body.
+/// * `Err(String)` - If the template cannot be read.
+fn render_localized_html_template(
+    template_name: &str,
+    language: &str,
+    values: &[(&str, &str)],
+    html_values: &[(&str, &str)],
+) -> Result<String, String> {
+    let translated_path = Path::new("src/templates").join(language).join(template_name);
+    let template_path = if translated_path.is_file() {
+        translated_path
+    } else {
+        Path::new("src/templates").join(template_name)
+    };
+
+    let template_content = fs::read_to_string(template_path)
+        .map_err(|err| format!("Failed to read email template: {}", err))?;
+
+    let body = values.iter().fold(template_content, |body, (placeholder, value)| {
+        body.replace(&format!("{{{{ {} }}}}", placeholder), &escape_html(value))
+    });
+
+    Ok(html_values.iter().fold(body, |body, (placeholder, html)| {
+        body.replace(&format!("{{{{ {} }}}}", placeholder), html)
+    }))
+}
+
+/// Sends an HTML email.
+///
+/// When `SMTP_SERVER` is not configured the email is not sent and only its recipient and
+/// subject are logged, as the body may contain login links. With `EMAIL_DEV_LOG=true` the
+/// whole email is printed instead, so that local setups work without an SMTP relay.
 ///
 /// # Arguments
 /// * `email` - The recipient's email address.
-/// * `title` - The title of the newsletter article.
-/// * `snippet` - A short snippet of the article.
-/// * `article_link` - A URL to the full article.
-/// * `unsubscribe_link` - A URL for the recipient to unsubscribe.
+/// * `subject` - The subject line.
+/// * `email_body` - The HTML body.
 ///
 /// # Returns
 /// * `Ok(())` on success.
-/// * `Err(String)` if an error occurs while sending the email.
-pub async fn send_newsletter_email(email: &str, title: &str, snippet: &str, article_link: &str, unsubscribe_link: &str) -> Result<(), String> {
-     // Read the email template
-     let template_path = Path::new("src/templates/email_template.html");
-     let template_content = fs::read_to_string(template_path)
-         .map_err(|err| format!("Failed to read email template: {}", err))?;
- 
-     // Replace placeholders with actual values
-     let email_body = template_content
-         .replace("{{ title }}", title)
-         .replace("{{ snippet }}", snippet)
-         .replace("{{ article_link }}", article_link)
-         .replace("{{ unsubscribe_link }}", unsubscribe_link);
+/// * `Err(String)` if an error occurs while sending the email or no SMTP server is configured.
+pub async fn send_email(email: &str, subject: &str, email_body: String) -> Result<(), String> {
+    let Some(smtp_server) = contants::SMTP_SERVER.as_deref() else {
+        if *contants::EMAIL_DEV_LOG {
+            println!("Email to {} ({}):\n{}", email, subject, email_body);
+            return Ok(());
+        }
+
+        eprintln!("Email to {} ({}) not sent: SMTP_SERVER is not configured", email, subject);
+        return Err("SMTP_SERVER is not configured".to_string());
+    };
 
     // Construct the email message
     let email = Message::builder()
-        .from("username".parse().unwrap())
-        .to(email.parse().unwrap())
-        .subject("📢 New Article Notification")
+        .from(contants::EMAIL_FROM.parse().map_err(|e: lettre::address::AddressError| e.to_string())?)
+        .to(email.parse().map_err(|e: lettre::address::AddressError| e.to_string())?)
+        .subject(subject)
         .header(ContentType::TEXT_HTML)
         .body(email_body)
         .map_err(|e| e.to_string())?;
 
     // Set up SMTP transport
-    let mailer = SmtpTransport::starttls_relay("smtp.mailersend.net")
-        .unwrap()
-        .credentials(Credentials::new("username".to_string(),
-         "password".to_string()))
+    let mailer = SmtpTransport::starttls_relay(smtp_server)
+        .map_err(|e| e.to_string())?
+        .credentials(Credentials::new(contants::SMTP_USERNAME.clone(),
+         contants::SMTP_PASSWORD.clone()))
         .port(587)
         .build();
     // Send the email
     mailer.send(&email).map_err(|e| e.to_string())?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Sends a newsletter email to a subscriber.
+///
+/// This function reads an email template from a file, replaces placeholders with actual values,
+/// and sends an email using an SMTP server.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `title` - The title of the newsletter article.
+/// * `snippet` - A short snippet of the article's plain text.
+/// * `published_at` - When the article was published, formatted for the recipient.
+/// * `article_link` - A URL to the full article.
+/// * `unsubscribe_link` - A URL for the recipient to unsubscribe.
+/// * `language` - The recipient's language.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_newsletter_email(
+    email: &str,
+    title: &str,
+    snippet: &str,
+    published_at: &str,
+    article_link: &str,
+    unsubscribe_link: &str,
+    language: &str,
+) -> Result<(), String> {
+    let email_body = render_localized_template(
+        "email_template.html",
+        language,
+        &[
+            ("title", title),
+            ("snippet", snippet),
+            ("published_at", published_at),
+            ("article_link", article_link),
+            ("unsubscribe_link", unsubscribe_link),
+        ],
+    )?;
+
+    let subject = match language {
+        "de" => "📢 Neuer Artikel",
+        _ => "📢 New Article Notification",
+    };
+
+    send_email(email, subject, email_body).await
+}
+
+/// Sends the link a newly registered user follows to verify their email address.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `verification_link` - A URL consuming the verification token.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_verification_email(email: &str, name: &str, verification_link: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "verify_email_template.html",
+        &[("name", name), ("verification_link", verification_link)],
+    )?;
+
+    send_email(email, "Please verify your email address", email_body).await
+}
+
+/// Sends the link confirming a new email address to that address.
+///
+/// # Arguments
+/// * `email` - The new email address.
+/// * `name` - The recipient's name.
+/// * `confirmation_link` - A URL consuming the email change token.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_email_change_email(email: &str, name: &str, confirmation_link: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "confirm_email_change_template.html",
+        &[("name", name), ("confirmation_link", confirmation_link)],
+    )?;
+
+    send_email(email, "Confirm your new email address", email_body).await
+}
+
+/// Tells a user at their previous address that the account email was changed.
+///
+/// # Arguments
+/// * `email` - The previous email address.
+/// * `name` - The recipient's name.
+/// * `new_email` - The address the account now uses.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_email_changed_email(email: &str, name: &str, new_email: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "email_changed_template.html",
+        &[("name", name), ("new_email", new_email)],
+    )?;
+
+    send_email(email, "Your email address was changed", email_body).await
+}
+
+/// Sends the link a user follows to choose a new password.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `reset_link` - A URL carrying the password reset token.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_password_reset_email(email: &str, name: &str, reset_link: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "reset_password_template.html",
+        &[("name", name), ("reset_link", reset_link)],
+    )?;
+
+    send_email(email, "Reset your password", email_body).await
+}
+
+/// Sends the link a user follows to log in without a password.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `login_link` - A URL carrying the magic link token.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_magic_link_email(email: &str, name: &str, login_link: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "magic_link_template.html",
+        &[("name", name), ("login_link", login_link)],
+    )?;
+
+    send_email(email, "Your login link", email_body).await
+}
+
+/// Tells a user that their account is scheduled for deletion.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `scheduled_for` - When the account will be erased, formatted for the recipient.
+/// * `language` - The recipient's language.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_account_deletion_email(email: &str, name: &str, scheduled_for: &str, language: &str) -> Result<(), String> {
+    let email_body = render_localized_template(
+        "account_deletion_template.html",
+        language,
+        &[("name", name), ("scheduled_for", scheduled_for)],
+    )?;
+
+    let subject = match language {
+        "de" => "Dein Konto wird gelöscht",
+        _ => "Your account is scheduled for deletion",
+    };
+
+    send_email(email, subject, email_body).await
+}
+
+/// Escapes text for use inside HTML.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Sends a digest listing several new articles in one email.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `articles` - Title, author name, publication date and link of each article.
+/// * `language` - The recipient's language.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_digest_email(email: &str, name: &str, articles: &[(&str, &str, &str, &str)], language: &str) -> Result<(), String> {
+    let by = match language {
+        "de" => "von",
+        _ => "by",
+    };
+
+    let article_list = articles
+        .iter()
+        .map(|(title, author, published_at, link)| {
+            format!(
+                "                <li><a href=\"{}\">{}</a> {} {}, {}</li>",
+                escape_html(link),
+                escape_html(title),
+                by,
+                escape_html(author),
+                escape_html(published_at)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let email_body = render_localized_html_template(
+        "digest_template.html",
+        language,
+        &[("name", name)],
+        &[("articles", &article_list)],
+    )?;
+
+    let subject = match language {
+        "de" => format!("📚 {} neue Artikel von den Autorinnen und Autoren, denen du folgst", articles.len()),
+        _ => format!("📚 {} new articles from the authors you follow", articles.len()),
+    };
+
+    send_email(email, &subject, email_body).await
+}
+
+/// Sends the link a user follows to download the export of their data.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `download_link` - A URL carrying the download token.
+/// * `hours` - How long the link works.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_data_export_email(email: &str, name: &str, download_link: &str, hours: i64) -> Result<(), String> {
+    let email_body = render_template(
+        "data_export_template.html",
+        &[("name", name), ("download_link", download_link), ("hours", &hours.to_string())],
+    )?;
+
+    send_email(email, "Your data export is ready", email_body).await
+}
+
+/// Tells a user that their account was locked after repeated failed logins.
+///
+/// # Arguments
+/// * `email` - The recipient's email address.
+/// * `name` - The recipient's name.
+/// * `minutes` - How long the lockout lasts.
+/// * `ip_address` - The client IP of the last failed attempt.
+///
+/// # Returns
+/// * `Ok(())` on success.
+/// * `Err(String)` if an error occurs while sending the email.
+pub async fn send_account_locked_email(email: &str, name: &str, minutes: i64, ip_address: &str) -> Result<(), String> {
+    let email_body = render_template(
+        "account_locked_template.html",
+        &[("name", name), ("minutes", &minutes.to_string()), ("ip_address", ip_address)],
+    )?;
+
+    send_email(email, "Your account was temporarily locked", email_body).await
+}
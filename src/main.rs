@@ -10,6 +10,7 @@ use sea_orm::{Database, DatabaseConnection};
 use utils::{app_state::AppState, main_error::MainError};
 
 mod utils;
+mod api_key;
 mod article;
 mod auth;
 mod email;
@@ -17,6 +18,7 @@ mod health;
 mod subscription;
 mod user;
 mod middlewares;
+mod well_known;
 
 #[cfg(test)]
 mod testcases;
@@ -37,9 +39,11 @@ async fn main() -> Result<(), MainError> {
     dotenv::dotenv().ok();
     env_logger::init();
 
-    let port = (*utils::contants::PORT).clone();
+    let port = *utils::contants::PORT;
     let host_address = (*utils::contants::HOST_ADDRESS).clone();
     let database_url: String = (*utils::contants::DATABASE_URL).clone();
+    // Fail at startup rather than on the first login if a signing key is misconfigured
+    lazy_static::initialize(&utils::jwt_keys::KEY_SET);
 
     let db: DatabaseConnection =
         Database::connect(database_url)
@@ -53,6 +57,10 @@ async fn main() -> Result<(), MainError> {
 
     let db = Arc::new(db);
 
+    utils::account_erasure::spawn_erasure_job(Arc::clone(&db));
+    utils::newsletter::spawn_digest_job(Arc::clone(&db));
+    utils::publishing::spawn_publishing_job(Arc::clone(&db));
+
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(AppState { db: Arc::clone(&db) }))
@@ -62,6 +70,8 @@ async fn main() -> Result<(), MainError> {
             .configure(user::user_routes::config)
             .configure(article::article_routes::config)
             .configure(subscription::subscription_routes::config)
+            .configure(api_key::api_key_routes::config)
+            .configure(well_known::well_known_routes::config)
     })
     .bind((host_address, port))
     .map_err(|err| MainError {message : err.to_string()})?
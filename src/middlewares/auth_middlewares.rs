@@ -1,20 +1,28 @@
 /// Middleware for authorization checks.
-/// This module provides a middleware to validate JWT tokens from request headers.
+/// This module provides a middleware to validate JWT tokens or API keys from request headers.
 use actix_web::{
     body::MessageBody,
     dev::{ServiceRequest, ServiceResponse},
     http::header::AUTHORIZATION,
     middleware::Next,
-    Error, HttpMessage,
+    web, Error, HttpMessage,
 };
 
+use chrono::Utc;
+use sea_orm::{sea_query::Expr, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
 use crate::utils::{
     api_response::{self, ApiResponse},
-    jwt::decode_jwt,
+    app_state::AppState,
+    auth_tokens::{hash_token, is_access_token_revoked},
+    jwt::{decode_jwt, Claims},
+    scopes::{parse_scopes, GrantedScopes},
 };
 
 /// Middleware to verify authorization token.
 /// 
+/// Accepts either `Authorization: Bearer <jwt>` or `Authorization: ApiKey <key>`.
+/// 
 /// # Arguments:
 /// * `req` - The incoming service request.
 /// * `next` - The next service to call if authorization succeeds.
@@ -34,27 +42,91 @@ pub async fn check_auth_middleware(
             "Unauthorized".to_string(),
         )));
     }
-    
-    // Extract and decode the Bearer token
-    let token: String = auth
+
+    let auth: String = auth
         .unwrap()
         .to_str()
-        .unwrap()
-        .replace("Bearer", "")
+        .map_err(|_| Error::from(api_response::ApiResponse::new(401, "Unauthorized".to_string())))?
         .trim()
         .to_owned();
 
-    // Decode the JWT token and extract claims 
-    let claim = decode_jwt(token.clone()).map_err(|err| {
-        eprintln!("JWT decoding error: {:?}", err);
-        Error::from(api_response::ApiResponse::new(401, "Invalid token".to_string()))
-    })?;
+    let app_state = req
+        .app_data::<web::Data<AppState>>()
+        .cloned()
+        .ok_or(Error::from(ApiResponse::new(500, "Missing application state".to_string())))?;
+
+    let (claims, scopes) = match auth.strip_prefix("ApiKey ") {
+        Some(api_key) => authenticate_api_key(&app_state.db, api_key.trim()).await?,
+        None => {
+            // Extract and decode the Bearer token
+            let token = auth.replace("Bearer", "").trim().to_owned();
+
+            // Decode the JWT token and extract claims 
+            let claim = decode_jwt(token).map_err(|err| {
+                eprintln!("JWT decoding error: {:?}", err);
+                Error::from(api_response::ApiResponse::new(401, "Invalid token".to_string()))
+            })?;
+
+            // Reject tokens that have been revoked (e.g. on logout) or whose session was ended
+            let is_revoked = is_access_token_revoked(&*app_state.db, &claim.claims.jti, claim.claims.sid.as_deref())
+                .await
+                .map_err(|err| Error::from(ApiResponse::new(500, err.to_string())))?;
+
+            if is_revoked {
+                return Err(Error::from(api_response::ApiResponse::new(401, "Token has been revoked".to_string())));
+            }
+
+            (claim.claims, GrantedScopes::Session)
+        }
+    };
 
-    // Store claims in the request extensions for downstream use
-    req.extensions_mut().insert(claim.claims);
+    // Store claims and scopes in the request extensions for downstream use
+    req.extensions_mut().insert(claims);
+    req.extensions_mut().insert(scopes);
     
-    // Proceed with the next service in the middleware chain
-    next.call(req)
+    // Proceed with the next service in the middleware chain, keeping the status
+    // of errors raised by inner middleware such as the role guard
+    next.call(req).await
+}
+
+/// Resolves an API key to the claims of its owner and the scopes granted to the key.
+/// 
+/// API keys do not expire, so the returned claims carry an `exp` of `0`.
+async fn authenticate_api_key(
+    db: &DatabaseConnection,
+    api_key: &str,
+) -> Result<(Claims, GrantedScopes), Error> {
+    let (key_model, user_model) = entity::api_key::Entity::find()
+        .filter(entity::api_key::Column::KeyHash.eq(hash_token(api_key)))
+        .filter(entity::api_key::Column::RevokedAt.is_null())
+        .find_also_related(entity::user::Entity)
+        .one(db)
         .await
-        .map_err(|err| Error::from(ApiResponse::new(500, err.to_string())))
+        .map_err(|err| Error::from(ApiResponse::new(500, err.to_string())))?
+        .ok_or(Error::from(api_response::ApiResponse::new(401, "Invalid API key".to_string())))?;
+
+    let user_model = user_model
+        .ok_or(Error::from(api_response::ApiResponse::new(401, "Invalid API key".to_string())))?;
+
+    let scopes = parse_scopes(&key_model.scopes)
+        .map_err(|err| Error::from(ApiResponse::new(500, err)))?;
+
+    entity::api_key::Entity::update_many()
+        .col_expr(entity::api_key::Column::LastUsedAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::api_key::Column::Id.eq(key_model.id))
+        .exec(db)
+        .await
+        .map_err(|err| Error::from(ApiResponse::new(500, err.to_string())))?;
+
+    let claims = Claims {
+        exp: 0,
+        iat: key_model.created_at.and_utc().timestamp() as usize,
+        email: user_model.email,
+        id: user_model.id,
+        jti: format!("api-key-{}", key_model.id),
+        role: user_model.role,
+        sid: None,
+    };
+
+    Ok((claims, GrantedScopes::ApiKey(scopes)))
 }
@@ -2,4 +2,7 @@
 //! This module exposes middleware functionalities for authentication checks.
 
 /// Module for authentication middleware, including JWT validation.
-pub mod auth_middlewares;
\ No newline at end of file
+pub mod auth_middlewares;
+
+/// Module for role-based access control middleware.
+pub mod role_middlewares;
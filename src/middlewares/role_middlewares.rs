@@ -0,0 +1,49 @@
+/// Middleware for role-based access control.
+/// This module provides a route guard that rejects users whose role lacks a permission.
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    Error, HttpMessage,
+};
+
+use crate::utils::{api_response::ApiResponse, jwt::Claims, roles::Permission};
+
+/// Middleware to verify that the authenticated user holds a permission.
+/// 
+/// Must run after `check_auth_middleware`, which stores the claims it reads. Wrap it
+/// *before* the auth middleware in a scope, since the last `wrap` runs first:
+/// 
+/// ```ignore
+/// web::scope("/admin")
+///     .wrap(from_fn(|req, next| require_permission(Permission::ManageUsers, req, next)))
+///     .wrap(from_fn(check_auth_middleware))
+/// ```
+/// 
+/// # Arguments:
+/// * `permission` - The permission the route requires.
+/// * `req` - The incoming service request.
+/// * `next` - The next service to call if the check succeeds.
+/// 
+/// # Returns:
+/// * `ServiceResponse` if permitted, a `401` without claims or a `403` otherwise.
+pub async fn require_permission(
+    permission: Permission,
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let role = req
+        .extensions()
+        .get::<Claims>()
+        .map(|claims| claims.role)
+        .ok_or(Error::from(ApiResponse::new(401, "Unauthorized".to_string())))?;
+
+    if !permission.is_granted_to(role) {
+        return Err(Error::from(ApiResponse::new(
+            403,
+            format!("Your role is not allowed to {}", permission),
+        )));
+    }
+
+    next.call(req).await
+}
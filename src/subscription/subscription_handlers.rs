@@ -1,23 +1,47 @@
 
 use std::{collections::HashMap, sync::Arc};
-use actix_web::{get, post, web};
+use actix_web::{get, post, put, web, HttpRequest};
 use chrono::Utc;
+use entity::{audit_event::AuditAction, subscription::DeliveryMode};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, FromQueryResult, JoinType, QueryFilter,
-    QuerySelect, Set,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, FromQueryResult, JoinType,
+    QueryFilter, QuerySelect, Set,
 };
 use serde::{Deserialize, Serialize};
-use crate::utils::{api_response::ApiResponse, app_state::AppState, jwt::Claims};
-
+use crate::utils::{
+    api_response::ApiResponse,
+    app_state::AppState,
+    audit,
+    jwt::Claims,
+    login_throttle::client_ip,
+    newsletter::unqueue_author,
+    scopes::{GrantedScopes, Scope},
+};
 
-/// Handlers for user subscription operations.
-/// Provides endpoints to subscribe and unsubscribe from other users.
 
+// Handlers for user subscription operations.
+// Provides endpoints to subscribe and unsubscribe from other users.
 
 /// Request model for subscription operations.
 #[derive(Serialize,Deserialize)]
 pub struct SubscriptionRequest {
     pub user_id: i32,
+    /// Delivery mode of the new subscription; the subscriber's default mode applies if unset.
+    #[serde(default)]
+    pub delivery_mode: Option<DeliveryMode>,
+}
+
+/// Request model for changing the default delivery mode of the user's subscriptions.
+#[derive(Serialize, Deserialize)]
+pub struct DeliveryModeRequest {
+    pub mode: DeliveryMode,
+}
+
+/// Request model for changing the delivery mode of one subscription.
+#[derive(Serialize, Deserialize)]
+pub struct SubscriptionDeliveryRequest {
+    /// The new mode, or `null` to use the default mode again.
+    pub mode: Option<DeliveryMode>,
 }
 /// Response model for subscription operations.
 #[derive(Serialize, FromQueryResult)]
@@ -25,6 +49,9 @@ pub struct SubscriptionResponse {
     pub id: i32,
     pub name: String,
     pub email: String,
+    /// Delivery mode of the subscription, if it overrides the default mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery_mode: Option<DeliveryMode>,
 }
 
 /// Endpoint to subscribe to another user.
@@ -33,8 +60,11 @@ pub struct SubscriptionResponse {
 pub async fn subscribe_user (
     app_state: web::Data<AppState>,
     claims: Claims,
+    granted_scopes: GrantedScopes,
     subscription_request: web::Json<SubscriptionRequest>,
 ) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscriptionsWrite)?;
+
     let subscriber_id = claims.id;
     let subscribed_to_id = subscription_request.user_id;
 
@@ -67,6 +97,7 @@ pub async fn subscribe_user (
     let subscription = entity::subscription::ActiveModel {
         subscribed_user_id: Set(subscribed_to_id),
         subscriber_user_id: Set(subscriber_id),
+        delivery_mode: Set(subscription_request.delivery_mode),
         created_at: Set(Utc::now().naive_local()),
         ..Default::default()
     };
@@ -84,9 +115,13 @@ pub async fn subscribe_user (
 #[get("/unsubscribe-user")]
 pub async fn unsubscribe_user(
     app_state: web::Data<AppState>,
+    req: HttpRequest,
     claims: Claims,
+    granted_scopes: GrantedScopes,
     subscription_request: web::Query<SubscriptionRequest>,
 ) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscriptionsWrite)?;
+
     let subscriber_id = claims.id;
     let subscribed_to_id = subscription_request.user_id;
     let db = Arc::clone(&app_state.db);
@@ -102,6 +137,20 @@ pub async fn unsubscribe_user(
         return Err(ApiResponse::new(404, "Subscription not found".to_owned()));
     }
 
+    unqueue_author(&*db, subscriber_id, subscribed_to_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(
+        &*db,
+        AuditAction::Unsubscribed,
+        Some(subscriber_id),
+        Some(subscriber_id),
+        &client_ip(&req),
+        Some(format!("From user {}", subscribed_to_id)),
+    )
+    .await;
+
     Ok(ApiResponse::new(200, "Unsubscribed successfully".to_owned()))
 }
 
@@ -110,6 +159,7 @@ pub async fn unsubscribe_user(
 #[get("/unsubscribe-user-from-email")]
 pub async fn unsubscribe_user_from_email(
     app_state: web::Data<AppState>,
+    req: HttpRequest,
     claims: web::Query<HashMap<String, String>>,
     subscription_request: web::Query<SubscriptionRequest>,
 ) -> Result<ApiResponse, ApiResponse> {
@@ -128,6 +178,21 @@ pub async fn unsubscribe_user_from_email(
         return Err(ApiResponse::new(404, "Subscription not found".to_owned()));
     }
 
+    unqueue_author(&*db, subscriber_id, subscribed_to_id)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    // The email link carries no login, so the actor is unknown
+    audit::record(
+        &*db,
+        AuditAction::Unsubscribed,
+        Some(subscriber_id),
+        None,
+        &client_ip(&req),
+        Some(format!("From user {} via email link", subscribed_to_id)),
+    )
+    .await;
+
     Ok(ApiResponse::new(200, "Unsubscribed successfully".to_owned()))
 }
 
@@ -137,7 +202,9 @@ pub async fn unsubscribe_user_from_email(
 pub async fn my_subscriptions(
     app_state: web::Data<AppState>,
     claims: Claims,
+    granted_scopes: GrantedScopes,
 ) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscriptionsRead)?;
 
     let db = Arc::clone(&app_state.db);
 
@@ -155,11 +222,12 @@ pub async fn my_subscriptions(
     .await
     .map_err(|err| ApiResponse::new(500, err.to_string()))?
     .into_iter()
-    .filter_map(|(_subscription, user_opt)| {
+    .filter_map(|(subscription, user_opt)| {
         user_opt.map(|user| SubscriptionResponse {
             id: user.id,
             name: user.name,
             email: user.email,
+            delivery_mode: subscription.delivery_mode,
         })
     })
     .collect::<Vec<SubscriptionResponse>>();
@@ -176,7 +244,9 @@ pub async fn my_subscriptions(
 pub async fn my_subscribers(
     app_state: web::Data<AppState>,
     claims: Claims,
+    granted_scopes: GrantedScopes,
 ) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscribersRead)?;
 
     let db = Arc::clone(&app_state.db);
 
@@ -199,6 +269,7 @@ pub async fn my_subscribers(
             id: user.id,
             name: user.name,
             email: user.email,
+            delivery_mode: None,
     }))
     .collect::<Vec<SubscriptionResponse>>();
 
@@ -206,4 +277,61 @@ pub async fn my_subscribers(
     .map_err(|err| ApiResponse::new(500, err.to_string()))?;
 
     Ok(ApiResponse::new(200, res_str))      
-}
\ No newline at end of file
+}
+
+/// Endpoint to change the default delivery mode of the user's subscriptions.
+/// Applies to every subscription without its own mode, including future ones. Articles
+/// already queued for a digest are still sent.
+#[put("/delivery")]
+pub async fn set_default_delivery(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    delivery_request: web::Json<DeliveryModeRequest>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscriptionsWrite)?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::DeliveryMode, Expr::value(delivery_request.mode))
+        .filter(entity::user::Column::Id.eq(claims.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(ApiResponse::new(404, "User not found".to_owned()));
+    }
+
+    Ok(ApiResponse::new(200, "Delivery preference updated".to_owned()))
+}
+
+/// Endpoint to change the delivery mode of one subscription.
+/// A `null` mode makes the subscription follow the default mode again.
+#[put("/delivery/{user_id}")]
+pub async fn set_subscription_delivery(
+    app_state: web::Data<AppState>,
+    claims: Claims,
+    granted_scopes: GrantedScopes,
+    user_id: web::Path<i32>,
+    delivery_request: web::Json<SubscriptionDeliveryRequest>,
+) -> Result<ApiResponse, ApiResponse> {
+    granted_scopes.require(Scope::SubscriptionsWrite)?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = entity::subscription::Entity::update_many()
+        .col_expr(entity::subscription::Column::DeliveryMode, Expr::value(delivery_request.mode))
+        .filter(entity::subscription::Column::SubscriberUserId.eq(claims.id))
+        .filter(entity::subscription::Column::SubscribedUserId.eq(user_id.into_inner()))
+        .exec(&*db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(ApiResponse::new(404, "Subscription not found".to_owned()));
+    }
+
+    Ok(ApiResponse::new(200, "Delivery preference updated".to_owned()))
+}
@@ -9,6 +9,8 @@ use super::subscription_handlers;
 /// # Routes:
 /// - `/subscription/subscribe-user`: Subscribe to a user (requires authentication).
 /// - `/subscription/unsubscribe-user`: Unsubscribe from a user (requires authentication).
+/// - `/secure/subscription/delivery`: Set the default delivery mode (requires authentication).
+/// - `/secure/subscription/delivery/{user_id}`: Set the delivery mode of one subscription (requires authentication).
 /// 
 /// # Middleware:
 /// - `auth_middlewares::check_auth_middleware`: Ensures user authentication.
@@ -23,6 +25,8 @@ pub fn config(config: &mut web::ServiceConfig) {
             .service(subscription_handlers::unsubscribe_user)// Endpoint to unsubscribe a user
             .service(subscription_handlers::my_subscriptions)
             .service(subscription_handlers::my_subscribers)
+            .service(subscription_handlers::set_default_delivery)
+            .service(subscription_handlers::set_subscription_delivery)
     )
     .service(web::scope("/subscription")
         .service(subscription_handlers::unsubscribe_user_from_email)
@@ -0,0 +1,187 @@
+/// Integration tests for API key handlers.
+/// This module contains tests for creating API keys and authenticating with them.
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use crate::testcases::fixtures;
+
+    use crate::{
+        api_key::{api_key_handlers::CreateApiKeyModel, api_key_routes},
+        article::article_routes,
+        utils::{app_state::AppState, auth_tokens::hash_token, jwt::encode_jwt},
+    };
+    use actix_web::{http::StatusCode, test, web, App};
+    use chrono::Utc;
+    use entity::user::UserRole;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    /// Builds a stored API key with the given scopes.
+    fn api_key_model(scopes: &str) -> entity::api_key::Model {
+        entity::api_key::Model {
+            id: 1,
+            user_id: 1,
+            name: "CI".to_string(),
+            prefix: "nlk_12345678".to_string(),
+            key_hash: hash_token("nlk_key"),
+            scopes: scopes.to_string(),
+            created_at: Utc::now().naive_local(),
+            last_used_at: None,
+            revoked_at: None,
+        }
+    }
+
+    /// Test creating an API key.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_api_key() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![api_key_model("article:write")]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(api_key_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/api-key/create")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CreateApiKeyModel {
+                name: "CI".to_string(),
+                scopes: vec!["article:write".to_string()],
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that unknown scopes are rejected.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_api_key_unknown_scope() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(api_key_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/api-key/create")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(CreateApiKeyModel {
+                name: "CI".to_string(),
+                scopes: vec!["everything".to_string()],
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test that an API key cannot be used to manage API keys.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_api_key_cannot_manage_keys() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![(
+                api_key_model("article:write"),
+                Some(fixtures::user(1, "Author", "author@example.com", "password")),
+            )]])
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(api_key_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/secure/api-key/my-keys")
+            .insert_header(("Authorization", "ApiKey nlk_key"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test authenticating a secure article route with a scoped API key.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_api_key_scopes() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // API key lookup, granted scope matches
+            .append_query_results(vec![vec![(
+                api_key_model("article:read"),
+                Some(fixtures::user(1, "Author", "author@example.com", "password")),
+            )]])
+            // Timezone of the author
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
+            .append_query_results(vec![vec![] as Vec<entity::article::Model>])
+            // API key lookup, granted scope does not match
+            .append_query_results(vec![vec![(
+                api_key_model("subscribers:read"),
+                Some(fixtures::user(1, "Author", "author@example.com", "password")),
+            )]])
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                };
+                2
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(article_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/secure/article/my-article")
+            .insert_header(("Authorization", "ApiKey nlk_key"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri("/secure/article/my-article")
+            .insert_header(("Authorization", "ApiKey nlk_key"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+}
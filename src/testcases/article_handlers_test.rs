@@ -2,15 +2,25 @@
 /// This module contains tests for creating, fetching, and listing articles.
 #[cfg(test)]
 pub mod tests {
-    use std::sync::Arc;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use crate::testcases::fixtures;
 
     use crate::{
         article::{self, article_routes::config},
-        utils::{app_state::AppState, jwt::encode_jwt},
+        utils::{
+            app_state::AppState,
+            jwt::encode_jwt,
+            locale::UserLocale,
+            markdown,
+            newsletter::{next_digest, notify_subscribers, send_due_digests},
+            publishing::publish_due_articles,
+        },
     };
     use actix_web::{http::StatusCode, test, web, App};
-    use chrono::Utc;
-    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use chrono::{NaiveDate, Utc};
+    use entity::{article::ArticleStatus, subscription::DeliveryMode, user::UserRole};
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
     use serial_test::serial;
     use uuid::Uuid;
 
@@ -19,25 +29,29 @@ pub mod tests {
     #[serial]
     pub async fn test_create_article_without_email() {
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // Author lookup for the verification check
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
             .append_query_results(vec![vec![entity::article::Model {
                 id: 1,
                 title: "Test Article".to_string(),
                 content: "Test Content".to_string(),
+                content_html: None,
+                content_text: None,
                 user_id: 1,
                 uuid: Uuid::new_v4(),
                 created_at: Utc::now().naive_local(),
                 image: None,
+                updated_at: Utc::now().naive_local(),
+                deleted_at: None,
+                status: ArticleStatus::Published,
+                publish_at: Some(Utc::now().naive_local()),
             }]])
             .append_exec_results(vec![MockExecResult {
                 last_insert_id: 1,
                 rows_affected: 1,
             }])
-            .append_query_results(vec![vec![entity::user::Model {
-                id: 1,
-                name: "Author".to_string(),
-                email: "author@example.com".to_string(),
-                password: "password".to_string(),
-            }]])
             .into_connection();
 
         let mock_db = Arc::new(mock_db);
@@ -50,11 +64,87 @@ pub mod tests {
             test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
 
         // Generate mock JWT token
-        let token = encode_jwt("author@example.com".to_string(), 1).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let article_data = article::article_handlers::CreateArticleModel {
+            title: "Test Article".to_string(),
+            content: "Test Content".to_string(),
+            status: None,
+            publish_at: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/secure/article/create?send_email=false")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(&article_data)
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that an unverified author cannot publish.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_unverified() {
+        let mut author = fixtures::user(1, "Author", "author@example.com", "password");
+        author.email_verified_at = None;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![author]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let article_data = article::article_handlers::CreateArticleModel {
+            title: "Test Article".to_string(),
+            content: "Test Content".to_string(),
+            status: None,
+            publish_at: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/secure/article/create?send_email=false")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(&article_data)
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test that readers cannot publish.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_reader() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("reader@example.com".to_string(), 1, UserRole::Reader, Uuid::new_v4()).unwrap();
 
         let article_data = article::article_handlers::CreateArticleModel {
             title: "Test Article".to_string(),
             content: "Test Content".to_string(),
+            status: None,
+            publish_at: None,
         };
 
         let req = test::TestRequest::post()
@@ -64,11 +154,184 @@ pub mod tests {
             .set_json(&article_data)
             .to_request();
 
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(resp.err().map(|err| err.as_response_error().status_code()), Some(StatusCode::FORBIDDEN));
+    }
+
+    /// Builds the subscription of a subscriber to author 1.
+    fn subscription(id: i32, subscriber_id: i32, delivery_mode: Option<DeliveryMode>) -> entity::subscription::Model {
+        entity::subscription::Model {
+            id,
+            subscribed_user_id: 1,
+            subscriber_user_id: subscriber_id,
+            delivery_mode,
+            created_at: Utc::now().naive_local(),
+        }
+    }
+
+    /// Test that publishing emails instant subscribers, queues the article for digest
+    /// subscribers and skips subscribers who turned emails off.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_delivery_modes() {
+        let content = "A newsletter article that is long enough for the email snippet.";
+        let weekly_reader = entity::user::Model {
+            delivery_mode: DeliveryMode::Weekly,
+            ..fixtures::user(4, "Weekly", "weekly@example.com", "password")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // Author lookup for the verification check
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
+            .append_query_results(vec![vec![entity::article::Model {
+                content: content.to_string(),
+                ..fixtures::article(1, 1, "Test Article")
+            }]])
+            .append_query_results(vec![vec![
+                (subscription(1, 2, None), Some(fixtures::user(2, "Instant", "instant@example.com", "password"))),
+                (subscription(2, 3, Some(DeliveryMode::Daily)), Some(fixtures::user(3, "Daily", "daily@example.com", "password"))),
+                (subscription(3, 4, None), Some(weekly_reader)),
+                (subscription(4, 5, Some(DeliveryMode::None)), Some(fixtures::user(5, "Muted", "muted@example.com", "password"))),
+            ]])
+            // Daily and weekly subscribers queued for their digests
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 2,
+            }])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/secure/article/create")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::CreateArticleModel {
+                title: "Test Article".to_string(),
+                content: content.to_string(),
+                status: None,
+                publish_at: None,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let transaction_log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        let digest_insert = transaction_log
+            .split("Statement {")
+            .find(|statement| statement.contains(r#"INSERT INTO \"digest_item\""#))
+            .unwrap();
+
+        // One row each for the daily and the weekly subscriber
+        assert!(digest_insert.contains("($5, $6, $7, $8)"));
+        assert!(!digest_insert.contains("$9"));
+    }
+
+    /// Test that a failed delivery does not fail the request once the article is saved.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_delivery_error() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // Author lookup for the verification check
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
+            .append_query_results(vec![vec![fixtures::article(1, 1, "Test Article")]])
+            // No result for the subscriber lookup, which fails
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/secure/article/create")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::CreateArticleModel {
+                title: "Test Article".to_string(),
+                content: "Test content".to_string(),
+                status: None,
+                publish_at: None,
+            })
+            .to_request();
+
         let resp = test::call_service(&app, req).await;
 
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    /// Test sending a due digest, which removes its items from the queue.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_send_due_digests() {
+        let now = Utc::now().naive_local();
+        let digest_item = |id: i32, article_id: i32| entity::digest_item::Model {
+            id,
+            subscriber_user_id: 2,
+            article_id,
+            deliver_after: now,
+            created_at: now,
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![
+                (digest_item(1, 1), Some(fixtures::article(1, 1, "First Article"))),
+                (digest_item(2, 2), Some(fixtures::article(2, 1, "Second Article"))),
+            ]])
+            .append_query_results(vec![vec![
+                fixtures::user(1, "Author", "author@example.com", "password"),
+                fixtures::user(2, "Reader", "reader@example.com", "password"),
+            ]])
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 2,
+            }])
+            .into_connection();
+
+        send_due_digests(&mock_db).await.unwrap();
+
+        let statements = format!("{:?}", mock_db.into_transaction_log());
+        assert!(statements.contains(r#"DELETE FROM \"digest_item\""#));
+    }
+
+    /// Test when digests are sent, at midnight in the subscriber's timezone.
+    #[actix_web::test]
+    pub async fn test_next_digest() {
+        // A Wednesday afternoon
+        let now = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(15, 30, 0).unwrap();
+
+        let utc = UserLocale::default();
+        let berlin = UserLocale::of(&entity::user::Model {
+            timezone: "Europe/Berlin".to_string(),
+            ..fixtures::user(2, "Reader", "reader@example.com", "password")
+        });
+
+        assert_eq!(next_digest(DeliveryMode::Daily, now, &utc), NaiveDate::from_ymd_opt(2025, 6, 12).unwrap().and_hms_opt(0, 0, 0));
+        assert_eq!(next_digest(DeliveryMode::Weekly, now, &utc), NaiveDate::from_ymd_opt(2025, 6, 16).unwrap().and_hms_opt(0, 0, 0));
+        assert_eq!(next_digest(DeliveryMode::Instant, now, &utc), None);
+
+        // Midnight in Berlin is 22:00 UTC in summer
+        assert_eq!(next_digest(DeliveryMode::Daily, now, &berlin), NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(22, 0, 0));
+        assert_eq!(next_digest(DeliveryMode::Weekly, now, &berlin), NaiveDate::from_ymd_opt(2025, 6, 15).unwrap().and_hms_opt(22, 0, 0));
+    }
+
     /// Test fetching all articles.
     #[actix_web::test]
     #[serial]
@@ -78,10 +341,16 @@ pub mod tests {
                 id: 1,
                 title: "Test Article".to_string(),
                 content: "Test Content".to_string(),
+                content_html: None,
+                content_text: None,
                 user_id: 1,
                 uuid: Uuid::new_v4(),
                 created_at: Utc::now().naive_local(),
                 image: None,
+                updated_at: Utc::now().naive_local(),
+                deleted_at: None,
+                status: ArticleStatus::Published,
+                publish_at: Some(Utc::now().naive_local()),
             }]])
             .into_connection();
 
@@ -100,6 +369,176 @@ pub mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    /// Test paging through articles with a cursor, filtered by author and date.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_all_articles_pagination() {
+        let created_at = |day: u32| NaiveDate::from_ymd_opt(2025, 6, day).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let article = |id: i32, day: u32| entity::article::Model {
+            created_at: created_at(day),
+            ..fixtures::article(id, 1, "Article")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![article(3, 12), article(2, 11)]])
+            .append_query_results(vec![vec![article(2, 11)]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/article/all-article?limit=1&author_id=1&from=2025-06-01T00:00:00&to=2025-07-01T00:00:00")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: article::article_handlers::ArticlePageModel = test::read_body_json(resp).await;
+        assert_eq!(page.articles.len(), 1);
+        assert_eq!(page.articles[0].id, 3);
+        let next_cursor = page.next_cursor.unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/article/all-article?limit=1&author_id=1&cursor={}", next_cursor))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: article::article_handlers::ArticlePageModel = test::read_body_json(resp).await;
+        assert_eq!(page.articles[0].id, 2);
+        assert!(page.next_cursor.is_none());
+
+        drop(app);
+        drop(app_state);
+        let transaction_log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(transaction_log.contains(r#"ORDER BY \"article\".\"created_at\" DESC, \"article\".\"id\" DESC LIMIT $"#));
+        assert!(transaction_log.contains(r#"\"article\".\"created_at\" >= $"#));
+        // The second page continues after the third article
+        assert!(transaction_log.contains(r#"(\"article\".\"created_at\" < $3 OR (\"article\".\"created_at\" = $4 AND \"article\".\"id\" < $5))"#));
+        assert!(transaction_log.contains("ChronoDateTime(Some(2025-06-12T09:00:00)), BigInt(Some(3))"));
+    }
+
+    /// Test rejecting invalid cursors and date ranges.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_all_articles_invalid_query() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let newest_cursor = crate::utils::pagination::encode_cursor("newest", &[0, 1]);
+        for uri in [
+            "/article/all-article?cursor=garbage".to_string(),
+            format!("/article/all-article?sort=oldest&cursor={}", newest_cursor),
+            "/article/all-article?from=2025-07-01T00:00:00&to=2025-06-01T00:00:00".to_string(),
+        ] {
+            let req = test::TestRequest::get().uri(&uri).to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    /// Test searching articles on PostgreSQL, ranked and quoted by the database.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_search_articles() {
+        let ranked_article = BTreeMap::from([
+            ("id", Value::from(2)),
+            ("rank", Value::from(0.6f32)),
+            ("snippet", Value::from("Why \u{E000}Rust\u{E001} is <fast>")),
+        ]);
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![ranked_article]])
+            .append_query_results(vec![vec![fixtures::article(2, 1, "Rust")]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/article/search?q=rust&author_id=1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: article::article_handlers::ArticleSearchModel = test::read_body_json(resp).await;
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.results[0].article.id, 2);
+        assert_eq!(page.results[0].snippet, "Why <mark>Rust</mark> is &lt;fast&gt;");
+        assert!(page.next_cursor.is_none());
+
+        drop(app);
+        drop(app_state);
+        let transaction_log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(transaction_log.contains(r#"\"article\".\"search_vector\" @@ websearch_to_tsquery('english', $"#));
+        assert!(transaction_log.contains(r#"ORDER BY ts_rank(\"article\".\"search_vector\", websearch_to_tsquery('english', $"#));
+        assert!(transaction_log.contains(r#"\"article\".\"user_id\" = $"#));
+    }
+
+    /// Test searching articles on other backends, ranked and quoted in Rust.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_search_articles_fallback() {
+        let article = |id: i32, title: &str, content: &str| entity::article::Model {
+            content: content.to_string(),
+            ..fixtures::article(id, 1, title)
+        };
+        let articles = vec![
+            article(1, "Weekly notes", "We *trust* our tools."),
+            article(2, "Notes", "Some **Rust** news.\n\nMore about Rust."),
+            article(3, "Rust in production", "Lessons learned."),
+        ];
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Sqlite)
+            .append_query_results(vec![articles.clone()])
+            .append_query_results(vec![articles])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/article/search?q=Rust!&limit=1")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: article::article_handlers::ArticleSearchModel = test::read_body_json(resp).await;
+        // A match in the title ranks first
+        assert_eq!(page.results[0].article.id, 3);
+        let next_cursor = page.next_cursor.unwrap();
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/article/search?q=Rust!&limit=1&cursor={}", next_cursor))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let page: article::article_handlers::ArticleSearchModel = test::read_body_json(resp).await;
+        // "trust" only contains the term, so there is no third article
+        assert_eq!(page.results.len(), 1);
+        assert_eq!(page.results[0].article.id, 2);
+        assert_eq!(page.results[0].snippet, "Some <mark>Rust</mark> news. More about <mark>Rust</mark>.");
+        assert!(page.next_cursor.is_none());
+
+        let req = test::TestRequest::get().uri("/article/search?q=%20-%20").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
     /// Test fetching a single article by UUID.
     #[actix_web::test]
     #[serial]
@@ -111,17 +550,18 @@ pub mod tests {
                     id: 1,
                     title: "Test Article".to_string(),
                     content: "Test Content".to_string(),
+                    content_html: None,
+                    content_text: None,
                     user_id: 1,
                     uuid: test_uuid,
                     created_at: Utc::now().naive_local(),
                     image: None,
+                    updated_at: Utc::now().naive_local(),
+                    deleted_at: None,
+                    status: ArticleStatus::Published,
+                    publish_at: Some(Utc::now().naive_local()),
                 },
-                Some(entity::user::Model {
-                    id: 1,
-                    name: "Test User".to_string(),
-                    email: "test@example.com".to_string(),
-                    password: "password".to_string(),
-                }),
+                Some(fixtures::user(1, "Test User", "test@example.com", "password")),
             )]])
             .into_connection();
 
@@ -140,21 +580,531 @@ pub mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    /// Test that articles stored without rendered content are rendered from their Markdown.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_one_article_markdown() {
+        let article = entity::article::Model {
+            content: "| Issue | Topic |\n|---|---|\n| 1 | *Rust* |".to_string(),
+            ..fixtures::article(1, 1, "Table Article")
+        };
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![(
+                article,
+                Some(fixtures::user(1, "Test User", "test@example.com", "password")),
+            )]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/article/get-by-uuid/{}", article_uuid))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("<td><em>Rust</em></td>"));
+        assert!(body.contains(r#""content_text":"Issue\tTopic\n1\tRust""#));
+    }
+
+    /// Test rendering Markdown with tables and footnotes, dropping unsafe HTML.
+    #[actix_web::test]
+    pub async fn test_render_markdown() {
+        let rendered = markdown::render(
+            "# Weekly notes\n\nRust is fast[^speed].<script>alert(1)</script>\n\n\
+             | Crate | Use |\n|---|---|\n| serde | JSON |\n\n\
+             [^speed]: Mostly.\n\n<a href=\"javascript:alert(1)\" onclick=\"alert(1)\">link</a>",
+        );
+
+        assert!(rendered.html.contains("<h1>Weekly notes</h1>"));
+        assert!(rendered.html.contains("<td>serde</td>"));
+        assert!(rendered.html.contains(r##"<a href="#fn-speed""##));
+        assert!(rendered.html.contains(r#"<div class="footnote-definition" id="fn-speed">"#));
+        assert!(!rendered.html.contains("<script"));
+        assert!(!rendered.html.contains("javascript:"));
+        assert!(!rendered.html.contains("onclick"));
+
+        assert!(rendered.text.starts_with("Weekly notes\n\nRust is fast."));
+        assert!(rendered.text.contains("serde\tJSON"));
+        assert!(!rendered.text.contains('<'));
+
+        assert_eq!(markdown::snippet("Grüße aus\n\nBerlin", 12), "Grüße aus Be");
+        assert_eq!(markdown::snippet("Short", 200), "Short");
+    }
+
+    /// Test emailing an article shorter than the snippet to an instant subscriber.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_notify_short_article() {
+        let article = entity::article::Model {
+            content: "Hi **all**".to_string(),
+            ..fixtures::article(1, 1, "Short Article")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![
+                (subscription(1, 2, None), Some(fixtures::user(2, "Instant", "instant@example.com", "password"))),
+            ]])
+            .into_connection();
+
+        assert_eq!(notify_subscribers(&mock_db, &article).await, Ok(()));
+    }
+
+    /// Test that links to a deleted article report that it was removed.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_one_article_deleted() {
+        let article = entity::article::Model {
+            deleted_at: Some(Utc::now().naive_local()),
+            ..fixtures::article(1, 1, "Deleted Article")
+        };
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![(
+                article,
+                Some(fixtures::user(1, "Test User", "test@example.com", "password")),
+            )]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/article/get-by-uuid/{}", article_uuid))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::GONE);
+    }
+
+    /// Test editing an own article.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_update_article() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = fixtures::article(1, 1, "Test Article");
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article.clone()]])
+            .append_query_results(vec![vec![entity::article::Model {
+                title: "Fixed Title".to_string(),
+                ..article
+            }]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::UpdateArticleModel {
+                title: "Fixed Title".to_string(),
+                content: "Test Content".to_string(),
+                status: None,
+                publish_at: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that authors cannot edit or delete the articles of others.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_change_article_not_owner() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = fixtures::article(1, 2, "Someone Else's Article");
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article.clone()]])
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::UpdateArticleModel {
+                title: "Defaced".to_string(),
+                content: "Defaced".to_string(),
+                status: None,
+                publish_at: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test deleting an own article, which is hidden, dropped from digests and audited.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_delete_article() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = fixtures::article(1, 1, "Test Article");
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article.clone()]])
+            .append_query_results(vec![vec![entity::article::Model {
+                deleted_at: Some(Utc::now().naive_local()),
+                ..article
+            }]])
+            // Digest cleanup and audit event
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                };
+                2
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains(r#"UPDATE \"article\""#));
+        assert!(statements.contains(r#"DELETE FROM \"digest_item\""#));
+        assert!(statements.contains("article_deleted"));
+    }
+
+    /// Test scheduling an article, which neither publishes nor notifies anyone yet.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_scheduled() {
+        let publish_at = Utc::now().naive_local() + chrono::Duration::days(1);
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // Author lookup for the verification check
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
+            .append_query_results(vec![vec![entity::article::Model {
+                status: ArticleStatus::Scheduled,
+                publish_at: Some(publish_at),
+                ..fixtures::article(1, 1, "Test Article")
+            }]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/secure/article/create")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::CreateArticleModel {
+                title: "Test Article".to_string(),
+                content: "Test Content".to_string(),
+                status: None,
+                publish_at: Some(publish_at),
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains("scheduled"));
+        // No subscriber lookup before the article is due
+        assert!(!statements.contains(r#"FROM \"subscription\""#));
+    }
+
+    /// Test that articles cannot be scheduled in the past or created archived.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_create_article_invalid_status() {
+        let mut mock_db = MockDatabase::new(DatabaseBackend::Postgres);
+        for _ in 0..2 {
+            mock_db = mock_db
+                // Revocation list lookup in the auth middleware
+                .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+                // Author lookup for the verification check
+                .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]]);
+        }
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db.into_connection()) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let invalid = [
+            (None, Some(Utc::now().naive_local() - chrono::Duration::hours(1))),
+            (Some(ArticleStatus::Archived), None),
+        ];
+
+        for (status, publish_at) in invalid {
+            let req = test::TestRequest::post()
+                .uri("/secure/article/create")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(article::article_handlers::CreateArticleModel {
+                    title: "Test Article".to_string(),
+                    content: "Test Content".to_string(),
+                    status,
+                    publish_at,
+                })
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    /// Test that drafts are not visible to readers.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_one_article_draft() {
+        let article = entity::article::Model {
+            status: ArticleStatus::Draft,
+            publish_at: None,
+            ..fixtures::article(1, 1, "Draft Article")
+        };
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![(
+                article,
+                Some(fixtures::user(1, "Test User", "test@example.com", "password")),
+            )]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/article/get-by-uuid/{}", article_uuid))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Test that authors can preview their drafts.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_preview_article() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = entity::article::Model {
+            status: ArticleStatus::Draft,
+            publish_at: None,
+            ..fixtures::article(1, 1, "Draft Article")
+        };
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@example.com", "password")]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains(r#""status":"draft""#));
+    }
+
+    /// Test publishing a draft, which claims it and notifies the subscribers.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_update_article_publish_draft() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = entity::article::Model {
+            status: ArticleStatus::Draft,
+            publish_at: None,
+            ..fixtures::article(1, 1, "Draft Article")
+        };
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article.clone()]])
+            .append_query_results(vec![vec![article]])
+            // Publication claim
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results(vec![vec![]] as Vec<Vec<(entity::subscription::Model, Option<entity::user::Model>)>>)
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::UpdateArticleModel {
+                title: "Draft Article".to_string(),
+                content: "Test Content".to_string(),
+                status: Some(ArticleStatus::Published),
+                publish_at: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains("published"));
+        assert!(statements.contains(r#"FROM \"subscription\""#));
+    }
+
+    /// Test that published articles cannot go back to drafts.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_update_article_unpublish() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let article = fixtures::article(1, 1, "Test Article");
+        let article_uuid = article.uuid;
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![article]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secure/article/{}", article_uuid))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(article::article_handlers::UpdateArticleModel {
+                title: "Test Article".to_string(),
+                content: "Test Content".to_string(),
+                status: Some(ArticleStatus::Draft),
+                publish_at: None,
+            })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test that the publishing job only notifies subscribers of articles it claimed.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_publish_due_articles() {
+        let due_article = |id: i32| entity::article::Model {
+            status: ArticleStatus::Scheduled,
+            publish_at: Some(Utc::now().naive_local() - chrono::Duration::minutes(1)),
+            ..fixtures::article(id, 1, "Scheduled Article")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![due_article(1), due_article(2)]])
+            // The first article is claimed, the second was published meanwhile
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .append_query_results(vec![vec![]] as Vec<Vec<(entity::subscription::Model, Option<entity::user::Model>)>>)
+            .into_connection();
+
+        publish_due_articles(&mock_db).await.unwrap();
+
+        let statements = format!("{:?}", mock_db.into_transaction_log());
+        assert_eq!(statements.matches(r#"FROM \"subscription\""#).count(), 1);
+    }
+
     /// Test fetching articles belonging to the authenticated user.
     #[actix_web::test]
     #[serial]
     pub async fn test_my_articles() {
-        let token = encode_jwt("author@example.com".to_string(), 1).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
         let test_uuid = Uuid::new_v4();
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![entity::user::Model {
+                timezone: "Europe/Berlin".to_string(),
+                ..fixtures::user(1, "Author", "author@example.com", "password")
+            }]])
             .append_query_results(vec![vec![entity::article::Model {
                 id: 1,
                 title: "My Article".to_string(),
                 content: "My Content".to_string(),
+                content_html: None,
+                content_text: None,
                 user_id: 1,
                 uuid: test_uuid,
-                created_at: Utc::now().naive_local(),
+                created_at: NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(10, 0, 0).unwrap(),
                 image: None,
+                updated_at: Utc::now().naive_local(),
+                deleted_at: None,
+                status: ArticleStatus::Published,
+                publish_at: Some(Utc::now().naive_local()),
             }]])
             .into_connection();
 
@@ -172,5 +1122,9 @@ pub mod tests {
         let resp = test::call_service(&app, req).await;
 
         assert_eq!(resp.status(), StatusCode::OK);
+
+        // Dated in the author's timezone
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("2025-06-11T12:00:00+02:00"));
     }
 }
@@ -4,13 +4,89 @@
 pub mod tests {
     use std::sync::Arc;
 
+    use crate::testcases::fixtures;
+
     use crate::{
         auth::{self, auth_routes::config},
-        utils::app_state::AppState,
+        email::email_service,
+        utils::{
+            app_state::AppState,
+            auth_tokens::hash_token,
+            contants,
+            jwt::{encode_jwt, encode_mfa_jwt},
+            password::hash_password,
+            totp::{build_totp, generate_secret},
+        },
     };
     use actix_web::{http::StatusCode, test, web, App};
+    use chrono::{Duration, Utc};
+    use entity::login_throttle::ThrottleKind;
+    use entity::one_time_token::TokenPurpose;
+    use entity::user::UserRole;
     use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
     use serial_test::serial;
+    use uuid::Uuid;
+
+    /// Builds a stored refresh token for the given plain token.
+    fn refresh_token_model(token: &str, revoked: bool) -> entity::refresh_token::Model {
+        let now = Utc::now().naive_local();
+        entity::refresh_token::Model {
+            id: 1,
+            user_id: 1,
+            token_hash: hash_token(token),
+            family_id: Uuid::new_v4(),
+            expires_at: now + Duration::days(30),
+            revoked_at: revoked.then_some(now),
+            created_at: now,
+        }
+    }
+
+    /// Builds the revocation list entry of an ended session.
+    fn session_revocation_model() -> entity::revoked_token::Model {
+        entity::revoked_token::Model {
+            id: 1,
+            jti: format!("session:{}", Uuid::new_v4()),
+            expires_at: Utc::now().naive_local(),
+        }
+    }
+
+    /// Builds a failed login counter.
+    fn throttle_model(
+        kind: ThrottleKind,
+        failed_count: i32,
+        locked_until: Option<chrono::NaiveDateTime>,
+    ) -> entity::login_throttle::Model {
+        entity::login_throttle::Model {
+            id: 1,
+            kind,
+            key: "author@test.com".to_string(),
+            failed_count,
+            last_failed_at: Utc::now().naive_local() - Duration::minutes(1),
+            locked_until,
+        }
+    }
+
+    /// Builds a user with two-factor authentication enabled.
+    fn two_factor_user(secret: &str, enabled: bool) -> entity::user::Model {
+        let mut user = fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap());
+        user.totp_secret = Some(secret.to_string());
+        user.totp_enabled_at = enabled.then(|| Utc::now().naive_local());
+        user
+    }
+
+    /// Builds an unused one-time token for the given flow.
+    fn one_time_token_model(purpose: TokenPurpose) -> entity::one_time_token::Model {
+        let now = Utc::now().naive_local();
+        entity::one_time_token::Model {
+            id: 1,
+            user_id: 1,
+            purpose,
+            token_hash: hash_token("token"),
+            expires_at: now + Duration::hours(1),
+            used_at: None,
+            created_at: now,
+        }
+    }
 
     /// Test user registration endpoint.
     #[actix_web::test]
@@ -18,16 +94,13 @@ pub mod tests {
     pub async fn test_register() {
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
             .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
-            .append_query_results(vec![vec![entity::user::Model {
-                id: 1,
-                name: "Author".to_string(),
-                email: "author@test.com".to_string(),
-                password: "12345".to_string(),
-            }]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // Earlier verification tokens invalidated, then the new one is inserted
             .append_exec_results(vec![MockExecResult {
                 last_insert_id: 1,
                 rows_affected: 1,
             }])
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::EmailVerification)]])
             .into_connection();
 
         let mock_db = Arc::new(mock_db);
@@ -57,17 +130,19 @@ pub mod tests {
     }
 
 
-    /// Test user login endpoint.
+    /// Test user login endpoint with a legacy SHA-256 password, which gets rehashed.
     #[actix_web::test]
     #[serial]
     pub async fn test_login() {
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
-            .append_query_results(vec![vec![entity::user::Model {
-                id: 1,
-                name: "Author".to_string(),
-                email: "author@test.com".to_string(),
-                password: "5994471abb01112afcc18159f6cc74b4f511b99806da59b3caf5a9c173cacfc5".to_string(),
-            }]])
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "5994471abb01112afcc18159f6cc74b4f511b99806da59b3caf5a9c173cacfc5")]])
+            // Rehash of the legacy password
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // Session started and refresh token issued on login
+            .append_query_results(vec![vec![fixtures::session(1, 1, Uuid::new_v4())]])
+            .append_query_results(vec![vec![refresh_token_model("refresh", false)]])
             .into_connection();
 
         let mock_db = Arc::new(mock_db);
@@ -95,4 +170,1033 @@ pub mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
-}
\ No newline at end of file
+    /// Test user login endpoint rejects a wrong password against an Argon2id hash.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_with_wrong_password() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // Counters of the email address and client IP incremented
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Account, 1, None)]])
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Ip, 1, None)]])
+            .append_exec_results(vec![
+                // Back-off of the email address
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Failed login recorded in the audit log
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let wrong_password = auth::auth_handlers::LoginModel {
+            email: "author@test.com".to_string(),
+            password: "wrong".to_string(),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(&wrong_password)
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        drop((resp, app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains(r#"INSERT INTO \"audit_event\""#));
+        assert!(statements.contains("login_failed"));
+        // Counted by the database, so concurrent failures cannot overwrite each other
+        assert!(statements.contains(r#"ON CONFLICT (\"kind\", \"key\") DO UPDATE SET \"failed_count\" = (CASE WHEN (\"login_throttle\".\"last_failed_at\" > $"#));
+        assert!(statements.contains(r#"\"login_throttle\".\"failed_count\" + $"#));
+        assert!(statements.contains(r#"WHERE \"login_throttle\".\"id\" = $2 AND \"login_throttle\".\"failed_count\" = $3"#));
+    }
+
+    /// Test that an unknown email gets the same response as a wrong password.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_with_unknown_email() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Account, 1, None)]])
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Ip, 1, None)]])
+            // Back-off of the email address
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::LoginModel {
+                email: "nobody@test.com".to_string(),
+                password: "12345".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let body = test::read_body(resp).await;
+        assert_eq!(String::from_utf8_lossy(&body), "Invalid email or password");
+    }
+
+    /// Test that reaching the failed login limit locks the account out.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_lockout() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // One failure away from the limit, back-off already over
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Account, 4, None)]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // The failure reaches the limit, which locks the email address out
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Account, 5, None)]])
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Ip, 1, None)]])
+            // Next attempt, now locked out
+            .append_query_results(vec![vec![throttle_model(
+                ThrottleKind::Account,
+                5,
+                Some(Utc::now().naive_local() + Duration::minutes(15)),
+            )]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let login = auth::auth_handlers::LoginModel {
+            email: "author@test.com".to_string(),
+            password: "wrong".to_string(),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(&login)
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // Even the right password is refused while locked out
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::LoginModel {
+                email: "author@test.com".to_string(),
+                password: "12345".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    /// Test rotating a valid refresh token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_refresh() {
+        let stored_token = refresh_token_model("refresh", false);
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![stored_token.clone()]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // New token inserted
+            .append_query_results(vec![vec![stored_token]])
+            .append_exec_results(vec![
+                // Old token marked as rotated
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Session last seen time updated
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::RefreshTokenModel { refresh_token: "refresh".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that reusing a rotated refresh token is rejected and revokes its family.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_refresh_token_reuse() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![refresh_token_model("refresh", true)]])
+            // Session added to the revocation list
+            .append_query_results(vec![vec![session_revocation_model()]])
+            .append_exec_results(vec![
+                // Refresh token family revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Session marked as revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::RefreshTokenModel { refresh_token: "refresh".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// Test that a refresh token rotated by a concurrent request counts as reuse.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_refresh_concurrent_rotation() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![refresh_token_model("refresh", false)]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // Session added to the revocation list
+            .append_query_results(vec![vec![session_revocation_model()]])
+            .append_exec_results(vec![
+                // Token already rotated by the other request
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                // Session marked as revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Refresh token family revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::RefreshTokenModel { refresh_token: "refresh".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // No new token issued, and the session is ended
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(log.contains(r#"\"refresh_token\".\"revoked_at\" IS NULL"#));
+        assert!(!log.contains(r#"INSERT INTO \"refresh_token\""#));
+        assert!(log.contains(r#"INSERT INTO \"revoked_token\""#));
+    }
+
+    /// Test logging out revokes the refresh token family and the access token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_logout() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![refresh_token_model("refresh", false)]])
+            // Session, then the access token added to the revocation list
+            .append_query_results(vec![vec![session_revocation_model()]])
+            .append_query_results(vec![vec![entity::revoked_token::Model {
+                id: 1,
+                jti: "jti".to_string(),
+                expires_at: Utc::now().naive_local(),
+            }]])
+            .append_exec_results(vec![
+                // Refresh token family revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Session marked as revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Expired revocation entries purged
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/logout")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(auth::auth_handlers::RefreshTokenModel { refresh_token: "refresh".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that logging out twice with the same refresh token lists the session only once.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_logout_twice() {
+        let stored_token = refresh_token_model("refresh", false);
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![stored_token.clone()]])
+            // Session added to the revocation list on the first logout
+            .append_query_results(vec![vec![session_revocation_model()]])
+            .append_query_results(vec![vec![entity::refresh_token::Model {
+                revoked_at: Some(Utc::now().naive_local()),
+                ..stored_token
+            }]])
+            .append_exec_results(vec![
+                // Refresh token family and session revoked on the first logout
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Nothing left to revoke on the second logout
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState {
+            db: Arc::clone(&mock_db),
+        });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        for _ in 0..2 {
+            let req = test::TestRequest::post()
+                .uri("/auth/logout")
+                .insert_header(("Content-Type", "application/json"))
+                .set_json(auth::auth_handlers::RefreshTokenModel { refresh_token: "refresh".to_string() })
+                .to_request();
+
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        drop((app, app_state));
+        let transaction_log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert_eq!(transaction_log.matches(r#"INSERT INTO \"revoked_token\""#).count(), 1);
+    }
+
+    /// Test verifying an email address with a valid token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_verify_email() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::EmailVerification)]])
+            .append_exec_results(vec![
+                // Token marked as used
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // User marked as verified
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/auth/verify-email?token=token")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that an unknown or already used verification token is rejected.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_verify_email_invalid_token() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::one_time_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/auth/verify-email?token=token")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test that names are escaped in emails, so that they cannot carry markup or links.
+    #[actix_web::test]
+    pub async fn test_email_escapes_name() {
+        let email_body = email_service::render_template(
+            "verify_email_template.html",
+            &[
+                ("name", r#"<a href="https://evil.example">Claim your prize</a>"#),
+                ("verification_link", "http://localhost:8080/auth/verify-email?token=token"),
+            ],
+        )
+        .unwrap();
+
+        assert!(!email_body.contains("evil.example\">"));
+        assert!(email_body.contains("&lt;a href=&quot;https://evil.example&quot;&gt;Claim your prize&lt;/a&gt;"));
+        assert!(email_body.contains(r#"href="http://localhost:8080/auth/verify-email?token=token""#));
+    }
+
+    /// Test requesting a password reset link for an existing account.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_forgot_password() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // Earlier reset tokens invalidated, then the new one is inserted
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::PasswordReset)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/forgot-password")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::EmailModel { email: "author@test.com".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that the emailed password reset link opens a registered route.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_password_reset_link_route() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::PasswordReset)]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let reset_link = auth::auth_handlers::password_reset_link("token");
+        let path = reset_link.strip_prefix(contants::APP_BASE_URL.as_str()).unwrap();
+
+        let req = test::TestRequest::get().uri(path).to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // The token is checked, not used up
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(!log.contains("UPDATE"));
+    }
+
+    /// Test resetting a password with a valid reset token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_reset_password() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::PasswordReset)]])
+            // No sessions recorded yet
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::session::Model>>)
+            .append_exec_results(vec![
+                // Token marked as used
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Password updated
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Refresh tokens revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 2,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/reset-password")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::ResetPasswordModel {
+                token: "token".to_string(),
+                new_password: "new-password".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test confirming a new email address.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_confirm_email_change() {
+        let user = entity::user::Model {
+            pending_email: Some("new@test.com".to_string()),
+            ..fixtures::user(1, "Author", "author@test.com", "12345")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::EmailChange)]])
+            .append_query_results(vec![vec![user]])
+            // The new address is still free
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .append_exec_results(vec![
+                // Token marked as used
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Email address replaced
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/auth/confirm-email-change?token=token")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test requesting a magic login link.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_request_magic_link() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // Earlier login links invalidated, then the new one is inserted
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::MagicLink)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/magic-link")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::EmailModel { email: "author@test.com".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that the emailed magic link opens a registered route.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_magic_link_route() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::MagicLink)]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let login_link = auth::auth_handlers::magic_link("token");
+        let path = login_link.strip_prefix(contants::APP_BASE_URL.as_str()).unwrap();
+
+        let req = test::TestRequest::get().uri(path).to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // The token is checked, not used up
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(!log.contains("UPDATE"));
+    }
+
+    /// Test logging in with a magic link token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_consume_magic_link() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![one_time_token_model(TokenPurpose::MagicLink)]])
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // Session started and refresh token issued on login
+            .append_query_results(vec![vec![fixtures::session(1, 1, Uuid::new_v4())]])
+            .append_query_results(vec![vec![refresh_token_model("refresh", false)]])
+            // Token marked as used
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/magic-link/consume")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TokenQuery { token: "token".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that a magic link cannot be used twice.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_consume_used_magic_link() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::one_time_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/magic-link/consume")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TokenQuery { token: "token".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// Test that login with two-factor authentication enabled only returns an "mfa pending" token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_requires_two_factor() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            .append_query_results(vec![vec![two_factor_user(&generate_secret(), true)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::LoginModel {
+                email: "author@test.com".to_string(),
+                password: "12345".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("mfa_token"));
+    }
+
+    /// Test completing a two-factor login with a TOTP code.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_two_factor() {
+        let secret = generate_secret();
+        let code = build_totp(&secret, "author@test.com").unwrap().generate_current().unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![two_factor_user(&secret, true)]])
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            // Session started and refresh token issued on login
+            .append_query_results(vec![vec![fixtures::session(1, 1, Uuid::new_v4())]])
+            .append_query_results(vec![vec![refresh_token_model("refresh", false)]])
+            .append_exec_results(vec![
+                // "mfa pending" token used
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Time step of the code recorded
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login/2fa")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TwoFactorLoginModel {
+                mfa_token: encode_mfa_jwt(1).unwrap(),
+                code,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(log.contains(r#"ON CONFLICT (\"jti\") DO NOTHING"#));
+        assert!(log.contains(r#"\"totp_last_step\" < $"#));
+    }
+
+    /// Test that an "mfa pending" token cannot be used twice.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_two_factor_replayed_mfa_token() {
+        let secret = generate_secret();
+        let code = build_totp(&secret, "author@test.com").unwrap().generate_current().unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Token already recorded as used
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login/2fa")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TwoFactorLoginModel {
+                mfa_token: encode_mfa_jwt(1).unwrap(),
+                code,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// Test that a TOTP code accepted before is rejected and counted as a failed login.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_two_factor_replayed_code() {
+        let secret = generate_secret();
+        let code = build_totp(&secret, "author@test.com").unwrap().generate_current().unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![two_factor_user(&secret, true)]])
+            // Failed login counters of the email address and client IP
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::login_throttle::Model>>)
+            // Failure counted for the email address and client IP
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Account, 1, None)]])
+            .append_query_results(vec![vec![throttle_model(ThrottleKind::Ip, 1, None)]])
+            .append_exec_results(vec![
+                // "mfa pending" token used
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Time step already recorded
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                // Not a recovery code
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                // Back-off of the email address
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login/2fa")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TwoFactorLoginModel {
+                mfa_token: encode_mfa_jwt(1).unwrap(),
+                code,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert_eq!(log.matches(r#"INSERT INTO \"login_throttle\""#).count(), 2);
+    }
+
+    /// Test that two-factor logins are refused while the account is locked out.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_login_two_factor_locked_out() {
+        let secret = generate_secret();
+        let code = build_totp(&secret, "author@test.com").unwrap().generate_current().unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![two_factor_user(&secret, true)]])
+            .append_query_results(vec![vec![throttle_model(
+                ThrottleKind::Account,
+                5,
+                Some(Utc::now().naive_local() + Duration::minutes(10)),
+            )]])
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/auth/login/2fa")
+            .insert_header(("Content-Type", "application/json"))
+            .set_json(auth::auth_handlers::TwoFactorLoginModel {
+                mfa_token: encode_mfa_jwt(1).unwrap(),
+                code,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    /// Test that an "mfa pending" token cannot be used as an access token.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_mfa_token_rejected_by_middleware() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/auth/2fa/setup")
+            .insert_header(("Authorization", format!("Bearer {}", encode_mfa_jwt(1).unwrap())))
+            .to_request();
+
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(resp.err().map(|err| err.as_response_error().status_code()), Some(StatusCode::UNAUTHORIZED));
+    }
+
+    /// Test confirming two-factor enrollment returns recovery codes.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_confirm_two_factor() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let secret = generate_secret();
+        let code = build_totp(&secret, "author@test.com").unwrap().generate_current().unwrap();
+
+        let recovery_code = entity::recovery_code::Model {
+            id: 1,
+            user_id: 1,
+            code_hash: hash_token("code"),
+            used_at: None,
+            created_at: Utc::now().naive_local(),
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![two_factor_user(&secret, false)]])
+            .append_exec_results(vec![
+                // Two-factor authentication enabled
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Previous recovery codes removed
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .append_query_results(vec![vec![recovery_code]; 10])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/auth/2fa/confirm")
+            .insert_header(("Content-Type", "application/json"))
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(auth::two_factor_handlers::TwoFactorCodeModel { code })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,65 @@
+/// Shared model fixtures for the handler tests.
+/// Keeps tests independent of columns they do not care about.
+use chrono::Utc;
+use entity::article::ArticleStatus;
+use entity::subscription::DeliveryMode;
+use entity::user::UserRole;
+use uuid::Uuid;
+
+/// Builds an author model with a verified email address.
+pub fn user(id: i32, name: &str, email: &str, password: &str) -> entity::user::Model {
+    entity::user::Model {
+        id,
+        name: name.to_string(),
+        email: email.to_string(),
+        password: password.to_string(),
+        email_verified_at: Some(Utc::now().naive_local()),
+        pending_email: None,
+        totp_secret: None,
+        totp_enabled_at: None,
+        totp_last_step: None,
+        role: UserRole::Author,
+        deleted_at: None,
+        handle: None,
+        bio: None,
+        avatar_url: None,
+        website: None,
+        delivery_mode: DeliveryMode::Instant,
+        timezone: "UTC".to_string(),
+        locale: "en-US".to_string(),
+    }
+}
+
+/// Builds an article of a user.
+pub fn article(id: i32, user_id: i32, title: &str) -> entity::article::Model {
+    entity::article::Model {
+        id,
+        title: title.to_string(),
+        content: "Test Content".to_string(),
+        content_html: None,
+        content_text: None,
+        uuid: Uuid::new_v4(),
+        user_id,
+        created_at: Utc::now().naive_local(),
+        image: None,
+        status: ArticleStatus::Published,
+        publish_at: Some(Utc::now().naive_local()),
+        updated_at: Utc::now().naive_local(),
+        deleted_at: None,
+    }
+}
+
+/// Builds an active session of a user.
+pub fn session(id: i32, user_id: i32, family_id: Uuid) -> entity::session::Model {
+    let now = Utc::now().naive_local();
+    entity::session::Model {
+        id,
+        user_id,
+        family_id,
+        user_agent: Some("test-agent".to_string()),
+        ip_address: "127.0.0.1".to_string(),
+        created_at: now,
+        last_seen_at: now,
+        revoked_at: None,
+    }
+}
@@ -0,0 +1,72 @@
+/// Integration tests for access token signing keys.
+/// This module contains tests for key rotation and the JWKS endpoint.
+/// The PEM files in `testcases/keys` are throwaway keys used only by these tests.
+#[cfg(test)]
+pub mod tests {
+    use crate::{
+        utils::{
+            jwt::Claims,
+            jwt_keys::{JwtKey, KeySet},
+        },
+        well_known::well_known_routes::config,
+    };
+    use actix_web::{http::StatusCode, test, App};
+    use chrono::{Duration, Utc};
+    use entity::user::UserRole;
+    use serial_test::serial;
+
+    const ED25519_PEM: &str = include_str!("keys/test_ed25519.pem");
+    const RSA_PEM: &str = include_str!("keys/test_rsa.pem");
+
+    /// Builds access token claims expiring in a few minutes.
+    fn claims() -> Claims {
+        let now = Utc::now();
+        Claims {
+            exp: (now + Duration::minutes(5)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            email: "author@test.com".to_string(),
+            id: 1,
+            jti: "jti".to_string(),
+            role: UserRole::Author,
+            sid: None,
+        }
+    }
+
+    /// Test that tokens signed before a rotation are accepted while the old key is kept.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_key_rotation() {
+        let before_rotation = KeySet::new(vec![JwtKey::from_pem("rsa-1", RSA_PEM).unwrap()]);
+        let during_rotation = KeySet::new(vec![
+            JwtKey::from_pem("ed-2", ED25519_PEM).unwrap(),
+            JwtKey::from_pem("rsa-1", RSA_PEM).unwrap(),
+        ]);
+        let after_rotation = KeySet::new(vec![JwtKey::from_pem("ed-2", ED25519_PEM).unwrap()]);
+
+        let old_token = before_rotation.encode(&claims()).unwrap();
+        let new_token = during_rotation.encode(&claims()).unwrap();
+
+        assert!(during_rotation.decode::<Claims>(&old_token).is_ok());
+        assert!(after_rotation.decode::<Claims>(&new_token).is_ok());
+        assert!(after_rotation.decode::<Claims>(&old_token).is_err());
+
+        let jwks = during_rotation.jwks().to_string();
+        assert!(jwks.contains("\"kid\":\"ed-2\"") && jwks.contains("\"kid\":\"rsa-1\""));
+        assert!(!jwks.contains("\"d\""));
+    }
+
+    /// Test fetching the JSON Web Key Set.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_jwks() {
+        let app = test::init_service(App::new().configure(config)).await;
+
+        let req = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = test::read_body(resp).await;
+        assert!(String::from_utf8_lossy(&body).contains("\"keys\""));
+    }
+}
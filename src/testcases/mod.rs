@@ -1,15 +1,29 @@
-/// Module declarations for test cases.
-/// This module exposes unit and integration tests for various handlers.
+//! Module declarations for test cases.
+//! This module exposes unit and integration tests for various handlers.
 
 /// Module for authentication handler tests.
 pub mod auth_handler_test;
 
+/// Module for API key handler tests.
+pub mod api_key_handlers_test;
+
 /// Module for article handler tests.
 pub mod article_handlers_test;
 
+/// Module for access token signing key tests.
+pub mod jwt_keys_test;
+
+/// Module for OpenID Connect sign-in tests.
+pub mod oauth_handlers_test;
+
+/// Module for login session handler tests.
+pub mod session_handlers_test;
+
 /// Module for subscription handler tests.
 pub mod subscription_handlers_test;
 
 /// Module for user handler tests.
 pub mod user_handlers_test;
 
+/// Module for model fixtures shared by the tests.
+pub mod fixtures;
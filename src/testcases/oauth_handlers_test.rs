@@ -0,0 +1,197 @@
+/// Integration tests for OpenID Connect sign-in handlers.
+/// This module runs the authorization code flow against a local mock issuer.
+#[cfg(test)]
+pub mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use crate::{
+        auth::auth_routes::config,
+        utils::{app_state::AppState, oauth::pkce_challenge},
+    };
+    use actix_web::{
+        cookie::Cookie,
+        dev::ServiceResponse,
+        http::{header::LOCATION, StatusCode},
+        test, web, App, HttpResponse, HttpServer,
+    };
+    use chrono::Utc;
+    use reqwest::Url;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    /// Configures the `mock` provider to use the given issuer URL.
+    fn configure_mock_provider(issuer: &str) {
+        std::env::set_var("OAUTH_MOCK_CLIENT_ID", "newsletter");
+        std::env::set_var("OAUTH_MOCK_AUTHORIZE_URL", format!("{}/authorize", issuer));
+        std::env::set_var("OAUTH_MOCK_TOKEN_URL", format!("{}/token", issuer));
+        std::env::set_var("OAUTH_MOCK_USERINFO_URL", format!("{}/userinfo", issuer));
+    }
+
+    /// Starts a sign-in with the `mock` provider and reads the redirect.
+    macro_rules! start_sign_in {
+        ($app:expr) => {
+            sign_in_params(
+                &test::call_service(&$app, test::TestRequest::get().uri("/auth/oauth/mock").to_request()).await,
+            )
+        };
+    }
+
+    /// Reads the `state` parameter, the state cookie and the PKCE challenge from the redirect.
+    fn sign_in_params(resp: &ServiceResponse) -> (String, Cookie<'static>, String) {
+        assert_eq!(resp.status(), StatusCode::FOUND);
+
+        let location = Url::parse(resp.headers().get(LOCATION).unwrap().to_str().unwrap()).unwrap();
+        let params: HashMap<String, String> = location.query_pairs().into_owned().collect();
+        let cookie = resp.response().cookies().next().unwrap().into_owned();
+
+        (params["state"].clone(), cookie, params["code_challenge"].clone())
+    }
+
+    /// Test that starting a sign-in redirects to the provider with a PKCE challenge.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_oauth_start() {
+        configure_mock_provider("http://issuer.test");
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app = test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let (state, cookie, code_challenge) = start_sign_in!(app);
+
+        assert!(!state.is_empty());
+        assert_eq!(cookie.name(), "oauth_state");
+        assert!(cookie.http_only().unwrap_or(false));
+        assert_eq!(code_challenge.len(), 43);
+    }
+
+    /// Test that unconfigured providers are rejected.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_oauth_unknown_provider() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app = test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get().uri("/auth/oauth/unknown").to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Test that a callback whose state does not match the cookie is rejected.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_oauth_callback_state_mismatch() {
+        configure_mock_provider("http://issuer.test");
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app = test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let (_, cookie, _) = start_sign_in!(app);
+
+        let req = test::TestRequest::get()
+            .uri("/auth/oauth/mock/callback?code=mock-code&state=forged")
+            .cookie(cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test a full sign-in that registers a new user from the verified email address.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_oauth_callback_creates_user() {
+        // Mock issuer checking the PKCE verifier against the challenge it was sent
+        let expected_challenge = Arc::new(std::sync::Mutex::new(String::new()));
+        let issuer_challenge = Arc::clone(&expected_challenge);
+        let issuer = HttpServer::new(move || {
+            let issuer_challenge = Arc::clone(&issuer_challenge);
+            App::new()
+                .route(
+                    "/token",
+                    web::post().to(move |form: web::Form<HashMap<String, String>>| {
+                        let is_valid = form.get("code").map(String::as_str) == Some("mock-code")
+                            && form
+                                .get("code_verifier")
+                                .is_some_and(|verifier| pkce_challenge(verifier) == *issuer_challenge.lock().unwrap());
+                        async move {
+                            if is_valid {
+                                HttpResponse::Ok().json(serde_json::json!({ "access_token": "mock-access-token" }))
+                            } else {
+                                HttpResponse::BadRequest().finish()
+                            }
+                        }
+                    }),
+                )
+                .route(
+                    "/userinfo",
+                    web::get().to(|| async {
+                        HttpResponse::Ok().json(serde_json::json!({
+                            "sub": "mock-subject",
+                            "email": "reader@example.com",
+                            "email_verified": true,
+                            "name": "Reader",
+                        }))
+                    }),
+                )
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+        let issuer_address = issuer.addrs()[0];
+        let issuer = issuer.run();
+        let issuer_handle = issuer.handle();
+        actix_web::rt::spawn(issuer);
+
+        configure_mock_provider(&format!("http://{}", issuer_address));
+
+        let now = Utc::now().naive_local();
+        let new_user = entity::user::Model {
+            password: String::new(),
+            ..crate::testcases::fixtures::user(1, "Reader", "reader@example.com", "")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // No linked identity and no account with the email yet
+            .append_query_results(vec![vec![]] as Vec<Vec<(entity::oauth_identity::Model, Option<entity::user::Model>)>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .append_query_results(vec![vec![new_user]])
+            .append_query_results(vec![vec![entity::oauth_identity::Model {
+                id: 1,
+                user_id: 1,
+                provider: "mock".to_string(),
+                subject: "mock-subject".to_string(),
+                created_at: now,
+            }]])
+            .append_query_results(vec![vec![crate::testcases::fixtures::session(1, 1, Uuid::new_v4())]])
+            .append_query_results(vec![vec![entity::refresh_token::Model {
+                id: 1,
+                user_id: 1,
+                token_hash: "hash".to_string(),
+                family_id: Uuid::new_v4(),
+                expires_at: now,
+                revoked_at: None,
+                created_at: now,
+            }]])
+            .into_connection();
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app = test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let (state, cookie, code_challenge) = start_sign_in!(app);
+        *expected_challenge.lock().unwrap() = code_challenge;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/auth/oauth/mock/callback?code=mock-code&state={}", state))
+            .cookie(cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        issuer_handle.stop(false).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
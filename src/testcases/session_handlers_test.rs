@@ -0,0 +1,271 @@
+/// Integration tests for session handlers.
+/// This module contains tests for listing and ending login sessions.
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use crate::testcases::fixtures;
+
+    use crate::{
+        auth::{auth_handlers::RefreshTokenModel, auth_routes},
+        utils::{app_state::AppState, auth_tokens::hash_token, jwt::encode_jwt},
+    };
+    use actix_web::{http::StatusCode, test, web, App};
+    use chrono::{Duration, Utc};
+    use entity::user::UserRole;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    /// Test listing the active sessions.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_my_sessions() {
+        let family_id = Uuid::new_v4();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, family_id).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![
+                fixtures::session(1, 1, family_id),
+                fixtures::session(2, 1, Uuid::new_v4()),
+            ]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/secure/auth/sessions")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test ending one session.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_revoke_one_session() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::session(2, 1, Uuid::new_v4())]])
+            // Session added to the revocation list
+            .append_query_results(vec![vec![entity::revoked_token::Model {
+                id: 1,
+                jti: "session".to_string(),
+                expires_at: Utc::now().naive_local(),
+            }]])
+            .append_exec_results(vec![
+                // Refresh token family revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Session marked as revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/secure/auth/sessions/2")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that sessions of other users cannot be ended.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_revoke_unknown_session() {
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::session::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/secure/auth/sessions/2")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Test ending every other session.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_revoke_other_sessions() {
+        let family_id = Uuid::new_v4();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, family_id).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // Only the current session is active
+            .append_query_results(vec![vec![fixtures::session(1, 1, family_id)]])
+            // Refresh tokens without a session revoked
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/auth/sessions/revoke-others")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test refreshing with a token of a session that was ended, which is rejected without listing it again.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_refresh_after_session_revoked() {
+        let family_id = Uuid::new_v4();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+        let now = Utc::now().naive_local();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::session(2, 1, family_id)]])
+            // Session added to the revocation list
+            .append_query_results(vec![vec![entity::revoked_token::Model {
+                id: 1,
+                jti: format!("session:{}", family_id),
+                expires_at: now,
+            }]])
+            // Refresh token of the ended session
+            .append_query_results(vec![vec![entity::refresh_token::Model {
+                id: 1,
+                user_id: 1,
+                token_hash: hash_token("refresh"),
+                family_id,
+                expires_at: now + Duration::days(30),
+                revoked_at: Some(now),
+                created_at: now,
+            }]])
+            .append_exec_results(vec![
+                // Refresh token family and session revoked
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Already revoked when the token is presented again
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri("/secure/auth/sessions/2")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::post()
+            .uri("/auth/refresh")
+            .set_json(RefreshTokenModel { refresh_token: "refresh".to_string() })
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// Test that access tokens of an ended session are rejected.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_revoked_session_token_rejected() {
+        let family_id = Uuid::new_v4();
+        let token = encode_jwt("author@example.com".to_string(), 1, UserRole::Author, family_id).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![entity::revoked_token::Model {
+                id: 1,
+                jti: format!("session:{}", family_id),
+                expires_at: Utc::now().naive_local(),
+            }]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app = test::init_service(
+            App::new().app_data(app_state.clone()).configure(auth_routes::config),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/secure/auth/sessions")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(resp.err().map(|err| err.as_response_error().status_code()), Some(StatusCode::UNAUTHORIZED));
+    }
+}
@@ -4,7 +4,11 @@
 pub mod tests {
     use std::sync::Arc;
 
-    use crate::subscription::subscription_handlers::SubscriptionRequest;
+    use crate::testcases::fixtures;
+
+    use crate::subscription::subscription_handlers::{
+        DeliveryModeRequest, SubscriptionDeliveryRequest, SubscriptionRequest,
+    };
     use crate::subscription::subscription_routes::config;
     use crate::utils::app_state::AppState;
     use crate::utils::jwt::encode_jwt;
@@ -12,16 +16,21 @@ pub mod tests {
     use actix_web::web;
     use actix_web::{test, App};
     use chrono::Utc;
+    use entity::subscription::DeliveryMode;
+    use entity::user::UserRole;
     use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
     use serial_test::serial;
+    use uuid::Uuid;
 
     /// Test subscribing to a user.
     #[actix_web::test]
     #[serial]
     pub async fn test_subscribe_user() {
-        let token = encode_jwt("author@example.com".to_string(), 2).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
 
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
             // First query: Check for existing subscription (returns None)
             .append_query_results(vec![vec![]] as Vec<Vec<entity::subscription::Model>>)
             // Second query: Insert new subscription
@@ -29,6 +38,7 @@ pub mod tests {
                 id: 1,
                 subscribed_user_id: 1,
                 subscriber_user_id: 2,
+                delivery_mode: None,
                 created_at: Utc::now().naive_local(),
             }]])
             .append_exec_results(vec![MockExecResult {
@@ -46,7 +56,7 @@ pub mod tests {
         let app =
             test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
 
-        let subscription_request = SubscriptionRequest { user_id: 1 };
+        let subscription_request = SubscriptionRequest { user_id: 1, delivery_mode: Some(DeliveryMode::Daily) };
 
         let req = test::TestRequest::post()
             .uri("/secure/subscription/subscribe-user")
@@ -64,19 +74,34 @@ pub mod tests {
     #[actix_web::test]
     #[serial]
     pub async fn test_unsubscribe_user() {
-        let token = encode_jwt("author@example.com".to_string(), 2).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
 
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
             .append_query_results(vec![vec![entity::subscription::Model {
                 id: 1,
                 subscribed_user_id: 1,
                 subscriber_user_id: 2,
+                delivery_mode: None,
                 created_at: Utc::now().naive_local(),
             }]])
-            .append_exec_results(vec![MockExecResult {
-                last_insert_id: 1,
-                rows_affected: 1,
-            }])
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 1,
+                    rows_affected: 1,
+                },
+                // Queued digest items of the author dropped
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+                // Unsubscribe recorded in the audit log
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
             .into_connection();
 
         let mock_db = Arc::new(mock_db);
@@ -103,19 +128,32 @@ pub mod tests {
      #[actix_web::test]
      #[serial]
      pub async fn test_unsubscribe_user_from_email() {
-         let token = encode_jwt("author@example.com".to_string(), 2).unwrap();
+         let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
  
          let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
              .append_query_results(vec![vec![entity::subscription::Model {
                  id: 1,
                  subscribed_user_id: 1,
                  subscriber_user_id: 2,
+                 delivery_mode: None,
                  created_at: Utc::now().naive_local(),
              }]])
-             .append_exec_results(vec![MockExecResult {
-                 last_insert_id: 1,
-                 rows_affected: 1,
-             }])
+             .append_exec_results(vec![
+                 MockExecResult {
+                     last_insert_id: 1,
+                     rows_affected: 1,
+                 },
+                 // Queued digest items of the author dropped
+                 MockExecResult {
+                     last_insert_id: 0,
+                     rows_affected: 0,
+                 },
+                 // Unsubscribe recorded in the audit log
+                 MockExecResult {
+                     last_insert_id: 0,
+                     rows_affected: 1,
+                 },
+             ])
              .into_connection();
  
          let mock_db = Arc::new(mock_db);
@@ -142,22 +180,20 @@ pub mod tests {
     #[actix_web::test]
     #[serial]
     pub async fn test_my_subscriptions() {
-        let token = encode_jwt("author@example.com".to_string(), 2).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
 
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
             .append_query_results(vec![vec![(
                 entity::subscription::Model {
                     id: 1,
                     subscribed_user_id: 1,
                     subscriber_user_id: 2,
+                    delivery_mode: None,
                     created_at: Utc::now().naive_local(),
                 },
-                Some(entity::user::Model {
-                    id: 1,
-                    name: "Test User".to_string(),
-                    email: "testuser@example.com".to_string(),
-                    password: "hashed_password".to_string(),
-                }),
+                Some(fixtures::user(1, "Test User", "testuser@example.com", "hashed_password")),
             )]])
             .into_connection();
 
@@ -185,22 +221,20 @@ pub mod tests {
     #[actix_web::test]
     #[serial]
     pub async fn test_my_subscribers() {
-        let token = encode_jwt("author@example.com".to_string(), 2).unwrap();
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
 
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
             .append_query_results(vec![vec![(
                 entity::subscription::Model {
                     id: 1,
                     subscribed_user_id: 2,
                     subscriber_user_id: 1,
+                    delivery_mode: None,
                     created_at: Utc::now().naive_local(),
                 },
-                Some(entity::user::Model {
-                    id: 1,
-                    name: "Subscriber User".to_string(),
-                    email: "subscriber@example.com".to_string(),
-                    password: "hashed_password".to_string(),
-                }),
+                Some(fixtures::user(1, "Subscriber User", "subscriber@example.com", "hashed_password")),
             )]])
             .into_connection();
 
@@ -223,4 +257,66 @@ pub mod tests {
 
         assert_eq!(resp.status(), StatusCode::OK);
     }
-}
\ No newline at end of file
+
+    /// Test changing the default delivery mode.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_set_default_delivery() {
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/subscription/delivery")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(DeliveryModeRequest { mode: DeliveryMode::Weekly })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that only existing subscriptions get a delivery mode.
+    #[actix_web::test]
+    #[serial]
+    pub async fn test_set_subscription_delivery_not_subscribed() {
+        let token = encode_jwt("author@example.com".to_string(), 2, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/subscription/delivery/3")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(SubscriptionDeliveryRequest { mode: Some(DeliveryMode::None) })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -2,30 +2,47 @@
 /// This module contains tests for retrieving user information.
 #[cfg(test)]
 pub mod tests {
-    use std::sync::Arc;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use crate::testcases::fixtures;
 
     use crate::{
-        user::user_routes::config,
-        utils::{app_state::AppState, jwt::encode_jwt},
+        user::{
+            user_handlers::{
+                AuthorDirectoryModel, AuthorProfileModel, ChangeEmailModel, ChangeNameModel,
+                ChangePasswordModel, DeleteAccountModel, RoleModel, UpdateProfileModel,
+                UpdateSettingsModel,
+            },
+            user_routes::config,
+        },
+        utils::{
+            account_erasure::erase_account, app_state::AppState, auth_tokens::hash_token,
+            data_export::build_archive, jwt::encode_jwt, locale::UserLocale,
+            pagination::encode_cursor, password::hash_password,
+        },
     };
     use actix_web::{http::StatusCode, test, web, App};
-    use sea_orm::{DatabaseBackend, MockDatabase};
+    use chrono::{Duration, NaiveDate, Utc};
+    use entity::account_deletion::ArticleErasure;
+    use entity::audit_event::AuditAction;
+    use entity::data_export::DataExportStatus;
+    use entity::one_time_token::TokenPurpose;
+    use entity::user::UserRole;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
     use serial_test::serial;
+    use uuid::Uuid;
 
     /// Test retrieving a user profile.
     #[actix_web::test]
     #[serial]
     pub async fn test_get_user() {
-        let token = encode_jwt("author@test.com".to_string(), 1).unwrap();
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
 
         // Mock database with a sample user
         let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
-            .append_query_results(vec![vec![entity::user::Model {
-                id: 1,
-                name: "Author".to_string(),
-                email: "author@test.com".to_string(),
-                password: "12345".to_string(),
-            }]])
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
             .into_connection();
 
         let mock_db = Arc::new(mock_db);
@@ -48,32 +65,1104 @@ pub mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    /// Builds a directory row as returned by the author directory query.
+    fn directory_row(id: i32, handle: &str, subscriber_count: i64) -> BTreeMap<&'static str, Value> {
+        BTreeMap::from([
+            ("id", Value::Int(Some(id))),
+            ("handle", Value::String(Some(Box::new(handle.to_string())))),
+            ("name", Value::String(Some(Box::new(format!("Author {}", id))))),
+            ("bio", Value::String(None)),
+            ("avatar_url", Value::String(None)),
+            ("subscriber_count", Value::BigInt(Some(subscriber_count))),
+        ])
+    }
+
+    /// Test searching the author directory by subscriber count, one page at a time.
     #[actix_web::test]
     #[serial]
-    async fn test_get_all_users() {
-        let token = encode_jwt("author@test.com".to_string(), 1).unwrap();
+    async fn test_author_directory() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // The requested author plus one telling that there is a next page
+            .append_query_results(vec![vec![directory_row(2, "jane-doe", 5), directory_row(1, "jane-roe", 3)]])
+            // Total number of matches
+            .append_query_results(vec![vec![BTreeMap::from([("num_items", Value::BigInt(Some(2)))])]])
+            .into_connection();
 
-        let mock_db = MockDatabase::new(sea_orm::DatabaseBackend::Postgres)
-            .append_query_results(vec![vec![entity::user::Model {
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/author?q=Jane&sort=subscribers&limit=1")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let directory: AuthorDirectoryModel = serde_json::from_str(&body).unwrap();
+        assert_eq!(directory.authors.len(), 1);
+        assert_eq!(directory.authors[0].handle, "jane-doe");
+        assert_eq!(directory.total, 2);
+        assert_eq!(
+            directory.next_cursor.as_deref(),
+            Some(encode_cursor("subscribers", &[5, 2]).as_str())
+        );
+        assert!(!body.contains("email"));
+
+        drop((app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains("LEFT JOIN"));
+        assert!(statements.contains("%jane%"));
+    }
+
+    /// Test that a cursor of another sort order is rejected.
+    #[actix_web::test]
+    #[serial]
+    async fn test_author_directory_invalid_cursor() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/author?sort=newest&cursor={}", encode_cursor("subscribers", &[5, 2])))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test changing the role of a user as an admin.
+    #[actix_web::test]
+    #[serial]
+    async fn test_set_role() {
+        let token = encode_jwt("admin@test.com".to_string(), 1, UserRole::Admin, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                // Role change recorded in the audit log
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/user/set-role/2")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(RoleModel { role: UserRole::Reader })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Builds an audit event of the given user.
+    fn audit_event_model(id: i32, user_id: i32, action: AuditAction) -> entity::audit_event::Model {
+        entity::audit_event::Model {
+            id,
+            user_id: Some(user_id),
+            actor_id: Some(user_id),
+            action,
+            ip_address: "127.0.0.1".to_string(),
+            details: None,
+            created_at: Utc::now().naive_local(),
+        }
+    }
+
+    /// Test listing the audit events of the authenticated user.
+    #[actix_web::test]
+    #[serial]
+    async fn test_my_audit_events() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![
+                audit_event_model(2, 1, AuditAction::PasswordChanged),
+                audit_event_model(1, 1, AuditAction::LoginSucceeded),
+            ]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/secure/user/audit-events?before=3&limit=500")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        assert!(body.contains("password_changed"));
+        assert!(body.contains("login_succeeded"));
+    }
+
+    /// Test searching the audit log as an admin.
+    #[actix_web::test]
+    #[serial]
+    async fn test_audit_events() {
+        let token = encode_jwt("admin@test.com".to_string(), 1, UserRole::Admin, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![audit_event_model(1, 2, AuditAction::LoginFailed)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/user/audit-events?user_id=2&action=login_failed&from=2025-01-01T00:00:00")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that users without the `ManageUsers` permission cannot search the audit log.
+    #[actix_web::test]
+    #[serial]
+    async fn test_audit_events_forbidden() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/user/audit-events")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(resp.err().map(|err| err.as_response_error().status_code()), Some(StatusCode::FORBIDDEN));
+    }
+
+    /// Test that a revoked access token is rejected by the auth middleware.
+    #[actix_web::test]
+    #[serial]
+    async fn test_revoked_token() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![entity::revoked_token::Model {
                 id: 1,
-                name: "Test User".to_string(),
-                email: "test@example.com".to_string(),
-                password: "12345".to_string(),
+                jti: "revoked".to_string(),
+                expires_at: chrono::Utc::now().naive_local(),
             }]])
             .into_connection();
 
         let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
-        let app = 
-        test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
-        
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
         let req = test::TestRequest::get()
-            .uri("/user/get-all-users")
-            .insert_header(("Content-Type", "application/json"))
+            .uri("/user/get-user")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::try_call_service(&app, req).await;
+
+        assert_eq!(resp.err().map(|err| err.as_response_error().status_code()), Some(StatusCode::UNAUTHORIZED));
+    }
+
+    /// Builds a pending account deletion.
+    fn account_deletion_model(articles: ArticleErasure) -> entity::account_deletion::Model {
+        let now = Utc::now().naive_local();
+        entity::account_deletion::Model {
+            id: 1,
+            user_id: 1,
+            articles,
+            requested_at: now - Duration::days(14),
+            scheduled_for: now,
+        }
+    }
+
+    /// Test requesting the deletion of the own account.
+    #[actix_web::test]
+    #[serial]
+    async fn test_request_account_deletion() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // No deletion pending yet
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::account_deletion::Model>>)
+            .append_query_results(vec![vec![account_deletion_model(ArticleErasure::Anonymize)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/delete-account")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(DeleteAccountModel {
+                password: "12345".to_string(),
+                articles: ArticleErasure::Anonymize,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that account deletion requires the current password.
+    #[actix_web::test]
+    #[serial]
+    async fn test_request_account_deletion_wrong_password() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/delete-account")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(DeleteAccountModel {
+                password: "wrong".to_string(),
+                articles: ArticleErasure::Delete,
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test that an account without a password confirms its deletion with a recent sign-in.
+    #[actix_web::test]
+    #[serial]
+    async fn test_request_account_deletion_without_password() {
+        let family_id = Uuid::new_v4();
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, family_id).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "")]])
+            // Signed in a minute ago
+            .append_query_results(vec![vec![fixtures::session(1, 1, family_id)]])
+            // No deletion pending yet
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::account_deletion::Model>>)
+            .append_query_results(vec![vec![account_deletion_model(ArticleErasure::Anonymize)]])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/delete-account")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(serde_json::json!({ "articles": "anonymize" }))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let log = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(log.contains(r#"\"session\".\"created_at\" > $"#));
+    }
+
+    /// Test that an account without a password cannot confirm changes long after signing in.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_email_without_password_stale_sign_in() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "")]])
+            // No session signed in recently
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::session::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/change-email")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangeEmailModel {
+                new_email: "new@test.com".to_string(),
+                password: String::new(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test cancelling a pending account deletion.
+    #[actix_web::test]
+    #[serial]
+    async fn test_cancel_account_deletion() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::delete()
+            .uri("/secure/user/delete-account")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test erasing an account that keeps its articles anonymously.
+    #[actix_web::test]
+    #[serial]
+    async fn test_erase_account_anonymize() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "Author@Test.com", "12345")]])
+            // No active sessions left
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::session::Model>>)
+            // Legacy refresh tokens revoked, then queued digest items, subscriptions, tokens,
+            // sessions, recovery codes, API keys, identities, login counters, data exports and
+            // the deletion request deleted, audit events scrubbed, the user anonymized and the
+            // erasure recorded
+            .append_exec_results(
+                (0..15)
+                    .map(|_| MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .into_connection();
+
+        erase_account(&mock_db, &account_deletion_model(ArticleErasure::Anonymize))
+            .await
+            .unwrap();
+
+        let transaction_log = mock_db.into_transaction_log();
+        let statements = format!("{:?}", transaction_log);
+
+        assert_eq!(transaction_log.len(), 1, "Erasure runs in one transaction");
+        assert!(statements.contains(r#"DELETE FROM \"digest_item\""#));
+        assert!(statements.contains(r#"DELETE FROM \"subscription\""#));
+        assert!(!statements.contains(r#"DELETE FROM \"article\""#));
+        assert!(statements.contains(r#"UPDATE \"user\""#));
+        assert!(statements.contains(r#"UPDATE \"audit_event\""#));
+        assert!(statements.contains(r#"INSERT INTO \"audit_event\""#));
+        // Login counters are keyed by the normalized address
+        assert!(statements.contains(r#"String(Some("author@test.com"))"#));
+    }
+
+    /// Builds a data export with the given status.
+    fn data_export_model(status: DataExportStatus) -> entity::data_export::Model {
+        let now = Utc::now().naive_local();
+        entity::data_export::Model {
+            id: 1,
+            user_id: 1,
+            status,
+            archive: (status == DataExportStatus::Ready).then(|| b"PK".to_vec()),
+            token_hash: (status == DataExportStatus::Ready).then(|| hash_token("token")),
+            created_at: now,
+            expires_at: (status == DataExportStatus::Ready).then(|| now + Duration::hours(48)),
+        }
+    }
+
+    /// Test requesting a data export.
+    #[actix_web::test]
+    #[serial]
+    async fn test_request_export() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", "12345")]])
+            // No export being built
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::data_export::Model>>)
+            .append_query_results(vec![vec![data_export_model(DataExportStatus::Pending)]])
+            // Earlier exports discarded
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 0,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/export")
             .insert_header(("Authorization", format!("Bearer {}", token)))
             .to_request();
 
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    }
+
+    /// Test downloading a built data export.
+    #[actix_web::test]
+    #[serial]
+    async fn test_download_export() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![data_export_model(DataExportStatus::Ready)]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/export/download?token=token")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("content-type").unwrap(), "application/zip");
+    }
+
+    /// Test that unknown or expired download links are rejected.
+    #[actix_web::test]
+    #[serial]
+    async fn test_download_expired_export() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::data_export::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/export/download?token=token")
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Test the contents of a data export archive.
+    #[actix_web::test]
+    #[serial]
+    async fn test_build_archive() {
+        let user = fixtures::user(1, "Author", "author@test.com", "secret-hash");
+        // Written as a draft the day before it was published
+        let published_at = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        let article = entity::article::Model {
+            created_at: published_at - Duration::days(1),
+            publish_at: Some(published_at),
+            ..fixtures::article(1, 1, "Test Article")
+        };
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![article.clone()]])
+            // Subscribed to one author, no subscribers
+            .append_query_results(vec![vec![entity::subscription::Model {
+                id: 1,
+                subscribed_user_id: 2,
+                subscriber_user_id: 1,
+                delivery_mode: None,
+                created_at: Utc::now().naive_local(),
+            }]])
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::subscription::Model>>)
+            .append_query_results(vec![vec![fixtures::user(2, "Other", "other@test.com", "12345")]])
+            .append_query_results(vec![vec![fixtures::session(1, 1, Uuid::new_v4())]])
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::api_key::Model>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::oauth_identity::Model>>)
+            // Queued for a digest of the subscriber
+            .append_query_results(vec![vec![(
+                entity::digest_item::Model {
+                    id: 1,
+                    subscriber_user_id: 1,
+                    article_id: 2,
+                    deliver_after: Utc::now().naive_local(),
+                    created_at: Utc::now().naive_local(),
+                },
+                Some(fixtures::article(2, 2, "Queued Article")),
+            )]])
+            .append_query_results(vec![vec![entity::audit_event::Model {
+                id: 1,
+                user_id: Some(1),
+                actor_id: Some(1),
+                action: AuditAction::PasswordChanged,
+                ip_address: "10.0.0.1".to_string(),
+                details: None,
+                created_at: Utc::now().naive_local(),
+            }]])
+            .into_connection();
+
+        let archive = build_archive(&mock_db, &user).await.unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+        let mut file_names: Vec<&str> = archive.file_names().collect();
+        file_names.sort();
+
+        let article_file = format!("articles/{}.md", article.uuid);
+        assert_eq!(
+            file_names,
+            vec![
+                "api_keys.json",
+                "articles.json",
+                article_file.as_str(),
+                "audit_events.json",
+                "digest_queue.json",
+                "linked_accounts.json",
+                "profile.json",
+                "sessions.json",
+                "subscriptions.json",
+            ]
+        );
+
+        let mut profile = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("profile.json").unwrap(), &mut profile).unwrap();
+        assert!(profile.contains("author@test.com"));
+
+        let mut article_markdown = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name(&article_file).unwrap(), &mut article_markdown).unwrap();
+        assert!(article_markdown.contains("_Published 2025-06-11 09:00:00_"));
+        assert!(!profile.contains("secret-hash"));
+
+        let mut subscriptions = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("subscriptions.json").unwrap(), &mut subscriptions).unwrap();
+        assert!(subscriptions.contains("Other"));
+        assert!(!subscriptions.contains("other@test.com"));
+
+        let mut digest_queue = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("digest_queue.json").unwrap(), &mut digest_queue).unwrap();
+        assert!(digest_queue.contains("Queued Article"));
+
+        let mut audit_events = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("audit_events.json").unwrap(), &mut audit_events).unwrap();
+        assert!(audit_events.contains("password_changed"));
+        assert!(audit_events.contains("10.0.0.1"));
+    }
+
+    /// Test changing the own name.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_name() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/user/change-name")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangeNameModel { name: "New Name".to_string() })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test changing the own timezone and locale; locale tags are stored with a dash.
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_settings() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let mock_db = Arc::new(mock_db);
+        let app_state = web::Data::new(AppState { db: Arc::clone(&mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/user/settings")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(UpdateSettingsModel {
+                timezone: Some("Europe/Berlin".to_string()),
+                locale: Some("de_DE".to_string()),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        drop((resp, app, app_state));
+        let statements = format!("{:?}", Arc::try_unwrap(mock_db).unwrap().into_transaction_log());
+        assert!(statements.contains("Europe/Berlin"));
+        assert!(statements.contains("de-DE"));
+    }
+
+    /// Test that unknown timezones and locales are rejected.
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_settings_invalid() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        for (timezone, locale) in [(Some("Mars/Olympus_Mons"), None), (None, Some("xx-YY"))] {
+            let req = test::TestRequest::put()
+                .uri("/secure/user/settings")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(UpdateSettingsModel {
+                    timezone: timezone.map(str::to_string),
+                    locale: locale.map(str::to_string),
+                })
+                .to_request();
+
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    /// Test formatting dates in the timezone and language of a user.
+    #[actix_web::test]
+    async fn test_user_locale_format() {
+        let user = entity::user::Model {
+            timezone: "Europe/Berlin".to_string(),
+            locale: "de-DE".to_string(),
+            ..fixtures::user(1, "Leser", "leser@example.com", "password")
+        };
+        let locale = UserLocale::of(&user);
+        let published_at = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap().and_hms_opt(15, 30, 0).unwrap();
+
+        assert_eq!(locale.language(), "de");
+        assert_eq!(locale.format(published_at), "11 Juni 2025, 17:30 CEST");
+        assert_eq!(locale.timestamp(published_at).to_rfc3339(), "2025-06-11T17:30:00+02:00");
+
+        let fallback = UserLocale::of(&entity::user::Model {
+            timezone: "Nowhere".to_string(),
+            locale: "xx".to_string(),
+            ..user
+        });
+        assert_eq!(fallback.format(published_at), "11 June 2025, 15:30 UTC");
+    }
+
+    /// Builds a profile update with the given handle and website.
+    fn profile_update(handle: &str, website: &str) -> UpdateProfileModel {
+        UpdateProfileModel {
+            handle: Some(handle.to_string()),
+            bio: Some("Writes about Rust.".to_string()),
+            avatar_url: None,
+            website: Some(website.to_string()),
+        }
+    }
+
+    /// Test updating the own public profile.
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_profile() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            // No other user has the handle
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .append_exec_results(vec![MockExecResult {
+                last_insert_id: 0,
+                rows_affected: 1,
+            }])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/user/profile")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(profile_update("jane-doe", "https://jane.example.com"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that invalid handles and non-web links are rejected.
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_profile_invalid() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookups in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        for profile in [
+            profile_update("Jane Doe", "https://jane.example.com"),
+            profile_update("jane-doe", "javascript:alert(1)"),
+        ] {
+            let req = test::TestRequest::put()
+                .uri("/secure/user/profile")
+                .insert_header(("Authorization", format!("Bearer {}", token)))
+                .set_json(profile)
+                .to_request();
+
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    /// Test that a handle of another user cannot be taken.
+    #[actix_web::test]
+    #[serial]
+    async fn test_update_profile_handle_taken() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![entity::user::Model {
+                handle: Some("jane-doe".to_string()),
+                ..fixtures::user(2, "Jane", "jane@test.com", "12345")
+            }]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::put()
+            .uri("/secure/user/profile")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(profile_update("jane-doe", "https://jane.example.com"))
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+    }
+
+    /// Test the public author page, which leaves out the email address.
+    #[actix_web::test]
+    #[serial]
+    async fn test_author_profile() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![entity::user::Model {
+                handle: Some("jane-doe".to_string()),
+                bio: Some("Writes about Rust.".to_string()),
+                ..fixtures::user(1, "Jane", "jane@test.com", "12345")
+            }]])
+            // Subscriber count
+            .append_query_results(vec![vec![BTreeMap::from([("num_items", Value::BigInt(Some(3)))])]])
+            .append_query_results(vec![vec![fixtures::article(1, 1, "Latest Article")]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get().uri("/author/jane-doe").to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+        let profile: AuthorProfileModel = serde_json::from_str(&body).unwrap();
+        assert_eq!(profile.subscriber_count, 3);
+        assert_eq!(profile.recent_articles[0].title, "Latest Article");
+        assert!(!body.contains("jane@test.com"));
+    }
+
+    /// Test that unknown handles have no author page.
+    #[actix_web::test]
+    #[serial]
+    async fn test_author_profile_not_found() {
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::get().uri("/author/nobody").to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Test changing the own password, which ends the other sessions.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_password() {
+        let current_session = Uuid::new_v4();
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, current_session).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // The current and one other session are active
+            .append_query_results(vec![vec![
+                fixtures::session(1, 1, current_session),
+                fixtures::session(2, 1, Uuid::new_v4()),
+            ]])
+            // The other session added to the revocation list
+            .append_query_results(vec![vec![entity::revoked_token::Model {
+                id: 1,
+                jti: "session".to_string(),
+                expires_at: Utc::now().naive_local(),
+            }]])
+            // Password updated, the other session's refresh tokens and row revoked,
+            // then legacy refresh tokens revoked
+            .append_exec_results(
+                (0..4)
+                    .map(|_| MockExecResult {
+                        last_insert_id: 0,
+                        rows_affected: 1,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/change-password")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangePasswordModel {
+                current_password: "12345".to_string(),
+                new_password: "new-password".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that changing the password requires the current one.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_password_wrong_current_password() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/change-password")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangePasswordModel {
+                current_password: "wrong".to_string(),
+                new_password: "new-password".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Test requesting an email change, which is confirmed from the new address.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_email() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            // The new address is not registered
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::user::Model>>)
+            .append_query_results(vec![vec![entity::one_time_token::Model {
+                id: 1,
+                user_id: 1,
+                purpose: TokenPurpose::EmailChange,
+                token_hash: hash_token("token"),
+                expires_at: Utc::now().naive_local() + Duration::hours(1),
+                used_at: None,
+                created_at: Utc::now().naive_local(),
+            }]])
+            // Pending address stored, earlier change tokens invalidated
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                },
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 0,
+                },
+            ])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/change-email")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangeEmailModel {
+                new_email: "new@test.com".to_string(),
+                password: "12345".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    /// Test that an email address of another account cannot be taken over.
+    #[actix_web::test]
+    #[serial]
+    async fn test_change_email_taken() {
+        let token = encode_jwt("author@test.com".to_string(), 1, UserRole::Author, Uuid::new_v4()).unwrap();
+
+        let mock_db = MockDatabase::new(DatabaseBackend::Postgres)
+            // Revocation list lookup in the auth middleware
+            .append_query_results(vec![vec![]] as Vec<Vec<entity::revoked_token::Model>>)
+            .append_query_results(vec![vec![fixtures::user(1, "Author", "author@test.com", &hash_password("12345").unwrap())]])
+            .append_query_results(vec![vec![fixtures::user(2, "Other", "other@test.com", "12345")]])
+            .into_connection();
+
+        let app_state = web::Data::new(AppState { db: Arc::new(mock_db) });
+
+        let app =
+            test::init_service(App::new().app_data(app_state.clone()).configure(config)).await;
+
+        let req = test::TestRequest::post()
+            .uri("/secure/user/change-email")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(ChangeEmailModel {
+                new_email: "other@test.com".to_string(),
+                password: "12345".to_string(),
+            })
+            .to_request();
+
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
     }
-}
\ No newline at end of file
+}
@@ -1,21 +1,231 @@
 use std::{str, sync::Arc};
 
-use actix_web::{get, web};
-use sea_orm::EntityTrait;
+use actix_web::{delete, get, http::header, post, put, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Utc};
+use entity::account_deletion::ArticleErasure;
+use entity::article::ArticleStatus;
+use entity::audit_event::AuditAction;
+use entity::data_export::DataExportStatus;
+use entity::user::UserRole;
+use entity::one_time_token::TokenPurpose;
+use sea_orm::{
+    sea_query::{Expr, Func, LikeExpr, SimpleExpr},
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, FromQueryResult, JoinType, Order,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select, Set,
+    TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
  
+use crate::email::email_service;
 use crate::utils::{
     api_response::{self},
     app_state,
+    audit,
+    auth_tokens::{hash_token, issue_one_time_token, revoke_all_for_user},
+    contants,
+    data_export::spawn_export,
     jwt::Claims,
+    locale::{parse_locale, parse_timezone, UserLocale},
+    login_throttle::client_ip,
+    pagination::{decode_cursor, encode_cursor, page_limit},
+    password::{hash_password, verify_password, PasswordCheck},
+    scopes::{GrantedScopes, Scope},
 };
 
-///Represents a user with minimal details.
-#[derive(Serialize,Deserialize)]
-pub struct UserModel {
+/// Request model for changing the role of a user.
+#[derive(Serialize, Deserialize)]
+pub struct RoleModel {
+    pub role: UserRole,
+}
+
+/// Request model for deleting the authenticated user's account.
+#[derive(Serialize, Deserialize)]
+pub struct DeleteAccountModel {
+    /// The current password, confirming the request; left out by accounts without a password.
+    #[serde(default)]
+    pub password: String,
+    /// Whether the user's articles are deleted or kept anonymously.
+    pub articles: ArticleErasure,
+}
+
+/// Request model for changing the name of the authenticated user.
+#[derive(Serialize, Deserialize)]
+pub struct ChangeNameModel {
+    pub name: String,
+}
+
+/// Request model for changing the password of the authenticated user.
+#[derive(Serialize, Deserialize)]
+pub struct ChangePasswordModel {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Request model for changing the email address of the authenticated user.
+#[derive(Serialize, Deserialize)]
+pub struct ChangeEmailModel {
+    pub new_email: String,
+    /// The current password, confirming the request; left out by accounts without a password.
+    #[serde(default)]
+    pub password: String,
+}
+
+/// Request model for changing the timezone and locale of the authenticated user.
+/// Fields that are left out keep their current value.
+#[derive(Serialize, Deserialize)]
+pub struct UpdateSettingsModel {
+    /// IANA timezone name, e.g. `Europe/Berlin`.
+    pub timezone: Option<String>,
+    /// Locale tag, e.g. `de-DE`.
+    pub locale: Option<String>,
+}
+
+/// Request model for updating the public profile of the authenticated user.
+/// Fields that are left out or empty are cleared.
+#[derive(Serialize, Deserialize)]
+pub struct UpdateProfileModel {
+    /// Unique handle of the author page: 3 to 30 lowercase letters, digits and dashes.
+    pub handle: Option<String>,
+    pub bio: Option<String>,
+    /// An `http` or `https` URL.
+    pub avatar_url: Option<String>,
+    /// An `http` or `https` URL.
+    pub website: Option<String>,
+}
+
+/// Represents an article listed on an author page.
+#[derive(Serialize, Deserialize)]
+pub struct AuthorArticleModel {
+    pub uuid: Uuid,
+    pub title: String,
+    pub image: Option<String>,
+    /// In UTC.
+    pub created_at: DateTime<FixedOffset>,
+}
+
+/// Order of the author directory.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorSort {
+    /// Most recently registered authors first.
+    #[default]
+    Newest,
+    /// Authors with the most subscribers first.
+    Subscribers,
+}
+
+impl AuthorSort {
+    /// Returns the name of the order, as used in cursors.
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuthorSort::Newest => "newest",
+            AuthorSort::Subscribers => "subscribers",
+        }
+    }
+}
+
+/// Query model for browsing the author directory.
+#[derive(Serialize, Deserialize)]
+pub struct AuthorDirectoryQuery {
+    /// Part of the name or handle, matched case-insensitively.
+    pub q: Option<String>,
+    #[serde(default)]
+    pub sort: AuthorSort,
+    /// The `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+    /// Number of authors per page, capped at `DIRECTORY_MAX_LIMIT`.
+    pub limit: Option<u64>,
+}
+
+/// Represents an author listed in the directory. Never includes the email address.
+#[derive(Serialize, Deserialize, FromQueryResult)]
+pub struct AuthorSummaryModel {
+    pub id: i32,
+    pub handle: String,
+    pub name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub subscriber_count: i64,
+}
+
+/// Represents one page of the author directory.
+#[derive(Serialize, Deserialize)]
+pub struct AuthorDirectoryModel {
+    pub authors: Vec<AuthorSummaryModel>,
+    /// Number of authors matching the search, across all pages.
+    pub total: u64,
+    /// Cursor of the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+/// Represents the public page of an author. Never includes the email address.
+#[derive(Serialize, Deserialize)]
+pub struct AuthorProfileModel {
     pub id: i32,
+    pub handle: String,
     pub name: String,
-    pub email: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub website: Option<String>,
+    pub subscriber_count: u64,
+    /// The latest articles, newest first.
+    pub recent_articles: Vec<AuthorArticleModel>,
+}
+
+/// Represents an entry of the audit log.
+#[derive(Serialize, Deserialize)]
+pub struct AuditEventModel {
+    pub id: i32,
+    pub user_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub action: AuditAction,
+    pub ip_address: String,
+    pub details: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Query model for paging through the audit log, newest events first.
+#[derive(Serialize, Deserialize)]
+pub struct AuditPageQuery {
+    /// Only events with a smaller ID, i.e. the `id` of the last event of the previous page.
+    pub before: Option<i32>,
+    /// Maximum number of events, capped at `AUDIT_EVENTS_MAX_LIMIT`.
+    pub limit: Option<u64>,
+}
+
+/// Query model for searching the audit log of all users.
+#[derive(Serialize, Deserialize)]
+pub struct AuditSearchQuery {
+    pub user_id: Option<i32>,
+    pub action: Option<AuditAction>,
+    /// Only events at or after this time.
+    pub from: Option<NaiveDateTime>,
+    /// Only events before this time.
+    pub to: Option<NaiveDateTime>,
+    pub before: Option<i32>,
+    pub limit: Option<u64>,
+}
+
+/// Longest bio accepted, in characters.
+const BIO_MAX_LENGTH: usize = 500;
+
+/// Number of authors per directory page unless another limit is given.
+const DIRECTORY_DEFAULT_LIMIT: u64 = 20;
+
+/// Largest page of the author directory.
+const DIRECTORY_MAX_LIMIT: u64 = 50;
+
+/// Number of articles shown on an author page.
+const AUTHOR_RECENT_ARTICLES: u64 = 10;
+
+/// Largest page of audit events returned at once.
+const AUDIT_EVENTS_MAX_LIMIT: u64 = 100;
+
+/// Query model carrying the download token of a data export.
+#[derive(Serialize, Deserialize)]
+pub struct DownloadQuery {
+    pub token: String,
 }
 
 /// Handler for fetching a user's details based on their authentication claims.
@@ -25,7 +235,7 @@ pub struct UserModel {
 /// * `claim_data` - JWT claims containing the user's ID.
 ///
 /// # Returns
-/// * `ApiResponse` - A JSON response containing the user's name and email if found.
+/// * `ApiResponse` - A JSON response containing the user's name, email, timezone and locale if found.
 ///
 /// # Errors
 /// * Returns a `500` error if database lookup fails.
@@ -34,8 +244,10 @@ pub struct UserModel {
 pub async fn user(
     app_state: web::Data<app_state::AppState>,
     claim_data: Claims,
+    granted_scopes: GrantedScopes,
 ) -> Result<api_response::ApiResponse,api_response::ApiResponse> {
-    
+    granted_scopes.require(Scope::ProfileRead)?;
+
     let db = Arc::clone(&app_state.db);
  
     // Fetch user by ID from the database
@@ -51,49 +263,984 @@ pub async fn user(
     // Return user details as a JSON response
     Ok(api_response::ApiResponse::new(
         200,
-        format!("{{'name' : '{}' , 'email': '{}' }}" , user_model.name , user_model.email)
+        format!(
+            "{{'name' : '{}' , 'email': '{}' , 'timezone': '{}' , 'locale': '{}' }}",
+            user_model.name, user_model.email, user_model.timezone, user_model.locale
+        )
     ))
 }
 
-/// Handler for fetching all users from the database.
+/// Handler for changing the role of a user.
+/// Only available to users with the `ManageUsers` permission. The new role applies
+/// to access tokens issued after the change.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims of the admin making the change.
+/// * `user_id` - ID of the user whose role changes.
+/// * `role_json` - The new role.
+///
+/// # Errors
+/// * Returns a `400` error when admins try to change their own role.
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `500` error if the database update fails.
+#[put("/set-role/{user_id}")]
+pub async fn set_role(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    user_id: web::Path<i32>,
+    role_json: web::Json<RoleModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let user_id = user_id.into_inner();
+
+    // Keeps the last admin from locking everyone out
+    if user_id == claim_data.id {
+        return Err(api_response::ApiResponse::new(400, "You cannot change your own role".to_owned()));
+    }
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Role, Expr::value(role_json.role))
+        .filter(entity::user::Column::Id.eq(user_id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(api_response::ApiResponse::new(404, "User not found".to_owned()));
+    }
+
+    audit::record(
+        &*db,
+        AuditAction::RoleChanged,
+        Some(user_id),
+        Some(claim_data.id),
+        &client_ip(&req),
+        Some(format!("{:?}", role_json.role)),
+    )
+    .await;
+
+    Ok(api_response::ApiResponse::new(200, "Role updated".to_owned()))
+}
+
+/// Fetches one page of audit events, newest first, and renders it as a JSON list.
+async fn audit_event_page(
+    db: &sea_orm::DatabaseConnection,
+    mut query: Select<entity::audit_event::Entity>,
+    before: Option<i32>,
+    limit: Option<u64>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    if let Some(before) = before {
+        query = query.filter(entity::audit_event::Column::Id.lt(before));
+    }
+
+    let events = query
+        .order_by_desc(entity::audit_event::Column::Id)
+        .limit(page_limit(limit, AUDIT_EVENTS_MAX_LIMIT, AUDIT_EVENTS_MAX_LIMIT))
+        .all(db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let event_list: Vec<AuditEventModel> = events
+        .into_iter()
+        .map(|event| AuditEventModel {
+            id: event.id,
+            user_id: event.user_id,
+            actor_id: event.actor_id,
+            action: event.action,
+            ip_address: event.ip_address,
+            details: event.details,
+            created_at: event.created_at,
+        })
+        .collect();
+
+    let res_str = serde_json::to_string(&event_list)
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, res_str))
+}
+
+/// Handler for searching the audit log of all users.
+/// Only available to users with the `ManageUsers` permission.
 ///
 /// # Arguments
 /// * `app_state` - Shared application state containing the database connection.
+/// * `query` - Filters by user, action and time, and the page to return.
 ///
 /// # Returns
-/// * `ApiResponse` - A JSON response containing a list of users (`id`, `name`, `email`).
+/// * `ApiResponse` - A JSON list of events, newest first.
 ///
 /// # Errors
+/// * Returns a `403` error when called with an API key.
 /// * Returns a `500` error if the database query fails.
-/// * Returns a `404` error if no users are found.
-#[get("/get-all-users")]
-pub async fn get_all_users(
+#[get("/audit-events")]
+pub async fn audit_events(
     app_state: web::Data<app_state::AppState>,
+    granted_scopes: GrantedScopes,
+    query: web::Query<AuditSearchQuery>,
 ) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
-    
+    granted_scopes.require_session()?;
+
     let db = Arc::clone(&app_state.db);
 
-    // Fetch all users from the database
-    let users = entity::user::Entity::find()
-        .all(&*db)
+    let mut events = entity::audit_event::Entity::find();
+    if let Some(user_id) = query.user_id {
+        events = events.filter(entity::audit_event::Column::UserId.eq(user_id));
+    }
+    if let Some(action) = query.action {
+        events = events.filter(entity::audit_event::Column::Action.eq(action));
+    }
+    if let Some(from) = query.from {
+        events = events.filter(entity::audit_event::Column::CreatedAt.gte(from));
+    }
+    if let Some(to) = query.to {
+        events = events.filter(entity::audit_event::Column::CreatedAt.lt(to));
+    }
+
+    audit_event_page(&db, events, query.before, query.limit).await
+}
+
+/// Handler for listing the audit events of the authenticated user's account.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `query` - The page to return.
+///
+/// # Returns
+/// * `ApiResponse` - A JSON list of events, newest first.
+///
+/// # Errors
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `500` error if the database query fails.
+#[get("/audit-events")]
+pub async fn my_audit_events(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    query: web::Query<AuditPageQuery>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let events = entity::audit_event::Entity::find()
+        .filter(entity::audit_event::Column::UserId.eq(claim_data.id));
+
+    audit_event_page(&db, events, query.before, query.limit).await
+}
+
+/// Handler for requesting the deletion of the authenticated user's account.
+/// The account is erased by a background job once the grace period is over, see
+/// `utils::account_erasure`; until then the deletion can be cancelled.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `delete_json` - The current password and what happens to the user's articles.
+///
+/// # Errors
+/// * Returns a `403` error if the password is wrong, an account without a password did not sign in
+///   recently, or when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `409` error if a deletion is already pending.
+/// * Returns a `500` error if the database operation fails.
+#[post("/delete-account")]
+pub async fn request_account_deletion(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    delete_json: web::Json<DeleteAccountModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_model = confirmed_user(&db, &claim_data, &delete_json.password).await?;
+
+    let pending_deletion = entity::account_deletion::Entity::find()
+        .filter(entity::account_deletion::Column::UserId.eq(user_model.id))
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if pending_deletion.is_some() {
+        return Err(api_response::ApiResponse::new(409, "Account deletion already requested".to_owned()));
+    }
+
+    let now = Utc::now().naive_local();
+
+    let deletion = entity::account_deletion::ActiveModel {
+        user_id: Set(user_model.id),
+        articles: Set(delete_json.articles),
+        requested_at: Set(now),
+        scheduled_for: Set(now + Duration::days(*contants::ACCOUNT_DELETION_GRACE_DAYS)),
+        ..Default::default()
+    }
+    .insert(&*db)
+    .await
+    .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(
+        &*db,
+        AuditAction::AccountDeletionRequested,
+        Some(user_model.id),
+        Some(user_model.id),
+        &client_ip(&req),
+        Some(format!("Scheduled for {}", deletion.scheduled_for)),
+    )
+    .await;
+
+    let locale = UserLocale::of(&user_model);
+
+    if let Err(err) = email_service::send_account_deletion_email(
+        &user_model.email,
+        &user_model.name,
+        &locale.format(deletion.scheduled_for),
+        locale.language(),
+    )
+    .await
+    {
+        eprintln!("Account deletion email error: {}", err);
+    }
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        format!("{{'scheduled_for': '{}'}}", locale.timestamp(deletion.scheduled_for).to_rfc3339()),
+    ))
+}
+
+/// Handler for cancelling a pending deletion of the authenticated user's account.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+///
+/// # Errors
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if no deletion is pending.
+/// * Returns a `500` error if the database operation fails.
+#[delete("/delete-account")]
+pub async fn cancel_account_deletion(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = entity::account_deletion::Entity::delete_many()
+        .filter(entity::account_deletion::Column::UserId.eq(claim_data.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(api_response::ApiResponse::new(404, "No account deletion pending".to_owned()));
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Account deletion cancelled".to_owned()))
+}
+
+/// Handler for requesting an export of everything held about the authenticated user.
+/// The archive is built in the background, see `utils::data_export`, and the download
+/// link is emailed once it is ready. Earlier exports of the user are discarded.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+///
+/// # Errors
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `409` error if an export is still being built.
+/// * Returns a `500` error if the database operation fails.
+#[post("/export")]
+pub async fn request_export(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_model = entity::user::Entity::find_by_id(claim_data.id)
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .ok_or(api_response::ApiResponse::new(404, "User not found".to_owned()))?;
+
+    // Exports pending for longer died with their task and are discarded below
+    let pending_export = entity::data_export::Entity::find()
+        .filter(entity::data_export::Column::UserId.eq(user_model.id))
+        .filter(entity::data_export::Column::Status.eq(DataExportStatus::Pending))
+        .filter(
+            entity::data_export::Column::CreatedAt
+                .gt(Utc::now().naive_local() - Duration::minutes(*contants::DATA_EXPORT_TIMEOUT_MINUTES)),
+        )
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if pending_export.is_some() {
+        return Err(api_response::ApiResponse::new(409, "A data export is already being prepared".to_owned()));
+    }
+
+    entity::data_export::Entity::delete_many()
+        .filter(entity::data_export::Column::UserId.eq(user_model.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let data_export = entity::data_export::ActiveModel {
+        user_id: Set(user_model.id),
+        status: Set(DataExportStatus::Pending),
+        created_at: Set(Utc::now().naive_local()),
+        ..Default::default()
+    }
+    .insert(&*db)
+    .await
+    .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    spawn_export(Arc::clone(&db), data_export.id, user_model);
+
+    Ok(api_response::ApiResponse::new(
+        202,
+        "Your data export is being prepared, a download link will be sent by email".to_owned(),
+    ))
+}
+
+/// Handler for downloading a data export with the emailed token.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `download_query` - The download token from the emailed link.
+///
+/// # Returns
+/// * `HttpResponse` - The zip archive as an attachment.
+///
+/// # Errors
+/// * Returns a `404` error if the link is unknown or has expired.
+/// * Returns a `500` error if the database lookup fails.
+#[get("/download")]
+pub async fn download_export(
+    app_state: web::Data<app_state::AppState>,
+    download_query: web::Query<DownloadQuery>,
+) -> Result<HttpResponse, api_response::ApiResponse> {
+    let db = Arc::clone(&app_state.db);
+
+    let archive = entity::data_export::Entity::find()
+        .filter(entity::data_export::Column::TokenHash.eq(hash_token(&download_query.token)))
+        .filter(entity::data_export::Column::Status.eq(DataExportStatus::Ready))
+        .filter(entity::data_export::Column::ExpiresAt.gt(Utc::now().naive_local()))
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .and_then(|data_export| data_export.archive)
+        .ok_or(api_response::ApiResponse::new(404, "Invalid or expired download link".to_owned()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((header::CONTENT_DISPOSITION, "attachment; filename=\"data-export.zip\""))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .body(archive))
+}
+
+/// Loads the authenticated user and checks their current password.
+/// Accounts without a password, such as those created through an OpenID Connect provider,
+/// instead need a session that signed in within `REAUTH_WINDOW_MINUTES`.
+///
+/// # Errors
+/// * Returns a `403` error if the password is wrong or the sign-in is not recent enough.
+/// * Returns a `404` error if the user is not found.
+async fn confirmed_user(
+    db: &sea_orm::DatabaseConnection,
+    claim_data: &Claims,
+    password: &str,
+) -> Result<entity::user::Model, api_response::ApiResponse> {
+    let user_model = entity::user::Entity::find_by_id(claim_data.id)
+        .one(db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .ok_or(api_response::ApiResponse::new(404, "User not found".to_owned()))?;
+
+    if user_model.password.is_empty() {
+        // Access tokens are renewed on refresh, so the session tells when the user signed in
+        let recent_session = match claim_data.sid.as_deref().and_then(|sid| Uuid::parse_str(sid).ok()) {
+            Some(family_id) => entity::session::Entity::find()
+                .filter(entity::session::Column::FamilyId.eq(family_id))
+                .filter(entity::session::Column::UserId.eq(user_model.id))
+                .filter(entity::session::Column::RevokedAt.is_null())
+                .filter(
+                    entity::session::Column::CreatedAt
+                        .gt(Utc::now().naive_local() - Duration::minutes(*contants::REAUTH_WINDOW_MINUTES)),
+                )
+                .one(db)
+                .await
+                .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?,
+            None => None,
+        };
+
+        if recent_session.is_none() {
+            return Err(api_response::ApiResponse::new(403, "Sign in again to confirm this change".to_owned()));
+        }
+
+        return Ok(user_model);
+    }
+
+    let password_check = verify_password(password, &user_model.password)
+        .map_err(|err| api_response::ApiResponse::new(500, err))?;
+
+    if password_check == PasswordCheck::Invalid {
+        return Err(api_response::ApiResponse::new(403, "Invalid password".to_owned()));
+    }
+
+    Ok(user_model)
+}
+
+/// Handler for changing the name of the authenticated user.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `name_json` - The new name.
+///
+/// # Errors
+/// * Returns a `400` error if the name is empty.
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `500` error if the database update fails.
+#[put("/change-name")]
+pub async fn change_name(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    name_json: web::Json<ChangeNameModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let name = name_json.name.trim();
+    if name.is_empty() {
+        return Err(api_response::ApiResponse::new(400, "Name cannot be empty".to_owned()));
+    }
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Name, Expr::value(name))
+        .filter(entity::user::Column::Id.eq(claim_data.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(api_response::ApiResponse::new(404, "User not found".to_owned()));
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Name updated".to_owned()))
+}
+
+/// Trims an optional profile field, treating empty values as absent.
+fn profile_field(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Checks that a handle is 3 to 30 lowercase letters, digits and dashes, not starting or
+/// ending with a dash.
+fn is_valid_handle(handle: &str) -> bool {
+    (3..=30).contains(&handle.len())
+        && handle.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !handle.starts_with('-')
+        && !handle.ends_with('-')
+}
+
+/// Checks that a profile link is an absolute `http` or `https` URL.
+fn is_valid_link(link: &str) -> bool {
+    reqwest::Url::parse(link).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Handler for updating the public profile of the authenticated user.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `profile_json` - The new handle, bio, avatar URL and website.
+///
+/// # Errors
+/// * Returns a `400` error if the handle, a URL or the bio is invalid.
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `409` error if the handle belongs to another user.
+/// * Returns a `500` error if the database update fails.
+#[put("/profile")]
+pub async fn update_profile(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    profile_json: web::Json<UpdateProfileModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let handle = profile_field(&profile_json.handle);
+    let bio = profile_field(&profile_json.bio);
+    let avatar_url = profile_field(&profile_json.avatar_url);
+    let website = profile_field(&profile_json.website);
+
+    if handle.as_deref().is_some_and(|handle| !is_valid_handle(handle)) {
+        return Err(api_response::ApiResponse::new(
+            400,
+            "Handles are 3 to 30 lowercase letters, digits and dashes".to_owned(),
+        ));
+    }
+
+    if bio.as_deref().is_some_and(|bio| bio.chars().count() > BIO_MAX_LENGTH) {
+        return Err(api_response::ApiResponse::new(
+            400,
+            format!("Bio cannot be longer than {} characters", BIO_MAX_LENGTH),
+        ));
+    }
+
+    if [&avatar_url, &website]
+        .into_iter()
+        .flatten()
+        .any(|link| !is_valid_link(link))
+    {
+        return Err(api_response::ApiResponse::new(400, "Links must be http or https URLs".to_owned()));
+    }
+
+    let db = Arc::clone(&app_state.db);
+
+    if let Some(handle) = &handle {
+        let handle_owner = entity::user::Entity::find()
+            .filter(entity::user::Column::Handle.eq(handle.as_str()))
+            .filter(entity::user::Column::Id.ne(claim_data.id))
+            .one(&*db)
+            .await
+            .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+        if handle_owner.is_some() {
+            return Err(api_response::ApiResponse::new(409, "Handle is already taken".to_owned()));
+        }
+    }
+
+    let result = entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Handle, Expr::value(handle))
+        .col_expr(entity::user::Column::Bio, Expr::value(bio))
+        .col_expr(entity::user::Column::AvatarUrl, Expr::value(avatar_url))
+        .col_expr(entity::user::Column::Website, Expr::value(website))
+        .filter(entity::user::Column::Id.eq(claim_data.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    if result.rows_affected == 0 {
+        return Err(api_response::ApiResponse::new(404, "User not found".to_owned()));
+    }
+
+    Ok(api_response::ApiResponse::new(200, "Profile updated".to_owned()))
+}
+
+/// Handler for changing the timezone and locale of the authenticated user.
+/// They decide how dates are shown in emails and responses and when digests go out;
+/// digests that are already queued keep their time.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `settings_json` - The new timezone and locale.
+///
+/// # Errors
+/// * Returns a `400` error if the timezone or locale is unknown, or neither is given.
+/// * Returns a `403` error when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `500` error if the database update fails.
+#[put("/settings")]
+pub async fn update_settings(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    settings_json: web::Json<UpdateSettingsModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    let mut update = entity::user::Entity::update_many();
+
+    if let Some(timezone) = settings_json.timezone.as_deref() {
+        let timezone = parse_timezone(timezone.trim())
+            .ok_or(api_response::ApiResponse::new(400, "Unknown timezone".to_owned()))?;
+        update = update.col_expr(entity::user::Column::Timezone, Expr::value(timezone.name()));
+    }
+
+    if let Some(locale) = settings_json.locale.as_deref() {
+        let locale = locale.trim().replace('_', "-");
+        if parse_locale(&locale).is_none() {
+            return Err(api_response::ApiResponse::new(400, "Unsupported locale".to_owned()));
+        }
+        update = update.col_expr(entity::user::Column::Locale, Expr::value(locale));
+    }
+
+    if settings_json.timezone.is_none() && settings_json.locale.is_none() {
+        return Err(api_response::ApiResponse::new(400, "Nothing to update".to_owned()));
+    }
+
+    let db = Arc::clone(&app_state.db);
+
+    let result = update
+        .filter(entity::user::Column::Id.eq(claim_data.id))
+        .exec(&*db)
         .await
         .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
 
-    if users.is_empty() {
-        return Err(api_response::ApiResponse::new(404, "No users found".to_owned()));
+    if result.rows_affected == 0 {
+        return Err(api_response::ApiResponse::new(404, "User not found".to_owned()));
     }
 
-    // Map database models into UserModel
-    let user_list: Vec<UserModel> = users.into_iter()
-        .map(|u: entity::user::Model| UserModel {
-            id: u.id,
-            name: u.name,
-            email: u.email,
+    Ok(api_response::ApiResponse::new(200, "Settings updated".to_owned()))
+}
+
+/// Selects the users listed in the author directory: those with a public handle, optionally
+/// narrowed down to names or handles containing `search`.
+fn directory_condition(search: Option<&str>) -> Condition {
+    let mut condition = Condition::all()
+        .add(entity::user::Column::Handle.is_not_null())
+        .add(entity::user::Column::DeletedAt.is_null());
+
+    if let Some(search) = search.map(str::trim).filter(|search| !search.is_empty()) {
+        let escaped = search
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = format!("%{}%", escaped);
+
+        condition = condition.add(
+            Condition::any()
+                .add(
+                    Expr::expr(Func::lower(Expr::col((entity::user::Entity, entity::user::Column::Name))))
+                        .like(LikeExpr::new(pattern.clone()).escape('\\')),
+                )
+                .add(
+                    Expr::col((entity::user::Entity, entity::user::Column::Handle))
+                        .like(LikeExpr::new(pattern).escape('\\')),
+                ),
+        );
+    }
+
+    condition
+}
+
+/// Handler for browsing and searching the public author directory.
+/// Needs no login and only returns public profile fields.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `query` - The search term, sort order, cursor and page size.
+///
+/// # Returns
+/// * `ApiResponse` - A page of authors, the total number of matches and the cursor of the next page.
+///
+/// # Errors
+/// * Returns a `400` error if the cursor is invalid.
+/// * Returns a `500` error if the database query fails.
+#[get("")]
+pub async fn author_directory(
+    app_state: web::Data<app_state::AppState>,
+    query: web::Query<AuthorDirectoryQuery>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    let db = Arc::clone(&app_state.db);
+    let limit = page_limit(query.limit, DIRECTORY_DEFAULT_LIMIT, DIRECTORY_MAX_LIMIT);
+    let sort = query.sort;
+
+    let subscriber_count: SimpleExpr =
+        Expr::col((entity::subscription::Entity, entity::subscription::Column::Id)).count();
+
+    let mut authors = entity::user::Entity::find()
+        .select_only()
+        .column(entity::user::Column::Id)
+        .column(entity::user::Column::Handle)
+        .column(entity::user::Column::Name)
+        .column(entity::user::Column::Bio)
+        .column(entity::user::Column::AvatarUrl)
+        .column_as(subscriber_count.clone(), "subscriber_count")
+        .join_rev(JoinType::LeftJoin, entity::subscription::Relation::User1.def())
+        .filter(directory_condition(query.q.as_deref()))
+        .group_by(entity::user::Column::Id);
+
+    authors = match sort {
+        AuthorSort::Newest => {
+            if let Some(cursor) = &query.cursor {
+                let keys = decode_cursor(cursor, sort.as_str(), 1)?;
+                authors = authors.filter(entity::user::Column::Id.lt(keys[0]));
+            }
+            authors.order_by_desc(entity::user::Column::Id)
+        }
+        AuthorSort::Subscribers => {
+            if let Some(cursor) = &query.cursor {
+                let keys = decode_cursor(cursor, sort.as_str(), 2)?;
+                authors = authors.having(
+                    Condition::any()
+                        .add(Expr::expr(subscriber_count.clone()).lt(keys[0]))
+                        .add(
+                            Condition::all()
+                                .add(Expr::expr(subscriber_count.clone()).eq(keys[0]))
+                                .add(entity::user::Column::Id.lt(keys[1])),
+                        ),
+                );
+            }
+            authors
+                .order_by(subscriber_count, Order::Desc)
+                .order_by_desc(entity::user::Column::Id)
+        }
+    };
+
+    // One extra row tells whether there is a next page
+    let mut authors = authors
+        .limit(limit + 1)
+        .into_model::<AuthorSummaryModel>()
+        .all(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let next_cursor = if authors.len() as u64 > limit {
+        authors.truncate(limit as usize);
+        authors.last().map(|author| match sort {
+            AuthorSort::Newest => encode_cursor(sort.as_str(), &[author.id.into()]),
+            AuthorSort::Subscribers => {
+                encode_cursor(sort.as_str(), &[author.subscriber_count, author.id.into()])
+            }
         })
-        .collect();
+    } else {
+        None
+    };
+
+    let total = entity::user::Entity::find()
+        .filter(directory_condition(query.q.as_deref()))
+        .count(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let res_str = serde_json::to_string(&AuthorDirectoryModel { authors, total, next_cursor })
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, res_str))
+}
+
+/// Handler for the public page of an author.
+/// Needs no login and never exposes the email address.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `handle` - The handle of the author.
+///
+/// # Returns
+/// * `ApiResponse` - The profile, the number of subscribers and the latest articles.
+///
+/// # Errors
+/// * Returns a `404` error if no author has the handle.
+/// * Returns a `500` error if the database query fails.
+#[get("/{handle}")]
+pub async fn author_profile(
+    app_state: web::Data<app_state::AppState>,
+    handle: web::Path<String>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    let db = Arc::clone(&app_state.db);
+
+    let author = entity::user::Entity::find()
+        .filter(entity::user::Column::Handle.eq(handle.to_lowercase()))
+        .filter(entity::user::Column::DeletedAt.is_null())
+        .one(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?
+        .ok_or(api_response::ApiResponse::new(404, "Author not found".to_owned()))?;
+
+    let subscriber_count = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::SubscribedUserId.eq(author.id))
+        .count(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let recent_articles = entity::article::Entity::find()
+        .filter(entity::article::Column::UserId.eq(author.id))
+        .filter(entity::article::Column::Status.eq(ArticleStatus::Published))
+        .filter(entity::article::Column::DeletedAt.is_null())
+        .order_by_desc(entity::article::Column::CreatedAt)
+        .limit(AUTHOR_RECENT_ARTICLES)
+        .all(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let profile = AuthorProfileModel {
+        id: author.id,
+        handle: author.handle.unwrap_or_default(),
+        name: author.name,
+        bio: author.bio,
+        avatar_url: author.avatar_url,
+        website: author.website,
+        subscriber_count,
+        recent_articles: recent_articles
+            .into_iter()
+            .map(|article| AuthorArticleModel {
+                uuid: article.uuid,
+                title: article.title,
+                image: article.image,
+                created_at: UserLocale::default().timestamp(article.created_at),
+            })
+            .collect(),
+    };
+
+    let res_str = serde_json::to_string(&profile)
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    Ok(api_response::ApiResponse::new(200, res_str))
+}
+
+/// Handler for changing the password of the authenticated user.
+/// Every other session of the user ends; the one making the request stays logged in.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID and session.
+/// * `password_json` - The current and the new password.
+///
+/// # Errors
+/// * Returns a `400` error if the new password is empty.
+/// * Returns a `403` error if the current password is wrong, an account without a password did not
+///   sign in recently, or when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `500` error if the database update fails.
+#[post("/change-password")]
+pub async fn change_password(
+    app_state: web::Data<app_state::AppState>,
+    req: HttpRequest,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    password_json: web::Json<ChangePasswordModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    if password_json.new_password.is_empty() {
+        return Err(api_response::ApiResponse::new(400, "Password cannot be empty".to_owned()));
+    }
+
+    let db = Arc::clone(&app_state.db);
+
+    let user_model = confirmed_user(&db, &claim_data, &password_json.current_password).await?;
+
+    let password_hash = hash_password(&password_json.new_password)
+        .map_err(|err| api_response::ApiResponse::new(500, err))?;
+
+    let current_session = claim_data.sid.as_deref().and_then(|sid| Uuid::parse_str(sid).ok());
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::Password, Expr::value(password_hash))
+        .filter(entity::user::Column::Id.eq(user_model.id))
+        .exec(&txn)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    revoke_all_for_user(&txn, user_model.id, current_session)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    txn.commit()
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    audit::record(&*db, AuditAction::PasswordChanged, Some(user_model.id), Some(user_model.id), &client_ip(&req), None).await;
+
+    Ok(api_response::ApiResponse::new(200, "Password changed, other sessions have been logged out".to_owned()))
+}
+
+/// Handler for changing the email address of the authenticated user.
+/// The new address is kept in `pending_email` and only replaces the current one once it is
+/// confirmed with the link sent to it, see `auth_handlers::confirm_email_change`.
+///
+/// # Arguments
+/// * `app_state` - Shared application state containing the database connection.
+/// * `claim_data` - JWT claims containing the user's ID.
+/// * `email_json` - The new email address and the current password.
+///
+/// # Errors
+/// * Returns a `400` error if the address is invalid or unchanged.
+/// * Returns a `403` error if the password is wrong, an account without a password did not sign in
+///   recently, or when called with an API key.
+/// * Returns a `404` error if the user is not found.
+/// * Returns a `409` error if the address belongs to another account.
+/// * Returns a `500` error if the database operation fails.
+#[post("/change-email")]
+pub async fn change_email(
+    app_state: web::Data<app_state::AppState>,
+    claim_data: Claims,
+    granted_scopes: GrantedScopes,
+    email_json: web::Json<ChangeEmailModel>,
+) -> Result<api_response::ApiResponse, api_response::ApiResponse> {
+    granted_scopes.require_session()?;
+
+    // Validate email format
+    if !email_json.new_email.contains('@') || !email_json.new_email.contains('.') {
+        return Err(api_response::ApiResponse::new(400, "Invalid email format".to_owned()));
+    }
 
-        let res_str = serde_json::to_string(&user_list)
+    let db = Arc::clone(&app_state.db);
+
+    let user_model = confirmed_user(&db, &claim_data, &email_json.password).await?;
+
+    if user_model.email == email_json.new_email {
+        return Err(api_response::ApiResponse::new(400, "This is already your email address".to_owned()));
+    }
+
+    let existing_user = entity::user::Entity::find()
+        .filter(entity::user::Column::Email.eq(&email_json.new_email))
+        .one(&*db)
+        .await
         .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
-    
-        Ok(api_response::ApiResponse::new(200, res_str.to_owned()))
+
+    if existing_user.is_some() {
+        return Err(api_response::ApiResponse::new(409, "Email already registered".to_owned()));
+    }
+
+    entity::user::Entity::update_many()
+        .col_expr(entity::user::Column::PendingEmail, Expr::value(&email_json.new_email))
+        .filter(entity::user::Column::Id.eq(user_model.id))
+        .exec(&*db)
+        .await
+        .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let token = issue_one_time_token(
+        &*db,
+        user_model.id,
+        TokenPurpose::EmailChange,
+        Duration::hours(*contants::EMAIL_VERIFICATION_TTL_HOURS),
+    )
+    .await
+    .map_err(|err| api_response::ApiResponse::new(500, err.to_string()))?;
+
+    let confirmation_link = format!("{}/auth/confirm-email-change?token={}", *contants::APP_BASE_URL, token);
+
+    if let Err(err) = email_service::send_email_change_email(&email_json.new_email, &user_model.name, &confirmation_link).await {
+        eprintln!("Email change confirmation error: {}", err);
+    }
+
+    Ok(api_response::ApiResponse::new(
+        200,
+        "A confirmation link has been sent to the new email address".to_owned(),
+    ))
 }
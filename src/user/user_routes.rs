@@ -1,6 +1,6 @@
 use actix_web::{middleware::from_fn, web};
 
-use crate::middlewares;
+use crate::{middlewares, utils::roles::Permission};
 
 use super::user_handlers;
 
@@ -11,11 +11,45 @@ use super::user_handlers;
 ///
 /// This function sets up a scoped route under `/user`, ensuring that requests
 /// pass through the authentication middleware before reaching the handler.
+/// Changing roles and searching the audit log additionally require the `ManageUsers` permission.
+/// Account settings such as timezone and locale, deletion, data exports and the user's own
+/// audit events live under `/secure/user`; exports are downloaded from `/export/download`
+/// with the emailed token instead of a login. The author directory under `/author` and the author pages under
+/// `/author/{handle}` are public.
 pub fn config(config: &mut web::ServiceConfig) {
     config.service(
         web::scope("/user")
         .wrap(from_fn(middlewares::auth_middlewares::check_auth_middleware))
         .service(user_handlers::user)
-        .service(user_handlers::get_all_users)
+        .service(
+            web::scope("")
+            .wrap(from_fn(|req, next| {
+                middlewares::role_middlewares::require_permission(Permission::ManageUsers, req, next)
+            }))
+            .service(user_handlers::set_role)
+            .service(user_handlers::audit_events)
+        )
+    )
+    .service(
+        web::scope("/secure/user")
+        .wrap(from_fn(middlewares::auth_middlewares::check_auth_middleware))
+        .service(user_handlers::change_name)
+        .service(user_handlers::update_profile)
+        .service(user_handlers::update_settings)
+        .service(user_handlers::change_password)
+        .service(user_handlers::change_email)
+        .service(user_handlers::request_account_deletion)
+        .service(user_handlers::cancel_account_deletion)
+        .service(user_handlers::request_export)
+        .service(user_handlers::my_audit_events)
+    )
+    .service(
+        web::scope("/export")
+        .service(user_handlers::download_export)
+    )
+    .service(
+        web::scope("/author")
+        .service(user_handlers::author_directory)
+        .service(user_handlers::author_profile)
     );
 }
@@ -0,0 +1,183 @@
+/// Erases user accounts once their deletion grace period is over.
+///
+/// Erasure runs in a single transaction: sessions end, subscriptions in both directions
+/// and every row holding personal data are deleted, and the articles are either deleted
+/// together with the `user` row or kept under an anonymized "tombstone" user.
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use entity::{
+    account_deletion::ArticleErasure, audit_event::AuditAction, login_throttle::ThrottleKind,
+    user::UserRole,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+
+use super::{audit, auth_tokens::revoke_all_for_user, login_throttle, newsletter::unqueue_user};
+
+/// How often the background job looks for accounts due for erasure.
+const ERASURE_JOB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Name shown as the author of anonymized articles.
+pub const DELETED_USER_NAME: &str = "Deleted user";
+
+/// Erases one account as requested.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `deletion` - The pending deletion of the account.
+///
+/// # Errors
+/// Returns `DbErr` if any step fails; nothing is erased in that case.
+pub async fn erase_account(
+    db: &DatabaseConnection,
+    deletion: &entity::account_deletion::Model,
+) -> Result<(), DbErr> {
+    let user_id = deletion.user_id;
+    let txn = db.begin().await?;
+
+    let Some(user_data) = entity::user::Entity::find_by_id(user_id).one(&txn).await? else {
+        return Ok(());
+    };
+
+    // Outstanding access tokens stop working right away
+    revoke_all_for_user(&txn, user_id, None).await?;
+
+    unqueue_user(&txn, user_id).await?;
+
+    entity::subscription::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(entity::subscription::Column::SubscribedUserId.eq(user_id))
+                .add(entity::subscription::Column::SubscriberUserId.eq(user_id)),
+        )
+        .exec(&txn)
+        .await?;
+
+    if deletion.articles == ArticleErasure::Delete {
+        entity::article::Entity::delete_many()
+            .filter(entity::article::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+    }
+
+    entity::refresh_token::Entity::delete_many()
+        .filter(entity::refresh_token::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::session::Entity::delete_many()
+        .filter(entity::session::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::one_time_token::Entity::delete_many()
+        .filter(entity::one_time_token::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::recovery_code::Entity::delete_many()
+        .filter(entity::recovery_code::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::api_key::Entity::delete_many()
+        .filter(entity::api_key::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::oauth_identity::Entity::delete_many()
+        .filter(entity::oauth_identity::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::login_throttle::Entity::delete_many()
+        .filter(entity::login_throttle::Column::Kind.eq(ThrottleKind::Account))
+        .filter(entity::login_throttle::Column::Key.eq(login_throttle::account_key(&user_data.email)))
+        .exec(&txn)
+        .await?;
+    entity::data_export::Entity::delete_many()
+        .filter(entity::data_export::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    entity::account_deletion::Entity::delete_many()
+        .filter(entity::account_deletion::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    // Audit events stay, but without the personal data they carried
+    entity::audit_event::Entity::update_many()
+        .col_expr(entity::audit_event::Column::IpAddress, Expr::value(""))
+        .col_expr(entity::audit_event::Column::Details, Expr::value(None::<String>))
+        .filter(entity::audit_event::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    match deletion.articles {
+        ArticleErasure::Delete => {
+            entity::user::Entity::delete_by_id(user_id).exec(&txn).await?;
+        }
+        ArticleErasure::Anonymize => {
+            entity::user::Entity::update_many()
+                .col_expr(entity::user::Column::Name, Expr::value(DELETED_USER_NAME))
+                .col_expr(
+                    entity::user::Column::Email,
+                    Expr::value(format!("deleted-{}@deleted.invalid", user_id)),
+                )
+                .col_expr(entity::user::Column::Password, Expr::value(""))
+                .col_expr(entity::user::Column::EmailVerifiedAt, Expr::value(None::<chrono::NaiveDateTime>))
+                .col_expr(entity::user::Column::PendingEmail, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::TotpSecret, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::TotpEnabledAt, Expr::value(None::<chrono::NaiveDateTime>))
+                .col_expr(entity::user::Column::Role, Expr::value(UserRole::Reader))
+                .col_expr(entity::user::Column::Handle, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::Bio, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::AvatarUrl, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::Website, Expr::value(None::<String>))
+                .col_expr(entity::user::Column::DeletedAt, Expr::value(Utc::now().naive_local()))
+                .filter(entity::user::Column::Id.eq(user_id))
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    audit::record(
+        &txn,
+        AuditAction::AccountErased,
+        Some(user_id),
+        None,
+        "",
+        Some(format!("Articles: {:?}", deletion.articles)),
+    )
+    .await;
+
+    txn.commit().await
+}
+
+/// Erases every account whose grace period is over.
+/// Failures are logged and retried on the next run.
+pub async fn erase_due_accounts(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let due_deletions = entity::account_deletion::Entity::find()
+        .filter(entity::account_deletion::Column::ScheduledFor.lte(Utc::now().naive_local()))
+        .all(db)
+        .await?;
+
+    for deletion in due_deletions {
+        if let Err(err) = erase_account(db, &deletion).await {
+            eprintln!("Account erasure error for user {}: {}", deletion.user_id, err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Starts the background job erasing accounts whose grace period is over.
+pub fn spawn_erasure_job(db: Arc<DatabaseConnection>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(ERASURE_JOB_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = erase_due_accounts(&db).await {
+                eprintln!("Account erasure job error: {}", err);
+            }
+        }
+    });
+}
@@ -0,0 +1,41 @@
+/// Records security relevant events in the append-only `audit_event` table.
+///
+/// Recording never fails the action being audited; errors are logged instead.
+use chrono::Utc;
+use entity::audit_event::AuditAction;
+use sea_orm::{ConnectionTrait, EntityTrait, Set};
+
+/// Appends an event to the audit log.
+///
+/// # Arguments
+/// * `db` - The database connection or transaction.
+/// * `action` - Kind of the event.
+/// * `user_id` - The account the event belongs to, if known.
+/// * `actor_id` - The user who caused the event, if known.
+/// * `ip_address` - Client IP address of the request.
+/// * `details` - Free-form details of the event.
+pub async fn record<C: ConnectionTrait>(
+    db: &C,
+    action: AuditAction,
+    user_id: Option<i32>,
+    actor_id: Option<i32>,
+    ip_address: &str,
+    details: Option<String>,
+) {
+    let event = entity::audit_event::ActiveModel {
+        user_id: Set(user_id),
+        actor_id: Set(actor_id),
+        action: Set(action),
+        ip_address: Set(ip_address.to_string()),
+        details: Set(details),
+        created_at: Set(Utc::now().naive_local()),
+        ..Default::default()
+    };
+
+    if let Err(err) = entity::audit_event::Entity::insert(event)
+        .exec_without_returning(db)
+        .await
+    {
+        eprintln!("Audit log error for {:?}: {}", action, err);
+    }
+}
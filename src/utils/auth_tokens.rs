@@ -0,0 +1,339 @@
+/// Module for managing sessions, refresh tokens, one-time email tokens and the access token revocation list.
+///
+/// Refresh tokens are opaque random strings; only their SHA-256 hash is stored.
+/// Every token rotated from the same login shares a family id, so presenting an
+/// already rotated token revokes the whole family. The family id also identifies the
+/// login's `session` row and is carried by its access tokens as `sid`; revoking a session
+/// puts `session:<sid>` on the revocation list, which rejects all its access tokens at once.
+/// Used "mfa pending" tokens are listed as `mfa:<jti>`, so that each completes one login attempt.
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr,
+    EntityTrait, QueryFilter, Set,
+};
+use entity::one_time_token::TokenPurpose;
+use uuid::Uuid;
+
+use super::{contants, jwt::{Claims, MfaClaims}};
+
+/// Generates a new random opaque token.
+pub fn generate_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+/// Hashes an opaque token for storage and lookup.
+pub fn hash_token(token: &str) -> String {
+    sha256::digest(token)
+}
+
+/// Issues a new refresh token for a user within the given token family.
+///
+/// # Arguments
+/// * `db` - The database connection or transaction.
+/// * `user_id` - The user the token is issued to.
+/// * `family_id` - The token family, a new one for each login.
+///
+/// # Returns
+/// * `Ok(String)` - The plain refresh token to hand to the client.
+/// * `Err(DbErr)` - If the token cannot be stored.
+pub async fn issue_refresh_token<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    family_id: Uuid,
+) -> Result<String, DbErr> {
+    let token = generate_token();
+    let now = Utc::now().naive_local();
+
+    entity::refresh_token::ActiveModel {
+        user_id: Set(user_id),
+        token_hash: Set(hash_token(&token)),
+        family_id: Set(family_id),
+        expires_at: Set(now + Duration::days(*contants::REFRESH_TOKEN_TTL_DAYS)),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(token)
+}
+
+/// Records a new login session.
+///
+/// # Arguments
+/// * `db` - The database connection or transaction.
+/// * `user_id` - The user who logged in.
+/// * `user_agent` - The `User-Agent` header of the login request, if any.
+/// * `ip_address` - The client IP of the login request.
+///
+/// # Returns
+/// * `Ok(Uuid)` - The session id, used as the refresh token family.
+/// * `Err(DbErr)` - If the session cannot be stored.
+pub async fn start_session<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    user_agent: Option<String>,
+    ip_address: String,
+) -> Result<Uuid, DbErr> {
+    let family_id = Uuid::new_v4();
+    let now = Utc::now().naive_local();
+
+    entity::session::ActiveModel {
+        user_id: Set(user_id),
+        family_id: Set(family_id),
+        user_agent: Set(user_agent),
+        ip_address: Set(ip_address),
+        created_at: Set(now),
+        last_seen_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(family_id)
+}
+
+/// Marks a session as used, on every token refresh.
+pub async fn touch_session<C: ConnectionTrait>(db: &C, family_id: Uuid) -> Result<(), DbErr> {
+    entity::session::Entity::update_many()
+        .col_expr(entity::session::Column::LastSeenAt, Expr::value(Utc::now().naive_local()))
+        .filter(entity::session::Column::FamilyId.eq(family_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Revocation list entry rejecting every access token of a session.
+fn session_revocation_jti(family_id: &str) -> String {
+    format!("session:{}", family_id)
+}
+
+/// Ends a session: revokes its refresh tokens, marks it revoked and rejects its access tokens.
+/// Ending a session that already ended does nothing, e.g. when logging out twice.
+pub async fn revoke_session<C: ConnectionTrait>(db: &C, family_id: Uuid) -> Result<(), DbErr> {
+    let now = Utc::now().naive_local();
+
+    entity::refresh_token::Entity::update_many()
+        .col_expr(entity::refresh_token::Column::RevokedAt, Expr::value(now))
+        .filter(entity::refresh_token::Column::FamilyId.eq(family_id))
+        .filter(entity::refresh_token::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    let ended = entity::session::Entity::update_many()
+        .col_expr(entity::session::Column::RevokedAt, Expr::value(now))
+        .filter(entity::session::Column::FamilyId.eq(family_id))
+        .filter(entity::session::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    // Only the call that ended the session lists it, the `jti` is unique
+    if ended.rows_affected == 0 {
+        return Ok(());
+    }
+
+    // Outstanding access tokens of the session expire within their lifetime
+    entity::revoked_token::ActiveModel {
+        jti: Set(session_revocation_jti(&family_id.to_string())),
+        expires_at: Set(now + Duration::minutes(*contants::ACCESS_TOKEN_TTL_MINUTES)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Ends every session of a user, optionally keeping one.
+///
+/// # Arguments
+/// * `db` - The database connection or transaction.
+/// * `user_id` - The user whose sessions end.
+/// * `keep` - A session to keep, e.g. the one making the request.
+pub async fn revoke_all_for_user<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    keep: Option<Uuid>,
+) -> Result<(), DbErr> {
+    let sessions = entity::session::Entity::find()
+        .filter(entity::session::Column::UserId.eq(user_id))
+        .filter(entity::session::Column::RevokedAt.is_null())
+        .all(db)
+        .await?;
+
+    for session in sessions.into_iter().filter(|session| Some(session.family_id) != keep) {
+        revoke_session(db, session.family_id).await?;
+    }
+
+    // Refresh tokens issued before sessions were recorded have no session row
+    let mut legacy_tokens = entity::refresh_token::Entity::update_many()
+        .col_expr(
+            entity::refresh_token::Column::RevokedAt,
+            Expr::value(Utc::now().naive_local()),
+        )
+        .filter(entity::refresh_token::Column::UserId.eq(user_id))
+        .filter(entity::refresh_token::Column::RevokedAt.is_null());
+    if let Some(keep) = keep {
+        legacy_tokens = legacy_tokens.filter(entity::refresh_token::Column::FamilyId.ne(keep));
+    }
+    legacy_tokens.exec(db).await?;
+
+    Ok(())
+}
+
+/// Adds an access token to the revocation list and purges entries that have expired.
+pub async fn revoke_access_token<C: ConnectionTrait>(db: &C, claims: &Claims) -> Result<(), DbErr> {
+    let now = Utc::now().naive_local();
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0)
+        .map(|date| date.naive_utc())
+        .unwrap_or(now);
+
+    entity::revoked_token::Entity::delete_many()
+        .filter(entity::revoked_token::Column::ExpiresAt.lt(now))
+        .exec(db)
+        .await?;
+
+    entity::revoked_token::ActiveModel {
+        jti: Set(claims.jti.clone()),
+        expires_at: Set(expires_at),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Uses up an "mfa pending" token, so that it cannot be replayed for further two-factor attempts.
+///
+/// # Returns
+/// * `Ok(true)` - If the token had not been used before.
+/// * `Ok(false)` - If it was already used.
+/// * `Err(DbErr)` - If the token cannot be recorded.
+pub async fn consume_mfa_token<C: ConnectionTrait>(db: &C, claims: &MfaClaims) -> Result<bool, DbErr> {
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0)
+        .map(|date| date.naive_utc())
+        .unwrap_or(Utc::now().naive_local());
+
+    // Only the request that lists the token gets to use it
+    let inserted = entity::revoked_token::Entity::insert(entity::revoked_token::ActiveModel {
+        jti: Set(format!("mfa:{}", claims.jti)),
+        expires_at: Set(expires_at),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::column(entity::revoked_token::Column::Jti)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(inserted == 1)
+}
+
+/// Checks whether an access token, or the session it belongs to, has been revoked.
+pub async fn is_access_token_revoked<C: ConnectionTrait>(
+    db: &C,
+    jti: &str,
+    sid: Option<&str>,
+) -> Result<bool, DbErr> {
+    let mut jtis = vec![jti.to_string()];
+    jtis.extend(sid.map(session_revocation_jti));
+
+    let revoked = entity::revoked_token::Entity::find()
+        .filter(entity::revoked_token::Column::Jti.is_in(jtis))
+        .one(db)
+        .await?;
+    Ok(revoked.is_some())
+}
+
+/// Issues a single-use token for the given flow, invalidating earlier unused ones.
+///
+/// # Arguments
+/// * `db` - The database connection or transaction.
+/// * `user_id` - The user the token is issued to.
+/// * `purpose` - The flow the token belongs to.
+/// * `ttl` - How long the token stays valid.
+///
+/// # Returns
+/// * `Ok(String)` - The plain token to send to the user.
+/// * `Err(DbErr)` - If the token cannot be stored.
+pub async fn issue_one_time_token<C: ConnectionTrait>(
+    db: &C,
+    user_id: i32,
+    purpose: TokenPurpose,
+    ttl: Duration,
+) -> Result<String, DbErr> {
+    let token = generate_token();
+    let now = Utc::now().naive_local();
+
+    entity::one_time_token::Entity::update_many()
+        .col_expr(entity::one_time_token::Column::UsedAt, Expr::value(now))
+        .filter(entity::one_time_token::Column::UserId.eq(user_id))
+        .filter(entity::one_time_token::Column::Purpose.eq(purpose))
+        .filter(entity::one_time_token::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    entity::one_time_token::ActiveModel {
+        user_id: Set(user_id),
+        purpose: Set(purpose),
+        token_hash: Set(hash_token(&token)),
+        expires_at: Set(now + ttl),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(token)
+}
+
+/// Looks up a single-use token without using it up.
+///
+/// # Returns
+/// * `Ok(Some(Model))` - The token when it is valid, unused and not expired.
+/// * `Ok(None)` - If the token is unknown, already used, expired or issued for another flow.
+/// * `Err(DbErr)` - If the database lookup fails.
+pub async fn find_one_time_token<C: ConnectionTrait>(
+    db: &C,
+    token: &str,
+    purpose: TokenPurpose,
+) -> Result<Option<entity::one_time_token::Model>, DbErr> {
+    entity::one_time_token::Entity::find()
+        .filter(entity::one_time_token::Column::TokenHash.eq(hash_token(token)))
+        .filter(entity::one_time_token::Column::Purpose.eq(purpose))
+        .filter(entity::one_time_token::Column::UsedAt.is_null())
+        .filter(entity::one_time_token::Column::ExpiresAt.gt(Utc::now().naive_local()))
+        .one(db)
+        .await
+}
+
+/// Consumes a single-use token, marking it as used.
+///
+/// # Returns
+/// * `Ok(Some(Model))` - The consumed token when it was valid, unused and not expired.
+/// * `Ok(None)` - If the token is unknown, already used, expired or issued for another flow.
+/// * `Err(DbErr)` - If the database lookup fails.
+pub async fn consume_one_time_token<C: ConnectionTrait>(
+    db: &C,
+    token: &str,
+    purpose: TokenPurpose,
+) -> Result<Option<entity::one_time_token::Model>, DbErr> {
+    let now = Utc::now().naive_local();
+
+    let Some(stored_token) = find_one_time_token(db, token, purpose).await? else {
+        return Ok(None);
+    };
+
+    // Only the request that flips `used_at` gets to use the token
+    let result = entity::one_time_token::Entity::update_many()
+        .col_expr(entity::one_time_token::Column::UsedAt, Expr::value(now))
+        .filter(entity::one_time_token::Column::Id.eq(stored_token.id))
+        .filter(entity::one_time_token::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok((result.rows_affected == 1).then_some(stored_token))
+}
@@ -15,6 +15,63 @@ lazy_static! {
     pub static ref DATABASE_URL: String = set_database_url();
     /// Secret key for JWT authentication.
     pub static ref SECRET: String = set_secret();
+    /// Argon2 memory cost in KiB used when hashing passwords.
+    pub static ref ARGON2_MEMORY_COST: u32 = set_argon2_param("ARGON2_MEMORY_COST", argon2::Params::DEFAULT_M_COST);
+    /// Argon2 number of iterations used when hashing passwords.
+    pub static ref ARGON2_TIME_COST: u32 = set_argon2_param("ARGON2_TIME_COST", argon2::Params::DEFAULT_T_COST);
+    /// Argon2 degree of parallelism used when hashing passwords.
+    pub static ref ARGON2_PARALLELISM: u32 = set_argon2_param("ARGON2_PARALLELISM", argon2::Params::DEFAULT_P_COST);
+    /// Lifetime of access tokens in minutes.
+    pub static ref ACCESS_TOKEN_TTL_MINUTES: i64 = set_token_ttl("ACCESS_TOKEN_TTL_MINUTES", 15);
+    /// Lifetime of refresh tokens in days.
+    pub static ref REFRESH_TOKEN_TTL_DAYS: i64 = set_token_ttl("REFRESH_TOKEN_TTL_DAYS", 30);
+    /// Lifetime of the "mfa pending" token handed out between password and TOTP checks, in minutes.
+    pub static ref MFA_TOKEN_TTL_MINUTES: i64 = set_token_ttl("MFA_TOKEN_TTL_MINUTES", 5);
+    /// Issuer name shown in authenticator apps.
+    pub static ref TOTP_ISSUER: String = set_optional("TOTP_ISSUER").unwrap_or("Email Newsletter".to_string());
+    /// Lifetime of email verification links in hours.
+    pub static ref EMAIL_VERIFICATION_TTL_HOURS: i64 = set_token_ttl("EMAIL_VERIFICATION_TTL_HOURS", 24);
+    /// Lifetime of password reset links in minutes.
+    pub static ref PASSWORD_RESET_TTL_MINUTES: i64 = set_token_ttl("PASSWORD_RESET_TTL_MINUTES", 30);
+    /// Lifetime of magic login links in minutes.
+    pub static ref MAGIC_LINK_TTL_MINUTES: i64 = set_token_ttl("MAGIC_LINK_TTL_MINUTES", 15);
+    /// Days between an account deletion request and the erasure of the account.
+    pub static ref ACCOUNT_DELETION_GRACE_DAYS: i64 = set_token_ttl("ACCOUNT_DELETION_GRACE_DAYS", 14);
+    /// Hours a data export can be downloaded once it is built.
+    pub static ref DATA_EXPORT_TTL_HOURS: i64 = set_token_ttl("DATA_EXPORT_TTL_HOURS", 48);
+    /// Minutes after signing in during which accounts without a password, such as those created
+    /// through an OpenID Connect provider, can confirm sensitive changes.
+    pub static ref REAUTH_WINDOW_MINUTES: i64 = set_token_ttl("REAUTH_WINDOW_MINUTES", 10);
+    /// Minutes after which a data export that is still being built is given up, e.g. after a restart.
+    pub static ref DATA_EXPORT_TIMEOUT_MINUTES: i64 = set_token_ttl("DATA_EXPORT_TIMEOUT_MINUTES", 30);
+    /// Failed logins for one email address before it is locked out.
+    pub static ref LOGIN_MAX_FAILED_ATTEMPTS: i32 = set_login_limit("LOGIN_MAX_FAILED_ATTEMPTS", 5);
+    /// Failed logins from one client IP before it is locked out.
+    pub static ref LOGIN_IP_MAX_FAILED_ATTEMPTS: i32 = set_login_limit("LOGIN_IP_MAX_FAILED_ATTEMPTS", 20);
+    /// Duration of a login lockout, in minutes. Older failures are forgotten after the same time.
+    pub static ref LOGIN_LOCKOUT_MINUTES: i64 = set_token_ttl("LOGIN_LOCKOUT_MINUTES", 15);
+    /// Whether to take the client IP from `X-Forwarded-For`/`Forwarded`; only enable behind a trusted proxy.
+    pub static ref TRUST_PROXY_HEADERS: bool = set_optional("TRUST_PROXY_HEADERS").is_some_and(|value| value == "true");
+    /// Lifetime of an OpenID Connect sign-in in progress, in minutes.
+    pub static ref OAUTH_STATE_TTL_MINUTES: i64 = set_token_ttl("OAUTH_STATE_TTL_MINUTES", 10);
+    /// Public base URL of the application, used to build links in emails.
+    pub static ref APP_BASE_URL: String = set_app_base_url();
+    /// Base URL of a web frontend with `/reset-password` and `/magic-link` pages taking the
+    /// emailed `token`. Emailed links point to the API when not set.
+    pub static ref FRONTEND_BASE_URL: Option<String> =
+        set_optional("FRONTEND_BASE_URL").map(|url| url.trim_end_matches('/').to_string());
+    /// SMTP relay host. Emails cannot be sent when not set.
+    pub static ref SMTP_SERVER: Option<String> = set_optional("SMTP_SERVER");
+    /// SMTP username.
+    pub static ref SMTP_USERNAME: String = set_optional("SMTP_USERNAME").unwrap_or_default();
+    /// SMTP password.
+    pub static ref SMTP_PASSWORD: String = set_optional("SMTP_PASSWORD").unwrap_or_default();
+    /// Sender address of outgoing emails.
+    pub static ref EMAIL_FROM: String = set_optional("EMAIL_FROM").unwrap_or("noreply@localhost".to_string());
+    /// Whether emails are printed in full instead of failing when no SMTP relay is configured.
+    /// Meant for local development only, as the printed emails contain login and reset links.
+    /// Always enabled in tests.
+    pub static ref EMAIL_DEV_LOG: bool = cfg!(test) || set_flag("EMAIL_DEV_LOG");
 }
 
 /// Retrieves the host address from the environment variables.
@@ -45,3 +102,53 @@ fn set_secret() -> String {
     dotenv::dotenv().ok();
     env::var("SECRET").unwrap()
 }
+
+/// Retrieves an Argon2 cost parameter from the environment variables.
+/// Falls back to the given default if not set.
+fn set_argon2_param(key: &str, default: u32) -> u32 {
+    dotenv::dotenv().ok();
+    env::var(key)
+        .map(|value| value.parse::<u32>().expect("Can't parse the Argon2 parameter"))
+        .unwrap_or(default)
+}
+
+/// Retrieves a token lifetime from the environment variables.
+/// Falls back to the given default if not set.
+fn set_token_ttl(key: &str, default: i64) -> i64 {
+    dotenv::dotenv().ok();
+    env::var(key)
+        .map(|value| value.parse::<i64>().expect("Can't parse the token lifetime"))
+        .unwrap_or(default)
+}
+
+/// Retrieves a login throttling limit from the environment variables.
+/// Falls back to the given default if not set.
+fn set_login_limit(key: &str, default: i32) -> i32 {
+    dotenv::dotenv().ok();
+    env::var(key)
+        .map(|value| value.parse::<i32>().expect("Can't parse the login limit"))
+        .unwrap_or(default)
+}
+
+/// Retrieves the public base URL from the environment variables.
+/// Defaults to `http://localhost:8080` if not set.
+fn set_app_base_url() -> String {
+    dotenv::dotenv().ok();
+    env::var("APP_BASE_URL")
+        .unwrap_or("http://localhost:8080".to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Retrieves an optional value from the environment variables.
+fn set_optional(key: &str) -> Option<String> {
+    dotenv::dotenv().ok();
+    env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Retrieves an opt-in flag from the environment variables.
+/// Only `true` enables it.
+fn set_flag(key: &str) -> bool {
+    dotenv::dotenv().ok();
+    env::var(key).is_ok_and(|value| value.trim().eq_ignore_ascii_case("true"))
+}
@@ -0,0 +1,455 @@
+/// Builds archives of the personal data held about a user.
+///
+/// An archive is a zip file with JSON documents and one Markdown file per authored article:
+/// - `profile.json` - The account itself, without password hashes or secrets.
+/// - `articles.json` and `articles/<uuid>.md` - Every authored article, including deleted ones.
+/// - `subscriptions.json` - The authors the user subscribes to and the user's subscribers.
+/// - `sessions.json`, `api_keys.json`, `linked_accounts.json` - Login and access history.
+/// - `digest_queue.json` - Articles waiting to be sent in the user's digests.
+/// - `audit_events.json` - The security audit log of the account.
+///
+/// Archives are built in the background; the user gets a download link by email once ready.
+/// An export still pending after `DATA_EXPORT_TIMEOUT_MINUTES` is given up, so that a new one
+/// can be requested when the task building it died.
+use std::{
+    collections::HashMap,
+    io::{Cursor, Write},
+    sync::Arc,
+};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use entity::{
+    article::ArticleStatus, audit_event::AuditAction, data_export::DataExportStatus, subscription::DeliveryMode,
+    user::UserRole,
+};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+use serde::Serialize;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use super::{
+    auth_tokens::{generate_token, hash_token},
+    contants,
+};
+use crate::email::email_service;
+
+/// Profile section of an archive.
+#[derive(Serialize)]
+struct ProfileExport {
+    id: i32,
+    name: String,
+    email: String,
+    role: UserRole,
+    email_verified_at: Option<NaiveDateTime>,
+    pending_email: Option<String>,
+    two_factor_enabled_at: Option<NaiveDateTime>,
+    handle: Option<String>,
+    bio: Option<String>,
+    avatar_url: Option<String>,
+    website: Option<String>,
+    delivery_mode: DeliveryMode,
+    timezone: String,
+    locale: String,
+}
+
+/// Article section of an archive.
+#[derive(Serialize)]
+struct ArticleExport {
+    uuid: Uuid,
+    title: String,
+    content: String,
+    image: Option<String>,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    status: ArticleStatus,
+    publish_at: Option<NaiveDateTime>,
+    /// Set for articles the user deleted; they are kept until the account is erased.
+    deleted_at: Option<NaiveDateTime>,
+}
+
+/// The other side of a subscription.
+#[derive(Serialize)]
+struct SubscriptionExport {
+    user_id: i32,
+    name: Option<String>,
+    since: NaiveDateTime,
+}
+
+/// Subscription section of an archive.
+#[derive(Serialize)]
+struct SubscriptionsExport {
+    subscribed_to: Vec<SubscriptionExport>,
+    subscribers: Vec<SubscriptionExport>,
+}
+
+/// Session section of an archive.
+#[derive(Serialize)]
+struct SessionExport {
+    user_agent: Option<String>,
+    ip_address: String,
+    created_at: NaiveDateTime,
+    last_seen_at: NaiveDateTime,
+    revoked_at: Option<NaiveDateTime>,
+}
+
+/// API key section of an archive.
+#[derive(Serialize)]
+struct ApiKeyExport {
+    name: String,
+    prefix: String,
+    scopes: String,
+    created_at: NaiveDateTime,
+    last_used_at: Option<NaiveDateTime>,
+    revoked_at: Option<NaiveDateTime>,
+}
+
+/// Linked sign-in provider section of an archive.
+#[derive(Serialize)]
+struct LinkedAccountExport {
+    provider: String,
+    subject: String,
+    created_at: NaiveDateTime,
+}
+
+/// Digest queue section of an archive.
+#[derive(Serialize)]
+struct DigestItemExport {
+    article_uuid: Option<Uuid>,
+    title: Option<String>,
+    deliver_after: NaiveDateTime,
+    queued_at: NaiveDateTime,
+}
+
+/// Audit log section of an archive.
+#[derive(Serialize)]
+struct AuditEventExport {
+    action: AuditAction,
+    /// Set when someone else, such as an admin, caused the event.
+    actor_id: Option<i32>,
+    ip_address: String,
+    details: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+/// Renders an article as a Markdown document.
+fn article_markdown(article: &entity::article::Model) -> String {
+    let mut markdown = format!("# {}\n\n_Published {}_\n\n", article.title, article.published_at());
+    if let Some(image) = &article.image {
+        markdown.push_str(&format!("![]({})\n\n", image));
+    }
+    markdown.push_str(&article.content);
+    markdown.push('\n');
+    markdown
+}
+
+/// Looks up the names of the given users.
+async fn user_names<C: ConnectionTrait>(db: &C, user_ids: Vec<i32>) -> Result<HashMap<i32, String>, DbErr> {
+    let users = entity::user::Entity::find()
+        .filter(entity::user::Column::Id.is_in(user_ids))
+        .all(db)
+        .await?;
+
+    Ok(users.into_iter().map(|user| (user.id, user.name)).collect())
+}
+
+/// Builds the zip archive of everything held about a user.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `user_data` - The user whose data is exported.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - The zip archive.
+/// * `Err(String)` - If the data cannot be loaded or written.
+pub async fn build_archive<C: ConnectionTrait>(
+    db: &C,
+    user_data: &entity::user::Model,
+) -> Result<Vec<u8>, String> {
+    let articles = entity::article::Entity::find()
+        .filter(entity::article::Column::UserId.eq(user_data.id))
+        .order_by_asc(entity::article::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let subscribed_to = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::SubscriberUserId.eq(user_data.id))
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let subscribers = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::SubscribedUserId.eq(user_data.id))
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let names = user_names(
+        db,
+        subscribed_to
+            .iter()
+            .map(|subscription| subscription.subscribed_user_id)
+            .chain(subscribers.iter().map(|subscription| subscription.subscriber_user_id))
+            .collect(),
+    )
+    .await
+    .map_err(|err| err.to_string())?;
+
+    let sessions = entity::session::Entity::find()
+        .filter(entity::session::Column::UserId.eq(user_data.id))
+        .order_by_asc(entity::session::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let api_keys = entity::api_key::Entity::find()
+        .filter(entity::api_key::Column::UserId.eq(user_data.id))
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let linked_accounts = entity::oauth_identity::Entity::find()
+        .filter(entity::oauth_identity::Column::UserId.eq(user_data.id))
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let digest_items = entity::digest_item::Entity::find()
+        .filter(entity::digest_item::Column::SubscriberUserId.eq(user_data.id))
+        .order_by_asc(entity::digest_item::Column::DeliverAfter)
+        .find_also_related(entity::article::Entity)
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let audit_events = entity::audit_event::Entity::find()
+        .filter(entity::audit_event::Column::UserId.eq(user_data.id))
+        .order_by_asc(entity::audit_event::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let profile = ProfileExport {
+        id: user_data.id,
+        name: user_data.name.clone(),
+        email: user_data.email.clone(),
+        role: user_data.role,
+        email_verified_at: user_data.email_verified_at,
+        pending_email: user_data.pending_email.clone(),
+        two_factor_enabled_at: user_data.totp_enabled_at,
+        handle: user_data.handle.clone(),
+        bio: user_data.bio.clone(),
+        avatar_url: user_data.avatar_url.clone(),
+        website: user_data.website.clone(),
+        delivery_mode: user_data.delivery_mode,
+        timezone: user_data.timezone.clone(),
+        locale: user_data.locale.clone(),
+    };
+
+    let subscriptions = SubscriptionsExport {
+        subscribed_to: subscribed_to
+            .into_iter()
+            .map(|subscription| SubscriptionExport {
+                user_id: subscription.subscribed_user_id,
+                name: names.get(&subscription.subscribed_user_id).cloned(),
+                since: subscription.created_at,
+            })
+            .collect(),
+        subscribers: subscribers
+            .into_iter()
+            .map(|subscription| SubscriptionExport {
+                user_id: subscription.subscriber_user_id,
+                name: names.get(&subscription.subscriber_user_id).cloned(),
+                since: subscription.created_at,
+            })
+            .collect(),
+    };
+
+    let mut files = vec![
+        ("profile.json".to_string(), to_json(&profile)?),
+        (
+            "articles.json".to_string(),
+            to_json(
+                &articles
+                    .iter()
+                    .map(|article| ArticleExport {
+                        uuid: article.uuid,
+                        title: article.title.clone(),
+                        content: article.content.clone(),
+                        image: article.image.clone(),
+                        created_at: article.created_at,
+                        updated_at: article.updated_at,
+                        status: article.status,
+                        publish_at: article.publish_at,
+                        deleted_at: article.deleted_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+        ("subscriptions.json".to_string(), to_json(&subscriptions)?),
+        (
+            "sessions.json".to_string(),
+            to_json(
+                &sessions
+                    .into_iter()
+                    .map(|session| SessionExport {
+                        user_agent: session.user_agent,
+                        ip_address: session.ip_address,
+                        created_at: session.created_at,
+                        last_seen_at: session.last_seen_at,
+                        revoked_at: session.revoked_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+        (
+            "api_keys.json".to_string(),
+            to_json(
+                &api_keys
+                    .into_iter()
+                    .map(|api_key| ApiKeyExport {
+                        name: api_key.name,
+                        prefix: api_key.prefix,
+                        scopes: api_key.scopes,
+                        created_at: api_key.created_at,
+                        last_used_at: api_key.last_used_at,
+                        revoked_at: api_key.revoked_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+        (
+            "linked_accounts.json".to_string(),
+            to_json(
+                &linked_accounts
+                    .into_iter()
+                    .map(|identity| LinkedAccountExport {
+                        provider: identity.provider,
+                        subject: identity.subject,
+                        created_at: identity.created_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+        (
+            "digest_queue.json".to_string(),
+            to_json(
+                &digest_items
+                    .into_iter()
+                    .map(|(item, article)| DigestItemExport {
+                        article_uuid: article.as_ref().map(|article| article.uuid),
+                        title: article.map(|article| article.title),
+                        deliver_after: item.deliver_after,
+                        queued_at: item.created_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+        (
+            "audit_events.json".to_string(),
+            to_json(
+                &audit_events
+                    .into_iter()
+                    .map(|event| AuditEventExport {
+                        action: event.action,
+                        actor_id: event.actor_id.filter(|actor_id| *actor_id != user_data.id),
+                        ip_address: event.ip_address,
+                        details: event.details,
+                        created_at: event.created_at,
+                    })
+                    .collect::<Vec<_>>(),
+            )?,
+        ),
+    ];
+
+    files.extend(articles.iter().map(|article| {
+        (format!("articles/{}.md", article.uuid), article_markdown(article).into_bytes())
+    }));
+
+    write_zip(files).map_err(|err| err.to_string())
+}
+
+/// Serializes one archive document.
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|err| err.to_string())
+}
+
+/// Writes the given files into a zip archive.
+fn write_zip(files: Vec<(String, Vec<u8>)>) -> zip::result::ZipResult<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, contents) in files {
+        writer.start_file(name, options)?;
+        writer.write_all(&contents)?;
+    }
+
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Builds the archive of a requested export, stores it and emails the download link.
+/// Marks the export as failed if the archive cannot be built.
+async fn run_export(db: Arc<DatabaseConnection>, export_id: i32, user_data: entity::user::Model) {
+    let archive = match build_archive(&*db, &user_data).await {
+        Ok(archive) => archive,
+        Err(err) => {
+            eprintln!("Data export error for user {}: {}", user_data.id, err);
+
+            if let Err(err) = entity::data_export::Entity::update_many()
+                .col_expr(entity::data_export::Column::Status, Expr::value(DataExportStatus::Failed))
+                .filter(entity::data_export::Column::Id.eq(export_id))
+                .exec(&*db)
+                .await
+            {
+                eprintln!("Data export error for user {}: {}", user_data.id, err);
+            }
+            return;
+        }
+    };
+
+    let token = generate_token();
+
+    // Exports given up after the timeout are no longer pending and stay discarded
+    let stored = entity::data_export::Entity::update_many()
+        .col_expr(entity::data_export::Column::Status, Expr::value(DataExportStatus::Ready))
+        .col_expr(entity::data_export::Column::Archive, Expr::value(archive))
+        .col_expr(entity::data_export::Column::TokenHash, Expr::value(hash_token(&token)))
+        .col_expr(
+            entity::data_export::Column::ExpiresAt,
+            Expr::value(Utc::now().naive_local() + Duration::hours(*contants::DATA_EXPORT_TTL_HOURS)),
+        )
+        .filter(entity::data_export::Column::Id.eq(export_id))
+        .filter(entity::data_export::Column::Status.eq(DataExportStatus::Pending))
+        .exec(&*db)
+        .await;
+
+    match stored {
+        Ok(stored) if stored.rows_affected == 0 => return,
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("Data export error for user {}: {}", user_data.id, err);
+            return;
+        }
+    }
+
+    let download_link = format!("{}/export/download?token={}", *contants::APP_BASE_URL, token);
+
+    if let Err(err) = email_service::send_data_export_email(
+        &user_data.email,
+        &user_data.name,
+        &download_link,
+        *contants::DATA_EXPORT_TTL_HOURS,
+    )
+    .await
+    {
+        eprintln!("Data export email error: {}", err);
+    }
+}
+
+/// Starts building the archive of a requested export in the background.
+pub fn spawn_export(db: Arc<DatabaseConnection>, export_id: i32, user_data: entity::user::Model) {
+    actix_web::rt::spawn(run_export(db, export_id, user_data));
+}
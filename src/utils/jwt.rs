@@ -6,9 +6,11 @@ use std::future;
 
 use actix_web::{FromRequest, HttpMessage};
 use chrono::{Duration, Utc};
+use entity::user::UserRole;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
-use super::contants;
+use uuid::Uuid;
+use super::{contants, jwt_keys};
  
  /// Structure representing JWT claims.
 #[derive(Serialize, Deserialize, Clone)]
@@ -21,6 +23,13 @@ pub struct Claims {
     pub email: String,
     /// User ID.
     pub id: i32,
+    /// Unique token identifier, used to revoke the token before it expires.
+    pub jti: String,
+    /// Role of the user when the token was issued.
+    pub role: UserRole,
+    /// Session the token belongs to; absent for API keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
  
  /// Implements extraction of claims from an Actix request.
@@ -42,36 +51,57 @@ impl FromRequest for Claims {
     }
 }
 
-/// Encodes a JWT token with the given email and user ID.
+/// Audience of tokens proving only the password step of a two-factor login.
+const MFA_AUDIENCE: &str = "mfa";
+
+/// Structure representing the claims of an "mfa pending" token.
+///
+/// The token carries an audience, so it is rejected where an access token is expected.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MfaClaims {
+    /// Expiration time (Unix timestamp).
+    pub exp: usize,
+    /// Issued-at time (Unix timestamp).
+    pub iat: usize,
+    /// Audience, always `mfa`.
+    pub aud: String,
+    /// Unique token ID, recorded when the token is used so that it works only once.
+    pub jti: String,
+    /// ID of the user who passed the password check.
+    pub user_id: i32,
+}
+
+/// Encodes a short-lived access token with the given email, user ID and role.
+/// The token is signed with the active key, see `utils::jwt_keys`.
 ///
 /// # Arguments
 /// * `email` - The email to include in the token.
 /// * `id` - The user ID to include in the token.
+/// * `role` - The role of the user.
+/// * `session_id` - The session the token belongs to.
 ///
 /// # Returns
 /// * `Ok(String)` - The encoded JWT token.
 /// * `Err(jsonwebtoken::errors::Error)` - If encoding fails.
-pub fn encode_jwt(email: String, id: i32) -> Result<String, jsonwebtoken::errors::Error> {
+pub fn encode_jwt(email: String, id: i32, role: UserRole, session_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
     let now = Utc::now();
-    let expire = Duration::hours(24);
+    let expire = Duration::minutes(*contants::ACCESS_TOKEN_TTL_MINUTES);
  
     let claims = Claims {
         exp: (now + expire).timestamp() as usize,
         iat: now.timestamp() as usize,
         email,
         id,
+        jti: Uuid::new_v4().to_string(),
+        role,
+        sid: Some(session_id.to_string()),
     };
  
-    let secret = (*contants::SECRET).clone();
- 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
+    jwt_keys::KEY_SET.encode(&claims)
 }
  
  /// Decodes a JWT token and retrieves the claims.
+/// The token must be signed by one of the configured keys, see `utils::jwt_keys`.
 ///
 /// # Arguments
 /// * `jwt` - The JWT token as a string.
@@ -80,11 +110,110 @@ pub fn encode_jwt(email: String, id: i32) -> Result<String, jsonwebtoken::errors
 /// * `Ok(TokenData<Claims>)` - The extracted claims.
 /// * `Err(jsonwebtoken::errors::Error)` - If decoding fails.
 pub fn decode_jwt(jwt: String) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
+    jwt_keys::KEY_SET.decode(&jwt)
+}
+
+/// Encodes an "mfa pending" token for a user who still has to supply a TOTP code.
+///
+/// # Arguments
+/// * `user_id` - The user who passed the password check.
+///
+/// # Returns
+/// * `Ok(String)` - The encoded token.
+/// * `Err(jsonwebtoken::errors::Error)` - If encoding fails.
+pub fn encode_mfa_jwt(user_id: i32) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let expire = Duration::minutes(*contants::MFA_TOKEN_TTL_MINUTES);
+
+    let claims = MfaClaims {
+        exp: (now + expire).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        aud: MFA_AUDIENCE.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        user_id,
+    };
+
     let secret = (*contants::SECRET).clone();
-    let claim_data: Result<TokenData<Claims>, jsonwebtoken::errors::Error> = decode(
-        &jwt,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    );
-    claim_data
-}
\ No newline at end of file
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+/// Decodes an "mfa pending" token and retrieves its claims.
+pub fn decode_mfa_jwt(jwt: &str) -> Result<TokenData<MfaClaims>, jsonwebtoken::errors::Error> {
+    let secret = (*contants::SECRET).clone();
+    let mut validation = Validation::default();
+    validation.set_audience(&[MFA_AUDIENCE]);
+
+    decode(jwt, &DecodingKey::from_secret(secret.as_ref()), &validation)
+}
+
+/// Audience of tokens carrying the state of an OpenID Connect sign-in.
+const OAUTH_AUDIENCE: &str = "oauth";
+
+/// Structure representing the claims of an OpenID Connect sign-in in progress.
+///
+/// The token is kept in a cookie, so the PKCE verifier never passes through the provider.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OAuthStateClaims {
+    /// Expiration time (Unix timestamp).
+    pub exp: usize,
+    /// Issued-at time (Unix timestamp).
+    pub iat: usize,
+    /// Audience, always `oauth`.
+    pub aud: String,
+    /// Name of the provider the sign-in was started with.
+    pub provider: String,
+    /// Value the provider has to echo back in the `state` parameter.
+    pub state: String,
+    /// PKCE code verifier.
+    pub code_verifier: String,
+}
+
+/// Encodes the state of an OpenID Connect sign-in.
+///
+/// # Arguments
+/// * `provider` - Name of the provider.
+/// * `state` - The `state` parameter sent to the provider.
+/// * `code_verifier` - The PKCE code verifier.
+///
+/// # Returns
+/// * `Ok(String)` - The encoded token.
+/// * `Err(jsonwebtoken::errors::Error)` - If encoding fails.
+pub fn encode_oauth_state_jwt(
+    provider: String,
+    state: String,
+    code_verifier: String,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+    let expire = Duration::minutes(*contants::OAUTH_STATE_TTL_MINUTES);
+
+    let claims = OAuthStateClaims {
+        exp: (now + expire).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        aud: OAUTH_AUDIENCE.to_string(),
+        provider,
+        state,
+        code_verifier,
+    };
+
+    let secret = (*contants::SECRET).clone();
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_ref()),
+    )
+}
+
+/// Decodes the state of an OpenID Connect sign-in and retrieves its claims.
+pub fn decode_oauth_state_jwt(jwt: &str) -> Result<TokenData<OAuthStateClaims>, jsonwebtoken::errors::Error> {
+    let secret = (*contants::SECRET).clone();
+    let mut validation = Validation::default();
+    validation.set_audience(&[OAUTH_AUDIENCE]);
+
+    decode(jwt, &DecodingKey::from_secret(secret.as_ref()), &validation)
+}
@@ -0,0 +1,149 @@
+/// Module for the keys signing access tokens.
+///
+/// Keys are configured in `JWT_SIGNING_KEYS` as a comma separated list of `kid:path` pairs
+/// pointing to PKCS#8 (or PKCS#1 for RSA) PEM private keys. RSA keys sign with RS256 and
+/// Ed25519 keys with EdDSA. The first key signs new tokens; the others are only accepted
+/// for verification, so a rotated-out key keeps working until its last tokens expire.
+///
+/// Without `JWT_SIGNING_KEYS`, tokens are signed with HS256 using `SECRET` and no public
+/// keys are published.
+use std::{env, fs};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, TokenData,
+    Validation,
+};
+use lazy_static::lazy_static;
+use rsa::{pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePrivateKey, traits::PublicKeyParts, RsaPrivateKey};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use super::contants;
+
+lazy_static! {
+    /// Keys configured in `JWT_SIGNING_KEYS`.
+    pub static ref KEY_SET: KeySet = KeySet::from_env();
+}
+
+/// Asymmetric key identified by a `kid`.
+pub struct JwtKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    jwk: Value,
+}
+
+impl JwtKey {
+    /// Parses an RSA or Ed25519 private key in PEM format.
+    pub fn from_pem(kid: &str, pem: &str) -> Result<Self, String> {
+        let rsa_key = RsaPrivateKey::from_pkcs8_pem(pem).or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem));
+
+        if let Ok(rsa_key) = rsa_key {
+            let n = URL_SAFE_NO_PAD.encode(rsa_key.n().to_bytes_be());
+            let e = URL_SAFE_NO_PAD.encode(rsa_key.e().to_bytes_be());
+
+            return Ok(JwtKey {
+                kid: kid.to_string(),
+                algorithm: Algorithm::RS256,
+                encoding_key: EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|err| err.to_string())?,
+                decoding_key: DecodingKey::from_rsa_components(&n, &e).map_err(|err| err.to_string())?,
+                jwk: json!({ "kty": "RSA", "use": "sig", "alg": "RS256", "kid": kid, "n": n, "e": e }),
+            });
+        }
+
+        let ed_key = ed25519_dalek::SigningKey::from_pkcs8_pem(pem)
+            .map_err(|_| format!("Key '{}' is neither an RSA nor an Ed25519 private key", kid))?;
+        let x = URL_SAFE_NO_PAD.encode(ed_key.verifying_key().to_bytes());
+
+        Ok(JwtKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::EdDSA,
+            encoding_key: EncodingKey::from_ed_pem(pem.as_bytes()).map_err(|err| err.to_string())?,
+            decoding_key: DecodingKey::from_ed_components(&x).map_err(|err| err.to_string())?,
+            jwk: json!({ "kty": "OKP", "crv": "Ed25519", "use": "sig", "alg": "EdDSA", "kid": kid, "x": x }),
+        })
+    }
+}
+
+/// Keys used to sign and verify access tokens, the signing key first.
+pub struct KeySet {
+    keys: Vec<JwtKey>,
+}
+
+impl KeySet {
+    /// Creates a key set; the first key signs new tokens.
+    pub fn new(keys: Vec<JwtKey>) -> Self {
+        KeySet { keys }
+    }
+
+    /// Loads the keys listed in `JWT_SIGNING_KEYS`.
+    ///
+    /// # Panics
+    /// Panics if a key cannot be read or parsed, so misconfiguration fails at startup.
+    fn from_env() -> Self {
+        dotenv::dotenv().ok();
+        let Ok(config) = env::var("JWT_SIGNING_KEYS") else {
+            return KeySet::new(Vec::new());
+        };
+
+        let keys = config
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (kid, path) = entry
+                    .split_once(':')
+                    .expect("JWT_SIGNING_KEYS entries must look like kid:path");
+                let pem = fs::read_to_string(path).expect("Can't read the JWT signing key");
+                JwtKey::from_pem(kid, &pem).expect("Can't parse the JWT signing key")
+            })
+            .collect();
+
+        KeySet::new(keys)
+    }
+
+    /// Signs claims with the active key, or with HS256 and `SECRET` when no keys are configured.
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, jsonwebtoken::errors::Error> {
+        match self.keys.first() {
+            Some(key) => {
+                let header = Header {
+                    kid: Some(key.kid.clone()),
+                    ..Header::new(key.algorithm)
+                };
+                encode(&header, claims, &key.encoding_key)
+            }
+            None => encode(
+                &Header::default(),
+                claims,
+                &EncodingKey::from_secret(contants::SECRET.as_ref()),
+            ),
+        }
+    }
+
+    /// Verifies a token with the key named by its `kid` header.
+    pub fn decode<T: DeserializeOwned>(&self, jwt: &str) -> Result<TokenData<T>, jsonwebtoken::errors::Error> {
+        if self.keys.is_empty() {
+            return decode(
+                jwt,
+                &DecodingKey::from_secret(contants::SECRET.as_ref()),
+                &Validation::default(),
+            );
+        }
+
+        let kid = decode_header(jwt)?.kid;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| Some(&key.kid) == kid.as_ref())
+            .ok_or(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat)?;
+
+        decode(jwt, &key.decoding_key, &Validation::new(key.algorithm))
+    }
+
+    /// Returns the public keys as a JSON Web Key Set.
+    pub fn jwks(&self) -> Value {
+        json!({ "keys": self.keys.iter().map(|key| key.jwk.clone()).collect::<Vec<_>>() })
+    }
+}
@@ -0,0 +1,92 @@
+/// Timezone and locale preferences of users.
+///
+/// Timestamps are stored in UTC. `UserLocale` converts them to the user's timezone for API
+/// responses and emails, formats them in the user's language, and turns local times such as
+/// "next midnight" back into UTC for scheduling.
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+
+/// Format of dates in emails, e.g. `11 June 2025, 15:30 CEST`.
+const DATE_TIME_FORMAT: &str = "%-d %B %Y, %H:%M %Z";
+
+/// Parses an IANA timezone name such as `Europe/Berlin`.
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Parses a locale tag such as `de-DE` or `de_DE`.
+pub fn parse_locale(tag: &str) -> Option<chrono::Locale> {
+    chrono::Locale::try_from(tag.replace('-', "_").as_str()).ok()
+}
+
+/// The timezone and locale used to present times to a user.
+#[derive(Clone, Debug)]
+pub struct UserLocale {
+    timezone: Tz,
+    locale: chrono::Locale,
+    language: String,
+}
+
+impl Default for UserLocale {
+    /// UTC and American English.
+    fn default() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            locale: chrono::Locale::en_US,
+            language: "en".to_string(),
+        }
+    }
+}
+
+impl UserLocale {
+    /// Returns the preferences of a user, falling back to the defaults for unknown values.
+    pub fn of(user: &entity::user::Model) -> Self {
+        let timezone = parse_timezone(&user.timezone).unwrap_or(Tz::UTC);
+
+        match parse_locale(&user.locale) {
+            Some(locale) => Self {
+                timezone,
+                locale,
+                language: user.locale.split(['-', '_']).next().unwrap_or_default().to_lowercase(),
+            },
+            None => Self { timezone, ..Self::default() },
+        }
+    }
+
+    /// Returns the language part of the locale, e.g. `de` for `de-DE`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Converts a UTC timestamp to the user's timezone, keeping the offset for API responses.
+    pub fn timestamp(&self, utc: NaiveDateTime) -> DateTime<FixedOffset> {
+        self.timezone.from_utc_datetime(&utc).fixed_offset()
+    }
+
+    /// Formats a UTC timestamp in the user's timezone and language, e.g. `11 Juni 2025, 15:30 CEST`.
+    pub fn format(&self, utc: NaiveDateTime) -> String {
+        self.timezone
+            .from_utc_datetime(&utc)
+            .format_localized(DATE_TIME_FORMAT, self.locale)
+            .to_string()
+    }
+
+    /// Returns the user's wall clock time at the given UTC timestamp.
+    pub fn local(&self, utc: NaiveDateTime) -> NaiveDateTime {
+        self.timezone.from_utc_datetime(&utc).naive_local()
+    }
+
+    /// Converts a wall clock time of the user to UTC.
+    ///
+    /// Times repeated when clocks go back resolve to the first occurrence; times skipped when
+    /// clocks go forward are moved an hour later.
+    pub fn to_utc(&self, local: NaiveDateTime) -> NaiveDateTime {
+        match self.timezone.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.naive_utc(),
+            LocalResult::None => {
+                let later = self.timezone.from_local_datetime(&(local + chrono::Duration::hours(1)));
+                later.earliest().map_or(local, |time| time.naive_utc())
+            }
+        }
+    }
+}
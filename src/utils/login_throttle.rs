@@ -0,0 +1,192 @@
+/// Module for throttling failed logins.
+///
+/// Failures are counted per email address and per client IP. Each failure of an email address
+/// delays the next attempt exponentially, and reaching the limit locks the address or the IP
+/// out for `LOGIN_LOCKOUT_MINUTES`. Failures older than that are forgotten.
+use actix_web::HttpRequest;
+use chrono::{Duration, NaiveDateTime, Utc};
+use entity::login_throttle::ThrottleKind;
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+
+use super::contants;
+
+/// Longest back-off between two attempts for one email address, in seconds.
+const MAX_BACKOFF_SECONDS: i64 = 32;
+
+/// Returns the key counting the failed logins of an email address.
+pub fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Failed login counters of a login attempt.
+pub struct LoginThrottle {
+    account_key: String,
+    ip_key: String,
+    account: Option<entity::login_throttle::Model>,
+    ip: Option<entity::login_throttle::Model>,
+}
+
+impl LoginThrottle {
+    /// Loads the counters of an email address and a client IP.
+    pub async fn load<C: ConnectionTrait>(db: &C, email: &str, ip: &str) -> Result<Self, DbErr> {
+        let account_key = account_key(email);
+        let ip_key = ip.to_string();
+
+        let counters = entity::login_throttle::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(entity::login_throttle::Column::Kind.eq(ThrottleKind::Account))
+                            .add(entity::login_throttle::Column::Key.eq(&account_key)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(entity::login_throttle::Column::Kind.eq(ThrottleKind::Ip))
+                            .add(entity::login_throttle::Column::Key.eq(&ip_key)),
+                    ),
+            )
+            .all(db)
+            .await?;
+
+        let find = |kind: ThrottleKind| counters.iter().find(|counter| counter.kind == kind).cloned();
+
+        Ok(LoginThrottle {
+            account: find(ThrottleKind::Account),
+            ip: find(ThrottleKind::Ip),
+            account_key,
+            ip_key,
+        })
+    }
+
+    /// Returns until when login attempts are refused, if they currently are.
+    pub fn locked_until(&self) -> Option<NaiveDateTime> {
+        let now = Utc::now().naive_local();
+        [&self.account, &self.ip]
+            .into_iter()
+            .filter_map(|counter| counter.as_ref()?.locked_until)
+            .filter(|locked_until| *locked_until > now)
+            .max()
+    }
+
+    /// Records a failed login.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If this failure locked the email address out.
+    /// * `Ok(false)` - Otherwise.
+    /// * `Err(DbErr)` - If the counters cannot be stored.
+    pub async fn record_failure<C: ConnectionTrait>(&self, db: &C) -> Result<bool, DbErr> {
+        let account_locked = record(
+            db,
+            ThrottleKind::Account,
+            &self.account_key,
+            *contants::LOGIN_MAX_FAILED_ATTEMPTS,
+        )
+        .await?;
+
+        record(
+            db,
+            ThrottleKind::Ip,
+            &self.ip_key,
+            *contants::LOGIN_IP_MAX_FAILED_ATTEMPTS,
+        )
+        .await?;
+
+        Ok(account_locked)
+    }
+
+    /// Resets the counter of the email address after a successful login.
+    pub async fn record_success<C: ConnectionTrait>(&self, db: &C) -> Result<(), DbErr> {
+        if let Some(account) = &self.account {
+            entity::login_throttle::Entity::delete_by_id(account.id)
+                .exec(db)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Increments one counter and decides until when the key is throttled.
+/// Returns whether the key has just reached its limit.
+///
+/// The counter is incremented by the database in a single upsert, so concurrent failures
+/// each count and the first failure of a key cannot be inserted twice.
+async fn record<C: ConnectionTrait>(
+    db: &C,
+    kind: ThrottleKind,
+    key: &str,
+    max_failed_attempts: i32,
+) -> Result<bool, DbErr> {
+    let now = Utc::now().naive_local();
+    let window = Duration::minutes(*contants::LOGIN_LOCKOUT_MINUTES);
+
+    let counter = entity::login_throttle::Entity::insert(entity::login_throttle::ActiveModel {
+        kind: Set(kind),
+        key: Set(key.to_string()),
+        failed_count: Set(1),
+        last_failed_at: Set(now),
+        locked_until: Set(None),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::columns([
+            entity::login_throttle::Column::Kind,
+            entity::login_throttle::Column::Key,
+        ])
+        .value(
+            entity::login_throttle::Column::FailedCount,
+            // Failures older than the window are forgotten
+            Expr::case(
+                Expr::col((entity::login_throttle::Entity, entity::login_throttle::Column::LastFailedAt))
+                    .gt(now - window),
+                Expr::col((entity::login_throttle::Entity, entity::login_throttle::Column::FailedCount)).add(1),
+            )
+            .finally(1),
+        )
+        .value(entity::login_throttle::Column::LastFailedAt, now)
+        .to_owned(),
+    )
+    .exec_with_returning(db)
+    .await?;
+
+    let failed_count = counter.failed_count;
+
+    let locked_until = if failed_count >= max_failed_attempts {
+        Some(now + window)
+    } else if kind == ThrottleKind::Account {
+        // Back off 1, 2, 4, ... seconds; IPs are only locked out as they may be shared
+        let backoff = 1_i64 << (failed_count - 1).clamp(0, 5);
+        Some(now + Duration::seconds(backoff.min(MAX_BACKOFF_SECONDS)))
+    } else {
+        None
+    };
+
+    if locked_until != counter.locked_until {
+        // A failure counted after this one decides the lock instead
+        entity::login_throttle::Entity::update_many()
+            .col_expr(entity::login_throttle::Column::LockedUntil, Expr::value(locked_until))
+            .filter(entity::login_throttle::Column::Id.eq(counter.id))
+            .filter(entity::login_throttle::Column::FailedCount.eq(failed_count))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(failed_count == max_failed_attempts)
+}
+
+/// Returns the IP address of the client making the request.
+/// Proxy headers are only honored when `TRUST_PROXY_HEADERS` is enabled.
+pub fn client_ip(req: &HttpRequest) -> String {
+    if *contants::TRUST_PROXY_HEADERS {
+        if let Some(ip) = req.connection_info().realip_remote_addr() {
+            return ip.to_string();
+        }
+    }
+
+    req.peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or("unknown".to_string())
+}
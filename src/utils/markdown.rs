@@ -0,0 +1,116 @@
+/// Renders the Markdown source of articles to sanitized HTML and plain text.
+///
+/// Articles are written in CommonMark with tables and footnotes. The HTML is sanitized, so
+/// raw HTML in the source cannot inject scripts, styles or event handlers; the plain text is
+/// used wherever markup does not belong, such as email snippets.
+use lazy_static::lazy_static;
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+
+/// Prefix of footnote anchors, keeping them apart from the ids of the page around the article.
+const FOOTNOTE_PREFIX: &str = "fn-";
+
+lazy_static! {
+    /// Sanitizer keeping the markup Markdown produces, plus the ids footnote links point to
+    /// and the classes marking footnotes.
+    static ref SANITIZER: ammonia::Builder<'static> = {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .add_tag_attributes("div", ["id"])
+            .add_allowed_classes("div", ["footnote-definition"])
+            .add_allowed_classes("sup", ["footnote-reference", "footnote-definition-label"]);
+        builder
+    };
+}
+
+/// An article rendered from its Markdown source.
+pub struct RenderedContent {
+    /// Sanitized HTML.
+    pub html: String,
+    /// Plain text without markup.
+    pub text: String,
+}
+
+/// Parses Markdown with the supported extensions, prefixing footnote names.
+fn parse(markdown: &str) -> Vec<Event<'_>> {
+    Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES)
+        .map(|event| match event {
+            Event::FootnoteReference(name) => {
+                Event::FootnoteReference(format!("{}{}", FOOTNOTE_PREFIX, name).into())
+            }
+            Event::Start(Tag::FootnoteDefinition(name)) => {
+                Event::Start(Tag::FootnoteDefinition(format!("{}{}", FOOTNOTE_PREFIX, name).into()))
+            }
+            event => event,
+        })
+        .collect()
+}
+
+/// Renders Markdown to sanitized HTML and plain text.
+pub fn render(markdown: &str) -> RenderedContent {
+    let events = parse(markdown);
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.iter().cloned());
+
+    RenderedContent {
+        html: SANITIZER.clean(&unsafe_html).to_string(),
+        text: plain_text(&events),
+    }
+}
+
+/// Returns the rendered versions of an article, rendering articles stored before they existed.
+pub fn rendered(article: &entity::article::Model) -> RenderedContent {
+    match (&article.content_html, &article.content_text) {
+        (Some(html), Some(text)) => RenderedContent {
+            html: html.clone(),
+            text: text.clone(),
+        },
+        _ => render(&article.content),
+    }
+}
+
+/// Collects the text of parsed Markdown, keeping paragraphs and list items on their own lines.
+fn plain_text(events: &[Event]) -> String {
+    let mut text = String::new();
+
+    for event in events {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(content),
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak | Event::Rule => text.push('\n'),
+            Event::Start(Tag::Item) => text.push_str("- "),
+            Event::End(TagEnd::TableCell) => text.push('\t'),
+            Event::End(TagEnd::Item | TagEnd::TableHead | TagEnd::TableRow) => text.push('\n'),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::BlockQuote(_)
+                | TagEnd::List(_)
+                | TagEnd::Table
+                | TagEnd::FootnoteDefinition,
+            ) => text.push_str("\n\n"),
+            _ => {}
+        }
+    }
+
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Shortens plain text to at most `max_chars` characters, on one line.
+pub fn snippet(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].trim_end().to_string(),
+        None => text,
+    }
+}
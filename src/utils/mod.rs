@@ -9,5 +9,39 @@ pub mod api_response;
 pub mod app_state;
 /// Handles JSON Web Token (JWT) authentication and validation.
 pub mod jwt;
+/// Loads the keys signing access tokens and publishes their public parts.
+pub mod jwt_keys;
 /// Defines the custom error type `MainError` used for error handling in the application.
-pub mod main_error;
\ No newline at end of file
+pub mod main_error;
+/// Hashes and verifies user passwords with Argon2id.
+pub mod password;
+/// Manages refresh tokens and the access token revocation list.
+pub mod auth_tokens;
+/// Generates and verifies TOTP two-factor authentication codes.
+pub mod totp;
+/// Defines API key scopes and the scopes granted to a request.
+pub mod scopes;
+/// Maps user roles to the permissions they hold.
+pub mod roles;
+/// Configures OpenID Connect sign-in providers and talks to them.
+pub mod oauth;
+/// Throttles failed logins per account and client IP.
+pub mod login_throttle;
+/// Erases deleted accounts once their grace period is over.
+pub mod account_erasure;
+/// Builds archives of the personal data held about a user.
+pub mod data_export;
+/// Records security relevant events in the audit log.
+pub mod audit;
+/// Encodes and decodes cursors for keyset pagination.
+pub mod pagination;
+/// Delivers new articles to subscribers, right away or in digests.
+pub mod newsletter;
+/// Converts and formats times for the timezone and locale of a user.
+pub mod locale;
+/// Publishes drafts and scheduled articles.
+pub mod publishing;
+/// Renders the Markdown source of articles to HTML and plain text.
+pub mod markdown;
+/// Searches the full text of published articles.
+pub mod search;
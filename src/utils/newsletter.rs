@@ -0,0 +1,308 @@
+/// Delivers new articles to subscribers, right away or in daily and weekly digests.
+///
+/// Each subscription has a delivery mode; subscriptions without one use the subscriber's default:
+/// - `instant` - One email per article as soon as it is published.
+/// - `daily` - The article is queued and sent in a digest at the subscriber's next midnight.
+/// - `weekly` - The article is queued and sent in a digest at the start of the subscriber's next Monday.
+/// - `none` - No emails.
+///
+/// A background job sends the digests that are due, one email per subscriber. Instant emails
+/// that fail are queued as due digest items, so that the job retries them.
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Utc};
+use entity::{article::ArticleStatus, subscription::DeliveryMode};
+use sea_orm::{
+    sea_query::{Expr, Query},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use super::{account_erasure::DELETED_USER_NAME, contants, locale::UserLocale, markdown};
+use crate::email::email_service;
+
+/// How often the background job looks for digests that are due.
+const DIGEST_JOB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum length, in characters, of the article text quoted in instant emails.
+const SNIPPET_LENGTH: usize = 200;
+
+/// Returns when the next digest of the given mode is sent, or `None` for modes without digests.
+/// Digests go out at midnight in the subscriber's timezone; the returned time is in UTC.
+///
+/// # Arguments
+/// * `mode` - The delivery mode.
+/// * `now` - The current time (UTC).
+/// * `locale` - The subscriber's timezone and locale.
+pub fn next_digest(mode: DeliveryMode, now: NaiveDateTime, locale: &UserLocale) -> Option<NaiveDateTime> {
+    let local_now = locale.local(now);
+    let midnight = local_now.date().and_time(NaiveTime::MIN);
+
+    let next = match mode {
+        DeliveryMode::Daily => midnight + chrono::Duration::days(1),
+        DeliveryMode::Weekly => {
+            midnight + chrono::Duration::days(7 - i64::from(local_now.weekday().num_days_from_monday()))
+        }
+        DeliveryMode::Instant | DeliveryMode::None => return None,
+    };
+
+    Some(locale.to_utc(next))
+}
+
+/// Returns the public link of an article.
+fn article_link(article: &entity::article::Model) -> String {
+    format!("{}/article/get-by-uuid/{}", *contants::APP_BASE_URL, article.uuid)
+}
+
+/// Delivers a newly published article to the verified subscribers of its author.
+/// Instant subscribers are emailed right away; the article is queued for digest subscribers
+/// and for instant subscribers whose email could not be sent.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `article` - The published article.
+///
+/// # Errors
+/// Returns an error if the subscribers cannot be loaded or the article cannot be queued.
+pub async fn notify_subscribers<C: ConnectionTrait>(
+    db: &C,
+    article: &entity::article::Model,
+) -> Result<(), String> {
+    let subscribers = entity::subscription::Entity::find()
+        .filter(entity::subscription::Column::SubscribedUserId.eq(article.user_id))
+        .join_rev(
+            JoinType::InnerJoin,
+            entity::user::Entity::belongs_to(entity::subscription::Entity)
+                .from(entity::user::Column::Id)
+                .to(entity::subscription::Column::SubscriberUserId)
+                .into(),
+        )
+        .select_also(entity::user::Entity)
+        // Unverified accounts do not receive newsletters
+        .filter(entity::user::Column::EmailVerifiedAt.is_not_null())
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let now = Utc::now().naive_local();
+    let mut instant_subscribers = Vec::new();
+    let mut digest_items = Vec::new();
+
+    for (subscription, subscriber) in subscribers {
+        let Some(subscriber) = subscriber else {
+            continue;
+        };
+
+        let mode = subscription.delivery_mode.unwrap_or(subscriber.delivery_mode);
+        if mode == DeliveryMode::Instant {
+            instant_subscribers.push(subscriber);
+        } else if let Some(deliver_after) = next_digest(mode, now, &UserLocale::of(&subscriber)) {
+            digest_items.push(entity::digest_item::ActiveModel {
+                subscriber_user_id: Set(subscriber.id),
+                article_id: Set(article.id),
+                deliver_after: Set(deliver_after),
+                created_at: Set(now),
+                ..Default::default()
+            });
+        }
+    }
+
+    let article_link = article_link(article);
+    let snippet = markdown::snippet(&markdown::rendered(article).text, SNIPPET_LENGTH);
+
+    for subscriber in instant_subscribers {
+        let locale = UserLocale::of(&subscriber);
+        let unsubscribe_link = format!(
+            "{}/subscription/unsubscribe-user-from-email?user_id={}&subscriber_id={}",
+            *contants::APP_BASE_URL, article.user_id, subscriber.id
+        );
+
+        if let Err(err) = email_service::send_newsletter_email(
+            &subscriber.email,
+            &article.title,
+            &snippet,
+            &locale.format(article.published_at()),
+            &article_link,
+            &unsubscribe_link,
+            locale.language(),
+        )
+        .await
+        {
+            eprintln!("Newsletter email error for user {}: {}", subscriber.id, err);
+
+            // Retried by the digest job
+            digest_items.push(entity::digest_item::ActiveModel {
+                subscriber_user_id: Set(subscriber.id),
+                article_id: Set(article.id),
+                deliver_after: Set(now),
+                created_at: Set(now),
+                ..Default::default()
+            });
+        }
+    }
+
+    if !digest_items.is_empty() {
+        entity::digest_item::Entity::insert_many(digest_items)
+            .exec_without_returning(db)
+            .await
+            .map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Drops the queued articles of an author from a subscriber's digests, e.g. after unsubscribing.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `subscriber_id` - The subscriber whose digests are cleaned up.
+/// * `author_id` - The author whose articles are dropped.
+pub async fn unqueue_author<C: ConnectionTrait>(db: &C, subscriber_id: i32, author_id: i32) -> Result<(), DbErr> {
+    entity::digest_item::Entity::delete_many()
+        .filter(entity::digest_item::Column::SubscriberUserId.eq(subscriber_id))
+        .filter(Expr::col(entity::digest_item::Column::ArticleId).in_subquery(
+            Query::select()
+                .column(entity::article::Column::Id)
+                .from(entity::article::Entity)
+                .and_where(entity::article::Column::UserId.eq(author_id))
+                .to_owned(),
+        ))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Drops a deleted article from every digest it is queued for.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `article_id` - The deleted article.
+pub async fn unqueue_article<C: ConnectionTrait>(db: &C, article_id: i32) -> Result<(), DbErr> {
+    entity::digest_item::Entity::delete_many()
+        .filter(entity::digest_item::Column::ArticleId.eq(article_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Drops every queued digest item of a user, both as subscriber and as author.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `user_id` - The user whose items are dropped.
+pub async fn unqueue_user<C: ConnectionTrait>(db: &C, user_id: i32) -> Result<(), DbErr> {
+    entity::digest_item::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(entity::digest_item::Column::SubscriberUserId.eq(user_id))
+                .add(Expr::col(entity::digest_item::Column::ArticleId).in_subquery(
+                    Query::select()
+                        .column(entity::article::Column::Id)
+                        .from(entity::article::Entity)
+                        .and_where(entity::article::Column::UserId.eq(user_id))
+                        .to_owned(),
+                )),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Sends every digest that is due, one email per subscriber.
+/// Sent items are deleted; items whose email fails stay queued for the next run.
+pub async fn send_due_digests(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let due_items = entity::digest_item::Entity::find()
+        .filter(entity::digest_item::Column::DeliverAfter.lte(Utc::now().naive_local()))
+        .order_by_asc(entity::digest_item::Column::Id)
+        .find_also_related(entity::article::Entity)
+        .all(db)
+        .await?;
+
+    if due_items.is_empty() {
+        return Ok(());
+    }
+
+    let user_ids: Vec<i32> = due_items
+        .iter()
+        .flat_map(|(item, article)| {
+            std::iter::once(item.subscriber_user_id).chain(article.as_ref().map(|article| article.user_id))
+        })
+        .collect();
+
+    let users: HashMap<i32, entity::user::Model> = entity::user::Entity::find()
+        .filter(entity::user::Column::Id.is_in(user_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect();
+
+    let mut digests: BTreeMap<i32, Vec<(entity::digest_item::Model, Option<entity::article::Model>)>> = BTreeMap::new();
+    for (item, article) in due_items {
+        digests.entry(item.subscriber_user_id).or_default().push((item, article));
+    }
+
+    for (subscriber_id, items) in digests {
+        let subscriber = users
+            .get(&subscriber_id)
+            .filter(|subscriber| subscriber.email_verified_at.is_some() && subscriber.deleted_at.is_none());
+        let locale = subscriber.map(UserLocale::of).unwrap_or_default();
+
+        let entries: Vec<(String, String, String, String)> = items
+            .iter()
+            .filter_map(|(_, article)| article.as_ref())
+            .filter(|article| article.status == ArticleStatus::Published && article.deleted_at.is_none())
+            .map(|article| {
+                let author = users
+                    .get(&article.user_id)
+                    .map_or(DELETED_USER_NAME.to_string(), |author| author.name.clone());
+                (article.title.clone(), author, locale.format(article.published_at()), article_link(article))
+            })
+            .collect();
+
+        if let (Some(subscriber), false) = (subscriber, entries.is_empty()) {
+            let articles: Vec<(&str, &str, &str, &str)> = entries
+                .iter()
+                .map(|(title, author, published_at, link)| {
+                    (title.as_str(), author.as_str(), published_at.as_str(), link.as_str())
+                })
+                .collect();
+
+            if let Err(err) =
+                email_service::send_digest_email(&subscriber.email, &subscriber.name, &articles, locale.language()).await
+            {
+                eprintln!("Digest email error for user {}: {}", subscriber_id, err);
+                continue;
+            }
+        }
+
+        entity::digest_item::Entity::delete_many()
+            .filter(entity::digest_item::Column::Id.is_in(items.iter().map(|(item, _)| item.id)))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Starts the background job sending due digests every hour.
+pub fn spawn_digest_job(db: Arc<DatabaseConnection>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(DIGEST_JOB_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = send_due_digests(&db).await {
+                eprintln!("Digest job error: {}", err);
+            }
+        }
+    });
+}
@@ -0,0 +1,143 @@
+/// Module for OpenID Connect sign-in providers.
+///
+/// Providers are read from the environment on each request, so any OIDC issuer can be
+/// plugged in without code changes. A provider named `google` is configured with:
+/// - `OAUTH_GOOGLE_CLIENT_ID` (required, enables the provider)
+/// - `OAUTH_GOOGLE_CLIENT_SECRET` (optional for public clients)
+/// - `OAUTH_GOOGLE_AUTHORIZE_URL`, `OAUTH_GOOGLE_TOKEN_URL`, `OAUTH_GOOGLE_USERINFO_URL`
+/// - `OAUTH_GOOGLE_SCOPES` (defaults to `openid email profile`)
+use std::env;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::contants;
+
+/// Configuration of an OpenID Connect provider.
+#[derive(Clone, Debug)]
+pub struct OAuthProvider {
+    pub name: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub scopes: String,
+}
+
+/// Response of the provider's token endpoint.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// Standard claims returned by the provider's userinfo endpoint.
+#[derive(Deserialize)]
+pub struct UserInfo {
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+impl OAuthProvider {
+    /// Loads a provider from the environment.
+    ///
+    /// # Returns
+    /// * `Some(OAuthProvider)` - If the provider is fully configured.
+    /// * `None` - If the name is invalid or a required variable is missing.
+    pub fn from_env(name: &str) -> Option<Self> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+
+        dotenv::dotenv().ok();
+        let prefix = format!("OAUTH_{}_", name.to_ascii_uppercase());
+        let var = |key: &str| env::var(format!("{}{}", prefix, key)).ok().filter(|value| !value.is_empty());
+
+        Some(OAuthProvider {
+            name: name.to_string(),
+            client_id: var("CLIENT_ID")?,
+            client_secret: var("CLIENT_SECRET"),
+            authorize_url: var("AUTHORIZE_URL")?,
+            token_url: var("TOKEN_URL")?,
+            userinfo_url: var("USERINFO_URL")?,
+            scopes: var("SCOPES").unwrap_or("openid email profile".to_string()),
+        })
+    }
+
+    /// Returns the callback URL registered with the provider.
+    pub fn redirect_uri(&self) -> String {
+        format!("{}/auth/oauth/{}/callback", *contants::APP_BASE_URL, self.name)
+    }
+
+    /// Builds the URL the user is sent to for signing in at the provider.
+    pub fn authorization_url(&self, state: &str, code_challenge: &str) -> Result<String, String> {
+        let url = Url::parse_with_params(
+            &self.authorize_url,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri().as_str()),
+                ("scope", self.scopes.as_str()),
+                ("state", state),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+
+        Ok(url.to_string())
+    }
+
+    /// Exchanges an authorization code for the provider's access token.
+    pub async fn exchange_code(&self, code: &str, code_verifier: &str) -> Result<String, String> {
+        let redirect_uri = self.redirect_uri();
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", self.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(client_secret) = &self.client_secret {
+            form.push(("client_secret", client_secret.as_str()));
+        }
+
+        let token_response: TokenResponse = reqwest::Client::new()
+            .post(&self.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&form)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| format!("Token exchange failed: {}", err))?
+            .json()
+            .await
+            .map_err(|err| format!("Invalid token response: {}", err))?;
+
+        Ok(token_response.access_token)
+    }
+
+    /// Fetches the signed-in account from the provider's userinfo endpoint.
+    pub async fn fetch_userinfo(&self, access_token: &str) -> Result<UserInfo, String> {
+        reqwest::Client::new()
+            .get(&self.userinfo_url)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| format!("Userinfo request failed: {}", err))?
+            .json()
+            .await
+            .map_err(|err| format!("Invalid userinfo response: {}", err))
+    }
+}
+
+/// Derives the PKCE `S256` code challenge from a code verifier.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
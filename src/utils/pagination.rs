@@ -0,0 +1,54 @@
+/// Opaque cursors for keyset pagination.
+///
+/// A cursor names the sort order it belongs to and carries the sort keys of the last item
+/// of a page, e.g. the subscriber count and ID of an author. Clients pass it back unchanged
+/// to get the next page; a cursor of another sort order is rejected.
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use super::api_response::ApiResponse;
+
+/// Encodes the sort keys of the last item of a page.
+///
+/// # Arguments
+/// * `sort` - Name of the sort order, e.g. `newest`.
+/// * `keys` - The sort keys of the last item.
+pub fn encode_cursor(sort: &str, keys: &[i64]) -> String {
+    let keys: Vec<String> = keys.iter().map(i64::to_string).collect();
+    URL_SAFE_NO_PAD.encode(format!("{}:{}", sort, keys.join(":")))
+}
+
+/// Decodes a cursor of the given sort order.
+///
+/// # Returns
+/// * `Ok(Vec<i64>)` - The `count` sort keys of the cursor.
+/// * `Err(ApiResponse)` - A `400` error if the cursor is malformed or of another sort order.
+pub fn decode_cursor(cursor: &str, sort: &str, count: usize) -> Result<Vec<i64>, ApiResponse> {
+    let invalid = || ApiResponse::new(400, "Invalid cursor".to_owned());
+
+    let decoded = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(invalid)?;
+
+    let mut parts = decoded.split(':');
+    if parts.next() != Some(sort) {
+        return Err(invalid());
+    }
+
+    let keys = parts
+        .map(|part| part.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+
+    if keys.len() != count {
+        return Err(invalid());
+    }
+
+    Ok(keys)
+}
+
+/// Returns the requested page size, falling back to `default` and capped at `max`.
+pub fn page_limit(limit: Option<u64>, default: u64, max: u64) -> u64 {
+    limit.unwrap_or(default).clamp(1, max)
+}
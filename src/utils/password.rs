@@ -0,0 +1,110 @@
+/// Module for hashing and verifying user passwords.
+///
+/// New passwords are hashed with Argon2id and stored as PHC strings, which embed
+/// the per-user salt and cost parameters. Legacy unsalted SHA-256 digests are still
+/// accepted so existing accounts can be upgraded on their next successful login.
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+
+use lazy_static::lazy_static;
+
+use super::contants;
+
+lazy_static! {
+    /// Hash checked when no user exists, so unknown emails take as long as wrong passwords.
+    static ref DUMMY_PASSWORD_HASH: String = hash_password("dummy password").unwrap_or_default();
+}
+
+/// Result of checking a password against a stored hash.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordCheck {
+    /// The password matches and the stored hash is up to date.
+    Valid,
+    /// The password matches but the stored hash should be replaced
+    /// (legacy SHA-256 digest or outdated Argon2 parameters).
+    ValidNeedsRehash,
+    /// The password does not match.
+    Invalid,
+}
+
+/// Builds the Argon2id hasher using the configured cost parameters.
+fn argon2() -> Result<Argon2<'static>, String> {
+    let params = Params::new(
+        *contants::ARGON2_MEMORY_COST,
+        *contants::ARGON2_TIME_COST,
+        *contants::ARGON2_PARALLELISM,
+        None,
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+/// Hashes a password with Argon2id and a freshly generated salt.
+///
+/// # Arguments
+/// * `password` - The plain text password.
+///
+/// # Returns
+/// * `Ok(String)` - The PHC formatted hash.
+/// * `Err(String)` - If hashing fails.
+pub fn hash_password(password: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    argon2()?
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|err| err.to_string())
+}
+
+/// Verifies a password against a stored hash.
+///
+/// Stored values that are not PHC strings are treated as legacy SHA-256 digests.
+///
+/// # Arguments
+/// * `password` - The plain text password.
+/// * `stored_hash` - The value of the `user.password` column.
+///
+/// # Returns
+/// * `Ok(PasswordCheck)` - Whether the password matches and if it needs rehashing.
+/// * `Err(String)` - If the hasher cannot be configured.
+pub fn verify_password(password: &str, stored_hash: &str) -> Result<PasswordCheck, String> {
+    let parsed_hash = match PasswordHash::new(stored_hash) {
+        Ok(parsed_hash) => parsed_hash,
+        Err(_) => {
+            return Ok(if sha256::digest(password) == stored_hash {
+                PasswordCheck::ValidNeedsRehash
+            } else {
+                PasswordCheck::Invalid
+            });
+        }
+    };
+
+    let hasher = argon2()?;
+
+    if hasher.verify_password(password.as_bytes(), &parsed_hash).is_err() {
+        return Ok(PasswordCheck::Invalid);
+    }
+
+    // Rehash when the algorithm or cost parameters differ from the current configuration
+    let is_current = parsed_hash.algorithm == Algorithm::Argon2id.ident()
+        && Params::try_from(&parsed_hash).is_ok_and(|params| {
+            params.m_cost() == hasher.params().m_cost()
+                && params.t_cost() == hasher.params().t_cost()
+                && params.p_cost() == hasher.params().p_cost()
+        });
+
+    Ok(if is_current {
+        PasswordCheck::Valid
+    } else {
+        PasswordCheck::ValidNeedsRehash
+    })
+}
+
+/// Verifies a password against a throwaway hash and discards the result.
+///
+/// Used when the user does not exist, so response times do not reveal registered emails.
+pub fn verify_dummy_password(password: &str) {
+    let _ = verify_password(password, &DUMMY_PASSWORD_HASH);
+}
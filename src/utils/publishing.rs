@@ -0,0 +1,93 @@
+/// Publishes drafts and scheduled articles, notifying subscribers exactly once.
+///
+/// Articles only move from `draft` or `scheduled` to `published` through `publish`, which
+/// claims the article with a conditional update. Only the caller that wins the claim notifies
+/// the subscribers, so an author publishing by hand and the background job publishing a due
+/// article at the same moment cannot both send it. Published articles never go back to
+/// `draft` or `scheduled`.
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use entity::article::ArticleStatus;
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+
+use super::newsletter;
+
+/// How often the background job looks for scheduled articles that are due.
+const PUBLISHING_JOB_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Publishes a draft or scheduled article now.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `article` - The article to publish.
+///
+/// # Returns
+/// The published article, or `None` if it was already published, or deleted, in the meantime.
+pub async fn publish<C: ConnectionTrait>(
+    db: &C,
+    article: &entity::article::Model,
+) -> Result<Option<entity::article::Model>, DbErr> {
+    let now = Utc::now().naive_local();
+
+    let result = entity::article::Entity::update_many()
+        .col_expr(entity::article::Column::Status, Expr::value(ArticleStatus::Published))
+        .col_expr(entity::article::Column::PublishAt, Expr::value(now))
+        .filter(entity::article::Column::Id.eq(article.id))
+        .filter(entity::article::Column::Status.is_in([ArticleStatus::Draft, ArticleStatus::Scheduled]))
+        .filter(entity::article::Column::DeletedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(entity::article::Model {
+        status: ArticleStatus::Published,
+        publish_at: Some(now),
+        ..article.clone()
+    }))
+}
+
+/// Publishes every scheduled article that is due and notifies its subscribers.
+/// Notification errors are logged; the article stays published.
+pub async fn publish_due_articles(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let due_articles = entity::article::Entity::find()
+        .filter(entity::article::Column::Status.eq(ArticleStatus::Scheduled))
+        .filter(entity::article::Column::PublishAt.lte(Utc::now().naive_local()))
+        .filter(entity::article::Column::DeletedAt.is_null())
+        .order_by_asc(entity::article::Column::PublishAt)
+        .all(db)
+        .await?;
+
+    for article in due_articles {
+        let Some(published) = publish(db, &article).await? else {
+            continue;
+        };
+
+        if let Err(err) = newsletter::notify_subscribers(db, &published).await {
+            eprintln!("Newsletter error for article {}: {}", published.id, err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Starts the background job publishing due articles every minute.
+pub fn spawn_publishing_job(db: Arc<DatabaseConnection>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(PUBLISHING_JOB_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = publish_due_articles(&db).await {
+                eprintln!("Publishing job error: {}", err);
+            }
+        }
+    });
+}
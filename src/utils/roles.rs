@@ -0,0 +1,35 @@
+/// Module for role-based access control.
+///
+/// Routes require a `Permission` rather than a role, so new roles only have to be
+/// added to the mapping below.
+use std::fmt::Display;
+
+use entity::user::UserRole;
+
+/// Action that only some roles may perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// List users and change their roles.
+    ManageUsers,
+    /// Publish articles.
+    PublishArticles,
+}
+
+impl Permission {
+    /// Checks whether the given role holds this permission.
+    pub fn is_granted_to(&self, role: UserRole) -> bool {
+        match self {
+            Permission::ManageUsers => role == UserRole::Admin,
+            Permission::PublishArticles => matches!(role, UserRole::Admin | UserRole::Author),
+        }
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::ManageUsers => write!(f, "manage users"),
+            Permission::PublishArticles => write!(f, "publish articles"),
+        }
+    }
+}
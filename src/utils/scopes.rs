@@ -0,0 +1,131 @@
+/// Module for API key scopes.
+///
+/// Requests authenticated with a JWT may do everything the user can do, while requests
+/// authenticated with an API key are limited to the scopes granted to that key.
+/// The auth middleware stores the `GrantedScopes` of a request next to its `Claims`.
+use std::{fmt::Display, future, str::FromStr};
+
+use actix_web::{FromRequest, HttpMessage};
+
+use super::api_response::ApiResponse;
+
+/// Permission that can be granted to an API key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    /// Read the user's own articles.
+    ArticleRead,
+    /// Create articles.
+    ArticleWrite,
+    /// Read the user's profile.
+    ProfileRead,
+    /// Read the users the account is subscribed to.
+    SubscriptionsRead,
+    /// Subscribe to and unsubscribe from users.
+    SubscriptionsWrite,
+    /// Read the account's subscribers.
+    SubscribersRead,
+}
+
+impl Scope {
+    /// All scopes that can be granted.
+    pub const ALL: [Scope; 6] = [
+        Scope::ArticleRead,
+        Scope::ArticleWrite,
+        Scope::ProfileRead,
+        Scope::SubscriptionsRead,
+        Scope::SubscriptionsWrite,
+        Scope::SubscribersRead,
+    ];
+
+    /// Returns the textual name of the scope, e.g. `article:write`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ArticleRead => "article:read",
+            Scope::ArticleWrite => "article:write",
+            Scope::ProfileRead => "profile:read",
+            Scope::SubscriptionsRead => "subscriptions:read",
+            Scope::SubscriptionsWrite => "subscriptions:write",
+            Scope::SubscribersRead => "subscribers:read",
+        }
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == value)
+            .ok_or(format!("Unknown scope '{}'", value))
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Parses a space separated list of scopes, as stored on an API key.
+pub fn parse_scopes(scopes: &str) -> Result<Vec<Scope>, String> {
+    scopes.split_whitespace().map(Scope::from_str).collect()
+}
+
+/// Permissions of the current request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrantedScopes {
+    /// Authenticated with a JWT: no scope restrictions.
+    Session,
+    /// Authenticated with an API key carrying these scopes.
+    ApiKey(Vec<Scope>),
+}
+
+impl GrantedScopes {
+    /// Checks that the request is allowed to use the given scope.
+    ///
+    /// # Errors
+    /// Returns a `403` response if an API key lacks the scope.
+    pub fn require(&self, scope: Scope) -> Result<(), ApiResponse> {
+        match self {
+            GrantedScopes::Session => Ok(()),
+            GrantedScopes::ApiKey(scopes) if scopes.contains(&scope) => Ok(()),
+            GrantedScopes::ApiKey(_) => Err(ApiResponse::new(
+                403,
+                format!("API key is missing the '{}' scope", scope),
+            )),
+        }
+    }
+
+    /// Checks that the request was authenticated with a JWT rather than an API key.
+    ///
+    /// # Errors
+    /// Returns a `403` response for API keys.
+    pub fn require_session(&self) -> Result<(), ApiResponse> {
+        match self {
+            GrantedScopes::Session => Ok(()),
+            GrantedScopes::ApiKey(_) => Err(ApiResponse::new(
+                403,
+                "This endpoint cannot be used with an API key".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Implements extraction of granted scopes from an Actix request.
+impl FromRequest for GrantedScopes {
+    type Error = actix_web::Error;
+
+    type Future = future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(
+        req: &actix_web::HttpRequest,
+        _payload: &mut actix_web::dev::Payload,
+    ) -> std::future::Ready<Result<GrantedScopes, actix_web::Error>> {
+
+        match req.extensions().get::<GrantedScopes>() {
+            Some(scopes) => future::ready(Ok(scopes.clone())),
+            None => future::ready(Err(actix_web::error::ErrorBadRequest("Bad Scopes"))),
+        }
+    }
+}
@@ -0,0 +1,338 @@
+/// Full-text search over published articles.
+///
+/// On PostgreSQL, articles are matched against the generated `search_vector` column, where the
+/// title weighs more than the text, using `websearch_to_tsquery`; hits are ranked with `ts_rank`
+/// and quoted with `ts_headline`. Other backends, such as the mock databases in the tests, fall
+/// back to matching word prefixes in Rust. Both return hits ordered by relevance with the matches
+/// highlighted by `<mark>`, and the same cursors.
+use std::{collections::HashMap, ops::Range};
+
+use entity::article::ArticleStatus;
+use sea_orm::{
+    sea_query::{Expr, Func, SimpleExpr},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseBackend, EntityTrait, FromQueryResult, Order,
+    QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+use super::{
+    api_response::ApiResponse,
+    markdown,
+    pagination::{decode_cursor, encode_cursor},
+};
+use crate::email::email_service::escape_html;
+
+/// Text search configuration of the `search_vector` column, see the `article_search` migration.
+const SEARCH_CONFIG: &str = "english";
+
+/// Name of the relevance order, as used in cursors.
+const RELEVANCE: &str = "relevance";
+
+/// Marks the start of a match in snippets until they are escaped.
+const MATCH_START: char = '\u{E000}';
+
+/// Marks the end of a match in snippets until they are escaped.
+const MATCH_END: char = '\u{E001}';
+
+/// Weight of matches in the title, as given to `ts_rank` by default for weight `A`.
+const TITLE_WEIGHT: f32 = 1.0;
+
+/// Weight of matches in the text, as given to `ts_rank` by default for weight `B`.
+const TEXT_WEIGHT: f32 = 0.4;
+
+/// Words quoted before the first match in snippets of the fallback.
+const SNIPPET_WORDS_BEFORE: usize = 10;
+
+/// Words quoted in total in snippets of the fallback.
+const SNIPPET_WORDS: usize = 35;
+
+/// An article matching a search.
+pub struct SearchHit {
+    pub article: entity::article::Model,
+    /// Relevance; higher is better.
+    pub rank: f32,
+    /// Part of the text around the matches, as HTML with the matches in `<mark>`.
+    pub snippet: String,
+}
+
+/// One page of search hits, most relevant first.
+pub struct SearchPage {
+    pub hits: Vec<SearchHit>,
+    /// Cursor of the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+/// Row of the ranking query on PostgreSQL.
+#[derive(FromQueryResult)]
+struct RankedArticle {
+    id: i32,
+    rank: f32,
+    snippet: String,
+}
+
+/// Searches the published articles, optionally of one author.
+///
+/// # Arguments
+/// * `db` - The database connection.
+/// * `q` - The search terms; on PostgreSQL also quoted phrases, `or` and `-` for exclusions.
+/// * `author_id` - Only articles of this author.
+/// * `cursor` - The `next_cursor` of the previous page.
+/// * `limit` - The page size.
+///
+/// # Errors
+/// * Returns a `400` error if the search has no words or the cursor is invalid.
+/// * Returns a `500` error if the database query fails.
+pub async fn search_articles<C: ConnectionTrait>(
+    db: &C,
+    q: &str,
+    author_id: Option<i32>,
+    cursor: Option<&str>,
+    limit: u64,
+) -> Result<SearchPage, ApiResponse> {
+    let terms = terms(q);
+    if terms.is_empty() {
+        return Err(ApiResponse::new(400, "Search query is empty".to_owned()));
+    }
+
+    let after = cursor.map(decode_search_cursor).transpose()?;
+
+    let mut articles = entity::article::Entity::find()
+        .filter(entity::article::Column::Status.eq(ArticleStatus::Published))
+        .filter(entity::article::Column::DeletedAt.is_null());
+    if let Some(author_id) = author_id {
+        articles = articles.filter(entity::article::Column::UserId.eq(author_id));
+    }
+
+    // One extra hit tells whether there is a next page
+    let mut hits = match db.get_database_backend() {
+        DatabaseBackend::Postgres => postgres_hits(db, articles, q, after, limit + 1).await?,
+        _ => fallback_hits(db, articles, &terms, after, limit + 1).await?,
+    };
+
+    let next_cursor = if hits.len() as u64 > limit {
+        hits.truncate(limit as usize);
+        hits.last().map(|hit| {
+            encode_cursor(RELEVANCE, &[hit.rank.to_bits().into(), hit.article.id.into()])
+        })
+    } else {
+        None
+    };
+
+    Ok(SearchPage { hits, next_cursor })
+}
+
+/// Returns the lowercase words of a search.
+fn terms(q: &str) -> Vec<String> {
+    q.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Decodes the rank and ID of the last hit of the previous page.
+fn decode_search_cursor(cursor: &str) -> Result<(f32, i32), ApiResponse> {
+    let keys = decode_cursor(cursor, RELEVANCE, 2)?;
+
+    match (u32::try_from(keys[0]), i32::try_from(keys[1])) {
+        (Ok(rank), Ok(id)) => Ok((f32::from_bits(rank), id)),
+        _ => Err(ApiResponse::new(400, "Invalid cursor".to_owned())),
+    }
+}
+
+/// Ranks and quotes the matching articles in the database.
+async fn postgres_hits<C: ConnectionTrait>(
+    db: &C,
+    articles: Select<entity::article::Entity>,
+    q: &str,
+    after: Option<(f32, i32)>,
+    limit: u64,
+) -> Result<Vec<SearchHit>, ApiResponse> {
+    let tsquery = format!("websearch_to_tsquery('{}', $1)", SEARCH_CONFIG);
+    let matches = Expr::cust_with_values(format!(r#""article"."search_vector" @@ {}"#, tsquery), [q]);
+    let rank: SimpleExpr =
+        Expr::cust_with_values(format!(r#"ts_rank("article"."search_vector", {})"#, tsquery), [q]);
+    let snippet = Expr::cust_with_values(
+        format!(
+            r#"ts_headline('{}', COALESCE("article"."content_text", "article"."content"), {}, $2)"#,
+            SEARCH_CONFIG, tsquery
+        ),
+        [q.to_owned(), format!("StartSel={}, StopSel={}, MaxFragments=2", MATCH_START, MATCH_END)],
+    );
+
+    let mut ranked = articles
+        .select_only()
+        .column(entity::article::Column::Id)
+        .column_as(rank.clone(), "rank")
+        .column_as(snippet, "snippet")
+        .filter(matches);
+
+    if let Some((after_rank, after_id)) = after {
+        ranked = ranked.filter(
+            Condition::any()
+                .add(Expr::expr(rank.clone()).lt(after_rank))
+                .add(
+                    Condition::all()
+                        .add(Expr::expr(rank.clone()).eq(after_rank))
+                        .add(entity::article::Column::Id.lt(after_id)),
+                ),
+        );
+    }
+
+    let ranked = ranked
+        .order_by(rank, Order::Desc)
+        .order_by_desc(entity::article::Column::Id)
+        .limit(limit)
+        .into_model::<RankedArticle>()
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?;
+
+    let mut articles: HashMap<i32, entity::article::Model> = entity::article::Entity::find()
+        .filter(entity::article::Column::Id.is_in(ranked.iter().map(|row| row.id)))
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .map(|article| (article.id, article))
+        .collect();
+
+    Ok(ranked
+        .into_iter()
+        .filter_map(|row| {
+            articles.remove(&row.id).map(|article| SearchHit {
+                article,
+                rank: row.rank,
+                snippet: highlight(&row.snippet),
+            })
+        })
+        .collect())
+}
+
+/// Loads the articles containing every term and ranks and quotes them in Rust.
+async fn fallback_hits<C: ConnectionTrait>(
+    db: &C,
+    mut articles: Select<entity::article::Entity>,
+    terms: &[String],
+    after: Option<(f32, i32)>,
+    limit: u64,
+) -> Result<Vec<SearchHit>, ApiResponse> {
+    // Narrows the articles down in the database; whole words are checked below
+    for term in terms {
+        let pattern = format!("%{}%", term);
+        articles = articles.filter(
+            Condition::any()
+                .add(Expr::expr(Func::lower(Expr::col(entity::article::Column::Title))).like(pattern.clone()))
+                .add(Expr::expr(Func::lower(Expr::col(entity::article::Column::Content))).like(pattern)),
+        );
+    }
+
+    let mut hits: Vec<SearchHit> = articles
+        .all(db)
+        .await
+        .map_err(|err| ApiResponse::new(500, err.to_string()))?
+        .into_iter()
+        .filter_map(|article| fallback_hit(article, terms))
+        .filter(|hit| match after {
+            Some((rank, id)) => hit.rank < rank || (hit.rank == rank && hit.article.id < id),
+            None => true,
+        })
+        .collect();
+
+    hits.sort_by(|a, b| b.rank.total_cmp(&a.rank).then(b.article.id.cmp(&a.article.id)));
+    hits.truncate(limit as usize);
+
+    Ok(hits)
+}
+
+/// Ranks an article by the words starting with a term, or returns `None` if a term is missing.
+fn fallback_hit(article: entity::article::Model, terms: &[String]) -> Option<SearchHit> {
+    let text = markdown::rendered(&article).text;
+    let title_words = words(&article.title);
+    let text_words = words(&text);
+    let is_match = |source: &str, word: &Range<usize>| {
+        let word = source[word.clone()].to_lowercase();
+        terms.iter().any(|term| word.starts_with(term.as_str()))
+    };
+
+    let mut rank = 0.0;
+    for term in terms {
+        let count = |source: &str, words: &[Range<usize>]| {
+            words
+                .iter()
+                .filter(|word| source[(*word).clone()].to_lowercase().starts_with(term.as_str()))
+                .count() as f32
+        };
+        let in_title = count(&article.title, &title_words);
+        let in_text = count(&text, &text_words);
+
+        if in_title + in_text == 0.0 {
+            return None;
+        }
+        rank += TITLE_WEIGHT * in_title + TEXT_WEIGHT * in_text;
+    }
+
+    // Quotes the text from a little before the first match
+    let first_match = text_words.iter().position(|word| is_match(&text, word)).unwrap_or(0);
+    let start = first_match.saturating_sub(SNIPPET_WORDS_BEFORE);
+    let end = (start + SNIPPET_WORDS).min(text_words.len());
+
+    let mut snippet = String::new();
+    let mut position = match start {
+        0 => 0,
+        _ => text_words[start].start,
+    };
+    for word in &text_words[start..end] {
+        snippet.push_str(&text[position..word.start]);
+        if is_match(&text, word) {
+            snippet.push(MATCH_START);
+            snippet.push_str(&text[word.clone()]);
+            snippet.push(MATCH_END);
+        } else {
+            snippet.push_str(&text[word.clone()]);
+        }
+        position = word.end;
+    }
+
+    if start > 0 {
+        snippet.insert_str(0, "… ");
+    }
+    if end < text_words.len() {
+        snippet.push_str(" …");
+    } else {
+        snippet.push_str(&text[position..]);
+    }
+
+    Some(SearchHit {
+        article,
+        rank,
+        snippet: highlight(&snippet),
+    })
+}
+
+/// Returns the byte ranges of the words of a text.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(word_start)) => {
+                words.push(word_start..index);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(word_start..text.len());
+    }
+
+    words
+}
+
+/// Escapes a snippet and turns the match markers into `<mark>` elements, on one line.
+fn highlight(snippet: &str) -> String {
+    escape_html(&snippet.split_whitespace().collect::<Vec<_>>().join(" "))
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
@@ -0,0 +1,82 @@
+/// Module for TOTP two-factor authentication helpers.
+///
+/// Provides secret generation, `otpauth://` URI building, code verification
+/// and recovery code generation.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use super::contants;
+
+/// Number of recovery codes issued when two-factor authentication is enabled.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generates a new random TOTP secret, Base32 encoded.
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// Builds the TOTP generator for a secret and account.
+///
+/// # Arguments
+/// * `secret` - The Base32 encoded secret.
+/// * `account_name` - The account label shown in authenticator apps (the user's email).
+///
+/// # Returns
+/// * `Ok(TOTP)` - The configured generator (SHA-1, 6 digits, 30 second steps).
+/// * `Err(String)` - If the secret is invalid.
+pub fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, String> {
+    let secret_bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| format!("{:?}", err))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret_bytes,
+        Some((*contants::TOTP_ISSUER).clone()),
+        account_name.to_string(),
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Checks a TOTP code, allowing one step of clock skew.
+///
+/// # Returns
+/// * `Ok(Some(i64))` - The time step the code belongs to, so that it can be refused when replayed.
+/// * `Ok(None)` - If the code is wrong.
+/// * `Err(String)` - If the secret is invalid or the clock is before 1970.
+pub fn verify_code(secret: &str, account_name: &str, code: &str) -> Result<Option<i64>, String> {
+    let mut totp = build_totp(secret, account_name)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_secs();
+
+    let current_step = now / totp.step;
+    let skew = u64::from(totp.skew);
+
+    // Checks the steps one by one to learn which of them the code belongs to
+    totp.skew = 0;
+    Ok((current_step.saturating_sub(skew)..=current_step + skew)
+        .find(|step| totp.check(code.trim(), step * totp.step))
+        .map(|step| step as i64))
+}
+
+/// Generates a fresh set of recovery codes in the form `xxxxx-xxxxx`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let random = Uuid::new_v4().simple().to_string();
+            format!("{}-{}", &random[..5], &random[5..10])
+        })
+        .collect()
+}
+
+/// Normalizes a recovery code typed by a user before hashing it.
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.trim().to_lowercase().replace('-', "")
+}
@@ -0,0 +1,8 @@
+//! Module declarations for `well_known` components.
+//! This module exposes handlers and route configurations.
+
+/// Module for `/.well-known` request handlers.
+pub mod well_known_handlers;
+
+/// Module for `/.well-known` route configurations.
+pub mod well_known_routes;
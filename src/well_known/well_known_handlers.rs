@@ -0,0 +1,17 @@
+/// Handlers for `/.well-known` endpoints.
+/// Publishes the public keys other services use to verify our access tokens.
+use actix_web::{get, http::header::CACHE_CONTROL, HttpResponse};
+
+use crate::utils::jwt_keys::KEY_SET;
+
+/// Endpoint returning the JSON Web Key Set of the access token signing keys.
+/// 
+/// # Response:
+/// Returns a `200 OK` status with the public keys, including keys kept for verification
+/// during a rotation. The set is empty when tokens are signed with the shared secret.
+#[get("/jwks.json")]
+pub async fn jwks() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((CACHE_CONTROL, "public, max-age=300"))
+        .json(KEY_SET.jwks())
+}
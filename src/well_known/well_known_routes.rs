@@ -0,0 +1,18 @@
+/// Routes configuration for `/.well-known` endpoints.
+/// This module registers the JSON Web Key Set endpoint.
+use actix_web::web;
+use super::well_known_handlers;
+
+/// Configures `/.well-known` routes.
+/// 
+/// # Routes:
+/// - `/.well-known/jwks.json`: Public keys verifying access tokens.
+/// 
+/// # Arguments:
+/// * `config` - Mutable reference to Actix Web's `ServiceConfig`.
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/.well-known")
+            .service(well_known_handlers::jwks)
+    );
+}